@@ -0,0 +1,149 @@
+use crate::models::FeedItem;
+use crossterm::cursor::MoveTo;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::execute;
+use crossterm::terminal::{self, Clear, ClearType};
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+/// Position within a list of items, clamped to its bounds
+#[derive(Debug, Clone)]
+pub struct Pager {
+    len: usize,
+    index: usize,
+}
+
+impl Pager {
+    pub fn new(len: usize) -> Self {
+        Self { len, index: 0 }
+    }
+
+    /// Index of the current item, None when there are no items
+    pub fn current(&self) -> Option<usize> {
+        (self.len > 0).then_some(self.index)
+    }
+
+    /// Move to the next item; false if already on the last one
+    pub fn forward(&mut self) -> bool {
+        if self.index + 1 < self.len {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Move to the previous item; false if already on the first one
+    pub fn back(&mut self) -> bool {
+        if self.index > 0 {
+            self.index -= 1;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Browse items interactively, or print them as a plain list when stdin or
+/// stdout is not a terminal
+pub fn browse(items: &[FeedItem]) -> Result<(), String> {
+    if items.is_empty() {
+        println!("No items.");
+        return Ok(());
+    }
+
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        print_plain(items);
+        return Ok(());
+    }
+
+    terminal::enable_raw_mode().map_err(|e| format!("Failed to enter raw mode: {}", e))?;
+    let result = run_interactive(items);
+    let _ = terminal::disable_raw_mode();
+    println!();
+
+    result
+}
+
+fn print_plain(items: &[FeedItem]) {
+    for item in items {
+        println!("{}", item.title);
+        println!("  {} · {}", item.source, item.timestamp.as_deref().unwrap_or("no timestamp"));
+        println!("  {}", item.url);
+        println!();
+    }
+}
+
+fn run_interactive(items: &[FeedItem]) -> Result<(), String> {
+    let mut pager = Pager::new(items.len());
+    let mut stdout = io::stdout();
+    let mut status = String::new();
+    let io_err = |e: io::Error| format!("Terminal error: {}", e);
+
+    while let Some(index) = pager.current() {
+        let item = &items[index];
+
+        // Raw mode needs explicit carriage returns
+        execute!(stdout, Clear(ClearType::All), MoveTo(0, 0)).map_err(io_err)?;
+        write!(
+            stdout,
+            "[{}/{}] {}\r\n\r\n  Source:    {}\r\n  Published: {}\r\n  Reading:   {}\r\n  URL:       {}\r\n\r\n{}\r\n\r\nn/→ next  p/← prev  o/Enter open  q quit",
+            index + 1,
+            items.len(),
+            item.title,
+            item.source,
+            item.timestamp.as_deref().unwrap_or("-"),
+            item.reading_time_minutes.map_or("-".to_string(), |m| format!("~{} min", m)),
+            item.url,
+            status,
+        ).map_err(io_err)?;
+        stdout.flush().map_err(io_err)?;
+
+        let Event::Key(key) = event::read().map_err(io_err)? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        status = match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break,
+            KeyCode::Char('q') | KeyCode::Esc => break,
+            KeyCode::Char('n') | KeyCode::Char('j') | KeyCode::Char(' ') | KeyCode::Right | KeyCode::Down => {
+                if pager.forward() { String::new() } else { "(last item)".to_string() }
+            }
+            KeyCode::Char('p') | KeyCode::Char('k') | KeyCode::Left | KeyCode::Up => {
+                if pager.back() { String::new() } else { "(first item)".to_string() }
+            }
+            KeyCode::Char('o') | KeyCode::Enter => match open_url(&item.url) {
+                Ok(()) => format!("Opened {}", item.url),
+                Err(e) => e,
+            },
+            _ => String::new(),
+        };
+    }
+
+    Ok(())
+}
+
+fn open_url(url: &str) -> Result<(), String> {
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err(format!("Not opening non-HTTP URL: {}", url));
+    }
+
+    let program = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(windows) {
+        "explorer"
+    } else {
+        "xdg-open"
+    };
+
+    Command::new(program)
+        .arg(url)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open URL with {}: {}", program, e))
+}
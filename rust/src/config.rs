@@ -1,8 +1,10 @@
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
+use std::net::{IpAddr, Ipv4Addr, ToSocketAddrs};
 use std::path::Path;
-use url::Url;
+use url::{Host, Url};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -16,14 +18,248 @@ pub struct Config {
 pub struct Settings {
     #[serde(default = "default_max_concurrency")]
     pub max_concurrency: usize,
+    /// Fetches allowed against a single host at once (keyed on the feed URL's
+    /// host); unset means only `max_concurrency` applies
+    #[serde(default)]
+    pub max_concurrency_per_host: Option<usize>,
+    /// http(s) proxy URL for every feed request; a feed's own `proxy`
+    /// overrides it
+    #[serde(default)]
+    pub proxy: Option<String>,
+    /// Hosts (and their subdomains) fetched directly, bypassing `proxy`
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
     #[serde(default = "default_timeout_secs")]
     pub default_timeout_secs: u64,
+    /// Largest response body read, per request (each page of a paginated
+    /// feed counts separately); longer responses fail the fetch unparsed
+    #[serde(default = "default_max_response_bytes")]
+    pub max_response_bytes: u64,
+    /// Redirects followed per request; one more fails the fetch with
+    /// "too many redirects" (0 rejects any redirect)
+    #[serde(default = "default_max_redirects")]
+    pub max_redirects: usize,
+    /// Limit on a whole fetch run; feeds still running when it passes are
+    /// aborted and recorded as "deadline exceeded"
+    #[serde(default)]
+    pub run_deadline_secs: Option<u64>,
     #[serde(default = "default_retry_max")]
     pub retry_max: usize,
     #[serde(default = "default_retry_base_delay_ms")]
     pub retry_base_delay_ms: u64,
+    /// How the delay grows between retries
+    #[serde(default)]
+    pub backoff_strategy: BackoffStrategy,
+    /// Ceiling on each computed backoff delay (before jitter)
+    #[serde(default = "default_retry_max_delay_ms")]
+    pub retry_max_delay_ms: u64,
+    /// HTTP statuses that are retried; any other error status fails the
+    /// feed at once (network errors and timeouts are always retried). Unset
+    /// retries 429 and every status that isn't a 4xx
+    #[serde(default)]
+    pub retry_on_status: Option<Vec<u16>>,
+    /// After this many consecutive failed fetches (from fetch_log), a feed
+    /// gets one probe attempt per run without retries until it succeeds
+    #[serde(default)]
+    pub circuit_break_threshold: Option<usize>,
+    /// Longest `Retry-After` wait honored on 429/503 responses; longer
+    /// requests are cut to this
+    #[serde(default = "default_max_retry_after_secs")]
+    pub max_retry_after_secs: u64,
+    /// Add a random 0..=N ms to each retry delay so feeds on the same host
+    /// don't retry in lockstep (reproducible with `--seed`)
+    #[serde(default)]
+    pub retry_jitter_ms: u64,
+    /// Add a random 0..=N% of the capped backoff delay to each retry
+    #[serde(default)]
+    pub retry_jitter_pct: u64,
     #[serde(default = "default_database_path")]
     pub database_path: String,
+    /// Delete stored items older than this many days on `prune` (feeds can
+    /// override with their own `retention_days`); unset keeps everything
+    #[serde(default)]
+    pub retention_days: Option<u64>,
+    /// Record every fetch in fetch_log; when false, reports can only show
+    /// stored item counts (no errors or last-success times)
+    #[serde(default = "default_log_fetches")]
+    pub log_fetches: bool,
+    /// Apply retention automatically after every fetch is stored
+    #[serde(default)]
+    pub prune_on_store: bool,
+    /// Warn in reports when a feed's TLS certificate expires within this many days
+    #[serde(default = "default_cert_expiry_warn_days")]
+    pub cert_expiry_warn_days: u64,
+    /// Warn when a source returns a byte-identical body this many fetches in a row
+    #[serde(default = "default_stale_body_threshold")]
+    pub stale_body_threshold: usize,
+    /// User-Agent sent with every request unless the pool or a feed's own
+    /// `User-Agent` header supplies one
+    #[serde(default = "default_user_agent")]
+    pub user_agent: String,
+    /// User-Agent strings rotated per request, for compatibility with sources
+    /// that reject unfamiliar clients (not for evading blocks)
+    #[serde(default)]
+    pub user_agent_pool: Vec<String>,
+    /// Reject unknown feed_type values (default); when false, such feeds are
+    /// skipped with a warning so newer configs still run on older binaries
+    #[serde(default = "default_strict_feed_type")]
+    pub strict_feed_type: bool,
+    /// Reject responses whose Content-Type doesn't match the feed_type
+    #[serde(default)]
+    pub strict_content_type: bool,
+    /// Response headers (e.g. `X-RateLimit-Remaining`) recorded in fetch_log
+    #[serde(default)]
+    pub capture_headers: Vec<String>,
+    /// Regexes; parsed items whose URL matches any of them are not stored
+    #[serde(default)]
+    pub url_denylist: Vec<String>,
+    /// Which copy to keep when a response repeats an item (same source + URL)
+    #[serde(default)]
+    pub duplicate_keep: DuplicateKeep,
+    /// Prefix tags with their kind, e.g. `sub:programming`, `flair:Discussion`
+    #[serde(default)]
+    pub namespaced_tags: bool,
+    /// Trim titles and collapse internal runs of whitespace before storage
+    #[serde(default = "default_collapse_whitespace")]
+    pub collapse_whitespace: bool,
+    /// Strip `tracking_params` and the fragment from item URLs at parse
+    /// time; item ids derive from the URL, so enabling this re-keys items
+    #[serde(default)]
+    pub normalize_urls: bool,
+    /// Query parameters removed by `normalize_urls`; a trailing `*` matches
+    /// any parameter with that prefix
+    #[serde(default = "default_tracking_params")]
+    pub tracking_params: Vec<String>,
+    /// Shell command run after each fetch, with the run summary in
+    /// `FEEDPULSE_*` environment variables and new item URLs on stdin
+    #[serde(default)]
+    pub post_fetch_hook: Option<String>,
+    /// Permit running `post_fetch_hook`; off by default so a shared config
+    /// can't execute commands unless the user opts in
+    #[serde(default)]
+    pub allow_exec: bool,
+    /// Reject feeds (and mirrors) whose host is, or resolves to, a loopback,
+    /// link-local or private (RFC 1918 / IPv6 unique local) address, for
+    /// deployments where untrusted users supply feed URLs
+    #[serde(default)]
+    pub block_private_addresses: bool,
+    /// YAML file of `NAME: value` pairs substituted into `${secret.NAME}`
+    /// references (relative paths resolve against the config file)
+    #[serde(default)]
+    pub secrets_file: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackoffStrategy {
+    /// `base * 2^(n-1)`
+    #[default]
+    Exponential,
+    /// `base * n`
+    Linear,
+    /// `base`
+    Constant,
+}
+
+impl BackoffStrategy {
+    /// Delay before retry `attempt` (1-based)
+    pub fn delay_ms(self, base_ms: u64, attempt: u32) -> u64 {
+        let attempt = attempt.max(1);
+        match self {
+            BackoffStrategy::Exponential => {
+                base_ms.saturating_mul(2_u64.saturating_pow(attempt - 1))
+            }
+            BackoffStrategy::Linear => base_ms.saturating_mul(attempt as u64),
+            BackoffStrategy::Constant => base_ms,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicateKeep {
+    /// Keep the first occurrence in the response
+    #[default]
+    First,
+    /// Keep the last occurrence in the response
+    Last,
+}
+
+/// Credentials sent in a feed's `Authorization` header
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "RawFeedAuth", into = "RawFeedAuth")]
+pub enum FeedAuth {
+    /// `Authorization: Bearer <token>`
+    Bearer(String),
+    /// `Authorization: Basic base64(username:password)`
+    Basic { username: String, password: String },
+}
+
+/// On-disk shape of `auth`: a map with exactly one of `bearer` or `basic`.
+/// serde_yaml only accepts tagged enums from a `Value`, and the config is
+/// deserialized from one, so the enum goes through this struct instead.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawFeedAuth {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    bearer: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    basic: Option<BasicCredentials>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BasicCredentials {
+    username: String,
+    password: String,
+}
+
+impl TryFrom<RawFeedAuth> for FeedAuth {
+    type Error = String;
+
+    fn try_from(raw: RawFeedAuth) -> Result<Self, Self::Error> {
+        match (raw.bearer, raw.basic) {
+            (Some(token), None) => Ok(FeedAuth::Bearer(token)),
+            (None, Some(BasicCredentials { username, password })) => Ok(FeedAuth::Basic { username, password }),
+            _ => Err("auth must set exactly one of 'bearer' or 'basic'".to_string()),
+        }
+    }
+}
+
+impl From<FeedAuth> for RawFeedAuth {
+    fn from(auth: FeedAuth) -> Self {
+        match auth {
+            FeedAuth::Bearer(token) => RawFeedAuth { bearer: Some(token), basic: None },
+            FeedAuth::Basic { username, password } => RawFeedAuth {
+                bearer: None,
+                basic: Some(BasicCredentials { username, password }),
+            },
+        }
+    }
+}
+
+/// Built-in parser for a JSON feed, set explicitly with a feed's `parser`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JsonParser {
+    Hackernews,
+    Github,
+    Reddit,
+    Lobsters,
+    Generic,
+}
+
+impl JsonParser {
+    /// Parser kind as listed in `parser::JSON_PARSERS`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            JsonParser::Hackernews => "hackernews",
+            JsonParser::Github => "github",
+            JsonParser::Reddit => "reddit",
+            JsonParser::Lobsters => "lobsters",
+            JsonParser::Generic => "generic",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,27 +267,234 @@ pub struct Feed {
     pub name: String,
     pub url: String,
     pub feed_type: String,
+    /// Disabled feeds stay in the config but are not fetched
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
     #[serde(default = "default_refresh_interval")]
     pub refresh_interval_secs: u64,
     #[serde(default)]
     pub headers: HashMap<String, String>,
+    /// Bearer token or basic-auth credentials; an explicit `Authorization`
+    /// entry in `headers` takes precedence
+    #[serde(default)]
+    pub auth: Option<FeedAuth>,
+    /// Proxy URL for this feed, overriding `settings.proxy`
+    #[serde(default)]
+    pub proxy: Option<String>,
+    /// Parser for a JSON feed; when unset it is inferred from the feed name
+    /// (e.g. a name containing "Reddit" selects the Reddit parser)
+    #[serde(default)]
+    pub parser: Option<JsonParser>,
+    /// Field holding the item timestamp for generic JSON feeds; a path like
+    /// `title_path` (also accepted as `timestamp_path`)
+    #[serde(default, alias = "timestamp_path")]
+    pub timestamp_field: Option<String>,
+    /// Path within each item to its title for the generic JSON parser, e.g.
+    /// `$.attributes.title` or `names[0]`
+    #[serde(default)]
+    pub title_path: Option<String>,
+    /// Path within each item to its URL for the generic JSON parser
+    #[serde(default)]
+    pub url_path: Option<String>,
+    /// Overrides settings.retry_max for this feed
+    #[serde(default)]
+    pub retry_max: Option<usize>,
+    /// Overrides settings.default_timeout_secs for this feed's requests
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// Union stored and incoming tags on update instead of replacing them
+    #[serde(default)]
+    pub merge_tags: bool,
+    /// Dotted path that must exist in the JSON response for the fetch to count
+    #[serde(default)]
+    pub expect_key: Option<String>,
+    /// Keep at most this many items per fetch (XML feeds stop reading early)
+    #[serde(default)]
+    pub max_items_per_fetch: Option<usize>,
+    /// A successful fetch with fewer items than this is recorded as
+    /// `degraded` (e.g. a truncated response) instead of `success`
+    #[serde(default)]
+    pub min_expected_items: Option<usize>,
+    /// Trust feed_type regardless of the server's Content-Type header (for
+    /// feeds that serve e.g. JSON as `text/plain`) when strict_content_type is on
+    #[serde(default)]
+    pub skip_content_type_check: bool,
+    /// Former names of this feed; data stored under them is reported under
+    /// the current name
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Alternate URLs serving the same feed, tried in order when `url` fails
+    #[serde(default)]
+    pub mirror_urls: Vec<String>,
+    /// Override settings.retention_days for this source
+    #[serde(default)]
+    pub retention_days: Option<u64>,
+    /// Follow `Link: <...>; rel="next"` response headers (or `next_page_path`)
+    /// for up to this many pages per fetch
+    #[serde(default = "default_max_pages")]
+    pub max_pages: usize,
+    /// Path (as for `items_path`) to the next page's URL in a JSON response
+    /// body, for APIs that don't send a `Link` header
+    #[serde(default)]
+    pub next_page_path: Option<String>,
+    /// Page limit for a source's first fetch (no fetch history yet), to
+    /// backfill its archive before switching to `max_pages`
+    #[serde(default)]
+    pub first_run_max_pages: Option<usize>,
+    /// Delete this source's stored items that are missing from the latest
+    /// successful fetch, so storage mirrors the feed's current state
+    #[serde(default)]
+    pub mirror_latest: bool,
+    /// Path to the item array for JSON parsers: dotted (`data.children`) or
+    /// a JSON Pointer (`/data/children`). GitHub defaults to `items`, Reddit
+    /// to `data.children`; empty for a top-level array
+    #[serde(default)]
+    pub items_path: Option<String>,
+    /// `url` as written in the config file, when it referenced `${VAR}` or
+    /// `${secret.NAME}`; `redacted` shows it instead of the resolved value
+    #[serde(skip)]
+    pub(crate) url_template: Option<String>,
+    /// Likewise for header values
+    #[serde(skip)]
+    pub(crate) header_templates: HashMap<String, String>,
+}
+
+impl Feed {
+    /// A feed with every optional setting at its config-file default, for
+    /// building a `Config` in code
+    pub fn new(name: impl Into<String>, url: impl Into<String>, feed_type: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            url: url.into(),
+            feed_type: feed_type.into(),
+            enabled: default_enabled(),
+            refresh_interval_secs: default_refresh_interval(),
+            headers: HashMap::new(),
+            auth: None,
+            proxy: None,
+            parser: None,
+            timestamp_field: None,
+            title_path: None,
+            url_path: None,
+            retry_max: None,
+            timeout_secs: None,
+            merge_tags: false,
+            expect_key: None,
+            max_items_per_fetch: None,
+            min_expected_items: None,
+            skip_content_type_check: false,
+            aliases: Vec::new(),
+            mirror_urls: Vec::new(),
+            retention_days: None,
+            max_pages: default_max_pages(),
+            next_page_path: None,
+            first_run_max_pages: None,
+            mirror_latest: false,
+            items_path: None,
+            url_template: None,
+            header_templates: HashMap::new(),
+        }
+    }
 }
 
+/// Placeholder shown in place of secret values
+pub const REDACTED: &str = "***REDACTED***";
+
+/// Headers whose values are shown when displaying a config; any other
+/// header may carry a credential and is redacted
+const PLAIN_HEADERS: &[&str] = &["accept", "accept-encoding", "accept-language", "cache-control", "content-type", "user-agent"];
+
+/// Query parameter name fragments treated as secrets when displaying a URL
+const SECRET_PARAM_HINTS: &[&str] = &["auth", "key", "password", "secret", "session", "sig", "token"];
+
+/// feed_type values this build knows how to parse
+pub const FEED_TYPES: &[&str] = &["json", "rss", "atom"];
+
+/// Upper bound accepted for retry_max (config or `--retries`)
+pub const MAX_RETRY_MAX: usize = 10;
+
 fn default_max_concurrency() -> usize { 5 }
 fn default_timeout_secs() -> u64 { 10 }
+fn default_max_redirects() -> usize { 10 }
+fn default_max_response_bytes() -> u64 { 10 * 1024 * 1024 }
 fn default_retry_max() -> usize { 3 }
 fn default_retry_base_delay_ms() -> u64 { 500 }
+fn default_max_retry_after_secs() -> u64 { 60 }
+fn default_retry_max_delay_ms() -> u64 { 30_000 }
 fn default_database_path() -> String { "feedpulse.db".to_string() }
 fn default_refresh_interval() -> u64 { 300 }
+fn default_enabled() -> bool { true }
+fn default_max_pages() -> usize { 1 }
+fn default_cert_expiry_warn_days() -> u64 { 14 }
+fn default_stale_body_threshold() -> usize { 5 }
+fn default_strict_feed_type() -> bool { true }
+fn default_collapse_whitespace() -> bool { true }
+pub(crate) fn default_tracking_params() -> Vec<String> {
+    ["utm_*", "fbclid", "gclid", "mc_cid", "mc_eid", "ref_src"]
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+fn default_log_fetches() -> bool { true }
+fn default_user_agent() -> String { format!("feedpulse/{}", env!("CARGO_PKG_VERSION")) }
+
+impl Settings {
+    /// Backoff delay before retry `attempt` (1-based), clamped to
+    /// `retry_max_delay_ms`
+    pub fn retry_delay_ms(&self, attempt: u32) -> u64 {
+        self.backoff_strategy
+            .delay_ms(self.retry_base_delay_ms, attempt)
+            .min(self.retry_max_delay_ms)
+    }
+
+    /// Upper bound of the random jitter added to a retry delay of `delay_ms`
+    pub fn retry_jitter_max_ms(&self, delay_ms: u64) -> u64 {
+        let pct = delay_ms.saturating_mul(self.retry_jitter_pct) / 100;
+        self.retry_jitter_ms.saturating_add(pct)
+    }
+}
 
 impl Default for Settings {
     fn default() -> Self {
         Self {
             max_concurrency: default_max_concurrency(),
+            max_concurrency_per_host: None,
+            run_deadline_secs: None,
+            proxy: None,
+            no_proxy: Vec::new(),
             default_timeout_secs: default_timeout_secs(),
+            max_redirects: default_max_redirects(),
+            max_response_bytes: default_max_response_bytes(),
             retry_max: default_retry_max(),
             retry_base_delay_ms: default_retry_base_delay_ms(),
+            backoff_strategy: BackoffStrategy::default(),
+            retry_max_delay_ms: default_retry_max_delay_ms(),
+            max_retry_after_secs: default_max_retry_after_secs(),
+            retry_on_status: None,
+            circuit_break_threshold: None,
+            retry_jitter_ms: 0,
+            retry_jitter_pct: 0,
             database_path: default_database_path(),
+            retention_days: None,
+            log_fetches: default_log_fetches(),
+            prune_on_store: false,
+            cert_expiry_warn_days: default_cert_expiry_warn_days(),
+            stale_body_threshold: default_stale_body_threshold(),
+            user_agent: default_user_agent(),
+            user_agent_pool: Vec::new(),
+            strict_feed_type: default_strict_feed_type(),
+            strict_content_type: false,
+            capture_headers: Vec::new(),
+            url_denylist: Vec::new(),
+            duplicate_keep: DuplicateKeep::default(),
+            collapse_whitespace: default_collapse_whitespace(),
+            normalize_urls: false,
+            tracking_params: default_tracking_params(),
+            namespaced_tags: false,
+            post_fetch_hook: None,
+            allow_exec: false,
+            block_private_addresses: false,
+            secrets_file: None,
         }
     }
 }
@@ -61,58 +504,476 @@ impl Config {
         let content = fs::read_to_string(&path)
             .map_err(|e| format!("Failed to read config file: {}", e))?;
         
-        let config: Config = serde_yaml::from_str(&content)
+        let mut raw: serde_yaml::Value = serde_yaml::from_str(&content)
             .map_err(|e| format!("invalid config: {}", e))?;
-        
+
+        let secrets_file = raw.get("settings")
+            .and_then(|s| s.get("secrets_file"))
+            .and_then(|f| f.as_str())
+            .map(|f| path.as_ref().parent().unwrap_or(Path::new("")).join(f));
+
+        // Deserialize before substituting so error messages can never quote
+        // a secret value
+        let mut config: Config = serde_yaml::from_value(raw.clone())
+            .map_err(|e| format!("invalid config: {}", e))?;
+        let templates: Vec<(String, HashMap<String, String>)> = config.feeds.iter()
+            .map(|feed| (feed.url.clone(), feed.headers.clone()))
+            .collect();
+
+        if let Some(secrets_file) = secrets_file {
+            let secrets = load_secrets(&secrets_file)?;
+            substitute_secrets(&mut raw, &secrets)?;
+            config = serde_yaml::from_value(raw)
+                .map_err(|_| "invalid config after secret substitution".to_string())?;
+        }
+
+        for feed in &mut config.feeds {
+            feed.url = interpolate_env(&feed.url, &feed.name)?;
+            for value in feed.headers.values_mut() {
+                *value = interpolate_env(value, &feed.name)?;
+            }
+            match &mut feed.auth {
+                Some(FeedAuth::Bearer(token)) => *token = interpolate_env(token, &feed.name)?,
+                Some(FeedAuth::Basic { username, password }) => {
+                    *username = interpolate_env(username, &feed.name)?;
+                    *password = interpolate_env(password, &feed.name)?;
+                }
+                None => {}
+            }
+        }
+
+        for (feed, (url, headers)) in config.feeds.iter_mut().zip(templates) {
+            if feed.url != url {
+                feed.url_template = Some(url);
+            }
+            feed.header_templates = headers.into_iter()
+                .filter(|(name, template)| feed.headers.get(name) != Some(template))
+                .collect();
+        }
+
         Ok(config)
     }
 
+    /// Copy of the config with secrets masked: auth, header values (other
+    /// than `PLAIN_HEADERS`), URL passwords and secret-looking query
+    /// parameters. Values resolved from `${VAR}` or `${secret.NAME}` are
+    /// shown as written in the config file
+    pub fn redacted(&self) -> Config {
+        let mut config = self.clone();
+
+        for feed in &mut config.feeds {
+            for (name, value) in feed.headers.iter_mut() {
+                *value = if PLAIN_HEADERS.contains(&name.to_lowercase().as_str()) {
+                    feed.header_templates.get(name).unwrap_or(value).clone()
+                } else {
+                    REDACTED.to_string()
+                };
+            }
+
+            match &mut feed.auth {
+                Some(FeedAuth::Bearer(token)) => *token = REDACTED.to_string(),
+                Some(FeedAuth::Basic { password, .. }) => *password = REDACTED.to_string(),
+                None => {}
+            }
+
+            feed.url = redact_url(feed.url_template.as_deref().unwrap_or(&feed.url));
+            feed.mirror_urls = feed.mirror_urls.iter().map(|url| redact_url(url)).collect();
+            feed.proxy = feed.proxy.as_deref().map(redact_url);
+        }
+
+        config.settings.proxy = config.settings.proxy.as_deref().map(redact_url);
+
+        config
+    }
+
+    /// Render the effective config (defaults filled in, secrets redacted)
+    /// as `yaml` or `json`
+    pub fn render_resolved(&self, format: &str) -> Result<String, String> {
+        let redacted = self.redacted();
+
+        match format {
+            "yaml" => serde_yaml::to_string(&redacted)
+                .map_err(|e| format!("Failed to render config: {}", e)),
+            "json" => serde_json::to_string_pretty(&redacted)
+                .map_err(|e| format!("Failed to render config: {}", e)),
+            _ => Err(format!("Unknown format: {}", format)),
+        }
+    }
+
+    /// Remove feeds whose feed_type this build doesn't support, returning them
+    pub fn remove_unknown_feed_types(&mut self) -> Vec<Feed> {
+        let (known, unknown) = std::mem::take(&mut self.feeds)
+            .into_iter()
+            .partition(|feed| FEED_TYPES.contains(&feed.feed_type.as_str()));
+        self.feeds = known;
+        unknown
+    }
+
+    /// Use `secs` as the request timeout for every feed, including those
+    /// with their own `timeout_secs` (the `fetch --timeout` override)
+    pub fn override_timeout(&mut self, secs: u64) {
+        self.settings.default_timeout_secs = secs;
+        for feed in &mut self.feeds {
+            feed.timeout_secs = None;
+        }
+    }
+
+    /// Check settings and every feed, reporting all problems at once
     pub fn validate(&self) -> Result<(), String> {
+        let mut errors = Vec::new();
+
         // Validate settings
         if self.settings.max_concurrency < 1 || self.settings.max_concurrency > 50 {
-            return Err(format!(
+            errors.push(format!(
                 "max_concurrency must be between 1-50, got {}",
                 self.settings.max_concurrency
             ));
         }
 
+        if self.settings.max_concurrency_per_host == Some(0) {
+            errors.push("max_concurrency_per_host must be positive".to_string());
+        }
+
+        if let Some(status) = self.settings.retry_on_status.iter().flatten().find(|s| !(100..=599).contains(*s)) {
+            errors.push(format!("retry_on_status: {} is not an HTTP status code", status));
+        }
+
+        if self.settings.circuit_break_threshold == Some(0) {
+            errors.push("circuit_break_threshold must be positive".to_string());
+        }
+
+        if self.settings.circuit_break_threshold.is_some() && !self.settings.log_fetches {
+            errors.push("circuit_break_threshold needs the fetch log; it can't be used with log_fetches: false".to_string());
+        }
+
+        if self.settings.max_response_bytes == 0 {
+            errors.push("max_response_bytes must be positive".to_string());
+        }
+
+        if self.settings.run_deadline_secs == Some(0) {
+            errors.push("run_deadline_secs must be positive".to_string());
+        }
+
         if self.settings.default_timeout_secs == 0 {
-            return Err("default_timeout_secs must be positive".to_string());
+            errors.push("default_timeout_secs must be positive".to_string());
+        }
+
+        if let Some(Err(e)) = self.settings.proxy.as_deref().map(validate_proxy) {
+            errors.push(e);
+        }
+
+        if let Err(e) = validate_retry_max(self.settings.retry_max) {
+            errors.push(e);
+        }
+
+        if self.settings.retry_max_delay_ms == 0 {
+            errors.push("retry_max_delay_ms must be positive".to_string());
+        }
+
+        if self.settings.retry_jitter_pct > 100 {
+            errors.push(format!(
+                "retry_jitter_pct must be between 0-100, got {}",
+                self.settings.retry_jitter_pct
+            ));
+        }
+
+        if self.settings.user_agent.trim().is_empty() {
+            errors.push("user_agent cannot be empty".to_string());
+        } else if reqwest::header::HeaderValue::from_str(&self.settings.user_agent).is_err() {
+            errors.push(format!("user_agent is not a valid header value: '{}'", self.settings.user_agent));
+        }
+
+        for pattern in &self.settings.url_denylist {
+            if let Err(e) = Regex::new(pattern) {
+                errors.push(format!("url_denylist: invalid pattern '{}': {}", pattern, e));
+            }
         }
 
         // Validate feeds
         for feed in &self.feeds {
+            let mut feed_error = |message: String| errors.push(format!("feed '{}': {}", feed.name, message));
+
             // Name validation
             if feed.name.trim().is_empty() {
-                return Err(format!("feed '{}': name cannot be empty", feed.name));
+                feed_error("name cannot be empty".to_string());
             }
 
             // URL validation
             if feed.url.trim().is_empty() {
-                return Err(format!("feed '{}': missing field 'url'", feed.name));
+                feed_error("missing field 'url'".to_string());
+            } else {
+                for url in std::iter::once(&feed.url).chain(&feed.mirror_urls) {
+                    if Url::parse(url).is_err() {
+                        feed_error(format!("invalid URL '{}'", url));
+                    } else if self.settings.block_private_addresses {
+                        if let Err(e) = check_public_host(url, true) {
+                            feed_error(e);
+                        }
+                    }
+                }
             }
 
-            Url::parse(&feed.url).map_err(|_| {
-                format!("feed '{}': invalid URL '{}'", feed.name, feed.url)
-            })?;
+            if let Some(Err(e)) = feed.proxy.as_deref().map(validate_proxy) {
+                feed_error(e);
+            }
+
+            if feed.title_path.is_some() || feed.url_path.is_some() {
+                let parser = feed.parser
+                    .map_or_else(|| crate::parser::Parser::infer_json_parser(&feed.name), JsonParser::as_str);
+                if feed.feed_type != "json" {
+                    feed_error("title_path and url_path only apply to json feeds".to_string());
+                } else if parser != "generic" {
+                    feed_error(format!(
+                        "title_path and url_path only apply to the generic parser (this feed uses {})",
+                        parser
+                    ));
+                }
+            }
+
+            if feed.parser.is_some() && feed.feed_type != "json" {
+                feed_error("parser only applies to json feeds".to_string());
+            }
+
+            if feed.feed_type != "json" {
+                let json_only = [
+                    ("expect_key", &feed.expect_key),
+                    ("items_path", &feed.items_path),
+                    ("next_page_path", &feed.next_page_path),
+                ];
+                for (option, _) in json_only.iter().filter(|(_, value)| value.is_some()) {
+                    feed_error(format!("{} only applies to json feeds", option));
+                }
+            }
+
+            if let Some(alias) = feed.aliases.iter().find(|alias| self.feeds.iter().any(|f| &f.name == *alias)) {
+                feed_error(format!("alias '{}' is also the name of a configured feed", alias));
+            }
 
             // feed_type validation
-            if !["json", "rss", "atom"].contains(&feed.feed_type.as_str()) {
-                return Err(format!(
-                    "feed '{}': feed_type must be one of: json, rss, atom (got '{}')",
-                    feed.name, feed.feed_type
+            if self.settings.strict_feed_type && !FEED_TYPES.contains(&feed.feed_type.as_str()) {
+                feed_error(format!(
+                    "feed_type must be one of: json, rss, atom (got '{}')",
+                    feed.feed_type
                 ));
             }
 
             // refresh_interval validation
             if feed.refresh_interval_secs == 0 {
-                return Err(format!(
-                    "feed '{}': refresh_interval_secs must be positive",
-                    feed.name
-                ));
+                feed_error("refresh_interval_secs must be positive".to_string());
+            }
+
+            if feed.timeout_secs == Some(0) {
+                feed_error("timeout_secs must be positive".to_string());
             }
+
+            if feed.max_pages == 0 || feed.first_run_max_pages == Some(0) {
+                feed_error("max_pages and first_run_max_pages must be positive".to_string());
+            }
+
+            if feed.max_items_per_fetch == Some(0) {
+                feed_error("max_items_per_fetch must be positive".to_string());
+            }
+
+            if feed.min_expected_items == Some(0) {
+                feed_error("min_expected_items must be positive".to_string());
+            }
+
+            if let Some(Err(e)) = feed.retry_max.map(validate_retry_max) {
+                feed_error(e);
+            }
+        }
+
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.remove(0)),
+            n => Err(format!("{} config errors:\n  - {}", n, errors.join("\n  - "))),
+        }
+    }
+}
+
+/// Replace `${NAME}` references with environment variables (`${secret.…}`
+/// references belong to the secrets file and are left alone)
+fn interpolate_env(value: &str, feed_name: &str) -> Result<String, String> {
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find("${") {
+        let after = &rest[start + 2..];
+        let name = after.find('}').map(|end| &after[..end]).filter(|name| {
+            name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        });
+        let Some(name) = name else {
+            result.push_str(&rest[..start + 2]);
+            rest = after;
+            continue;
+        };
+
+        let resolved = std::env::var(name)
+            .map_err(|_| format!("feed '{}': undefined env var ${{{}}}", feed_name, name))?;
+        result.push_str(&rest[..start]);
+        result.push_str(&resolved);
+        rest = &after[name.len() + 1..];
+    }
+
+    result.push_str(rest);
+    Ok(result)
+}
+
+/// Read a secrets file, warning if other users can read it
+fn load_secrets(path: &Path) -> Result<HashMap<String, String>, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read secrets file {}: {}", path.display(), e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Ok(metadata) = fs::metadata(path) {
+            if metadata.permissions().mode() & 0o004 != 0 {
+                tracing::warn!(
+                    "secrets file {} is world-readable (consider chmod 600)",
+                    path.display()
+                );
+            }
+        }
+    }
+
+    // Don't echo serde's message, which can quote the offending value
+    serde_yaml::from_str(&content)
+        .map_err(|_| format!("invalid secrets file {}: expected NAME: value pairs", path.display()))
+}
+
+/// Replace `${secret.NAME}` references in every string value
+fn substitute_secrets(value: &mut serde_yaml::Value, secrets: &HashMap<String, String>) -> Result<(), String> {
+    match value {
+        serde_yaml::Value::String(s) => {
+            let mut result = String::with_capacity(s.len());
+            let mut rest = s.as_str();
+
+            while let Some(start) = rest.find("${secret.") {
+                result.push_str(&rest[..start]);
+                let after = &rest[start + "${secret.".len()..];
+                let end = after.find('}')
+                    .ok_or_else(|| "unterminated ${secret.…} reference in config".to_string())?;
+                let name = &after[..end];
+                let secret = secrets.get(name)
+                    .ok_or_else(|| format!("unknown secret '{}' referenced in config", name))?;
+                result.push_str(secret);
+                rest = &after[end + 1..];
+            }
+
+            result.push_str(rest);
+            *s = result;
         }
+        serde_yaml::Value::Sequence(items) => {
+            for item in items {
+                substitute_secrets(item, secrets)?;
+            }
+        }
+        serde_yaml::Value::Mapping(map) => {
+            for (_, item) in map.iter_mut() {
+                substitute_secrets(item, secrets)?;
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
 
-        Ok(())
+/// Check that `proxy` is an http or https URL reqwest can use
+pub fn validate_proxy(proxy: &str) -> Result<(), String> {
+    match Url::parse(proxy) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") && url.has_host() => Ok(()),
+        Ok(url) => Err(format!("proxy must be an http or https URL, got '{}://...'", url.scheme())),
+        Err(e) => Err(format!("invalid proxy URL: {}", e)),
+    }
+}
+
+/// Reject `url` when its host is a loopback, link-local or private address.
+/// IP literals and `localhost` are checked directly; with `resolve`, a host
+/// name is looked up and rejected if any address it resolves to is private.
+/// Names that don't resolve pass, since the fetch will fail on its own
+pub fn check_public_host(url: &str, resolve: bool) -> Result<(), String> {
+    let parsed = Url::parse(url).map_err(|e| format!("invalid URL '{}': {}", url, e))?;
+    let blocked = |ip: IpAddr| {
+        Err(format!(
+            "URL '{}' points to a private or loopback address ({}) and block_private_addresses is set",
+            redact_url(url), ip
+        ))
+    };
+
+    match parsed.host() {
+        Some(Host::Ipv4(ip)) if is_private_address(IpAddr::V4(ip)) => blocked(IpAddr::V4(ip)),
+        Some(Host::Ipv6(ip)) if is_private_address(IpAddr::V6(ip)) => blocked(IpAddr::V6(ip)),
+        Some(Host::Domain(domain)) => {
+            let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+            if domain == "localhost" || domain.ends_with(".localhost") {
+                return blocked(IpAddr::V4(Ipv4Addr::LOCALHOST));
+            }
+            if resolve {
+                let port = parsed.port_or_known_default().unwrap_or(80);
+                if let Ok(addrs) = (domain.as_str(), port).to_socket_addrs() {
+                    if let Some(addr) = addrs.map(|a| a.ip()).find(|ip| is_private_address(*ip)) {
+                        return blocked(addr);
+                    }
+                }
+            }
+            Ok(())
+        }
+        _ => Ok(()),
     }
 }
+
+/// Loopback, link-local, private (RFC 1918 / unique local) or unspecified;
+/// IPv4-mapped IPv6 addresses are judged by their IPv4 address
+pub fn is_private_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => ip.is_loopback() || ip.is_private() || ip.is_link_local() || ip.is_unspecified(),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(v4) => is_private_address(IpAddr::V4(v4)),
+            None => ip.is_loopback() || ip.is_unspecified() || ip.is_unique_local() || ip.is_unicast_link_local(),
+        },
+    }
+}
+
+/// `url` with any password, and the values of query parameters named like
+/// `SECRET_PARAM_HINTS`, replaced by `REDACTED`
+pub fn redact_url(url: &str) -> String {
+    let mut url = match Url::parse(url) {
+        Ok(mut parsed) if parsed.password().is_some() => {
+            let _ = parsed.set_password(Some(REDACTED));
+            parsed.to_string()
+        }
+        _ => url.to_string(),
+    };
+
+    // Edit the query as text, so the rest of the URL (e.g. an unresolved
+    // `${VAR}` template) is kept as written
+    if let Some(start) = url.find('?').map(|i| i + 1) {
+        let end = url[start..].find('#').map_or(url.len(), |i| start + i);
+        let redacted = url[start..end].split('&')
+            .map(|pair| match pair.split_once('=') {
+                Some((key, _)) if SECRET_PARAM_HINTS.iter().any(|hint| key.to_lowercase().contains(hint)) => {
+                    format!("{}={}", key, REDACTED)
+                }
+                _ => pair.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("&");
+        url.replace_range(start..end, &redacted);
+    }
+
+    url
+}
+
+pub fn validate_retry_max(retry_max: usize) -> Result<(), String> {
+    if retry_max > MAX_RETRY_MAX {
+        return Err(format!(
+            "retry_max must be between 0-{}, got {}",
+            MAX_RETRY_MAX, retry_max
+        ));
+    }
+
+    Ok(())
+}
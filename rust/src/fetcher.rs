@@ -1,66 +1,536 @@
-use crate::config::{Config, Feed};
-use crate::models::FeedItem;
-use crate::parser::Parser;
-use reqwest::Client;
-use std::sync::Arc;
+use crate::config::{check_public_host, Config, DuplicateKeep, Feed, Settings, REDACTED};
+use crate::http::{BlockedAddress, HttpClient, HttpRequest, HttpResponse, PublicResolver, ReqwestClient, TransportError};
+use crate::models::{FeedItem, FetchStatus};
+use crate::parser::{ParseOptions, Parser, JSON_PARSERS};
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::{Rng, RngCore, SeedableRng};
+use regex::Regex;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, USER_AGENT};
+use reqwest::{Client, NoProxy, Proxy};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tokio::sync::Semaphore;
 use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct FetchResult {
     pub source: String,
     pub items: Vec<FeedItem>,
     pub new_items: usize,
+    /// URLs of the new items (filled in by storage)
+    pub new_urls: Vec<String>,
     pub duration_ms: u64,
     pub error: Option<String>,
+    /// The error came from using the response (parsing, expected key,
+    /// Content-Type, compression) rather than from fetching it
+    pub parse_failed: bool,
+    /// The feed's circuit was open (see `circuit_break_threshold`) and the
+    /// single probe attempt failed too
+    pub circuit_open: bool,
+    /// Why a successful fetch looks wrong (too few items); it is still
+    /// stored, but logged as degraded
+    pub warning: Option<String>,
+    /// Peer certificate expiry (RFC 3339), None for plain-HTTP feeds
+    pub cert_expires_at: Option<String>,
+    /// SHA-256 of the response body for successful fetches
+    pub body_hash: Option<String>,
+    /// Values of `settings.capture_headers` present on the response
+    pub captured_headers: BTreeMap<String, String>,
+    /// Parsed items discarded because their URL matched `settings.url_denylist`
+    pub denylisted: usize,
+    /// Repeated items (same id) dropped from the response
+    pub duplicates: usize,
+    /// Why each skipped item was dropped by the parser (e.g. a missing URL)
+    pub parse_warnings: Vec<String>,
+    /// Mirror URL that served the feed when the primary `url` failed
+    pub mirror_url: Option<String>,
+    /// URL the feed's request ended up at when it was redirected
+    pub final_url: Option<String>,
+    /// Title the feed declares for itself (RSS/Atom `<title>`, JSON Feed `title`)
+    pub feed_title: Option<String>,
+    /// The server answered 304 Not Modified to a conditional request
+    pub not_modified: bool,
+    /// ETag / Last-Modified of the response, sent back on the next fetch
+    pub validators: CacheValidators,
+    /// The feed answered 410 Gone and should be removed or disabled
+    pub gone: bool,
+}
+
+impl FetchResult {
+    pub fn status(&self) -> FetchStatus {
+        if self.error.is_some() {
+            if self.parse_failed { FetchStatus::ParseError } else { FetchStatus::Error }
+        } else if self.warning.is_some() {
+            FetchStatus::Degraded
+        } else {
+            FetchStatus::Success
+        }
+    }
+}
+
+/// Response headers that let the next request for a feed be conditional
+/// (`If-None-Match` / `If-Modified-Since`)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheValidators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl CacheValidators {
+    fn from_response(response: &HttpResponse) -> Self {
+        let header = |name| response.headers.get(name)
+            .and_then(|v: &reqwest::header::HeaderValue| v.to_str().ok())
+            .map(str::to_string);
+        Self { etag: header(ETAG), last_modified: header(LAST_MODIFIED) }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
+/// Outcome of sampling one feed with `Fetcher::sample_all`
+#[derive(Debug, Clone)]
+pub struct SampleReport {
+    pub source: String,
+    /// Parser that handled the response (`rss`/`atom` for XML feeds)
+    pub parser: String,
+    /// The JSON parser was inferred from the feed name rather than set
+    pub inferred: bool,
+    pub items: usize,
+    pub error: Option<String>,
+    /// Another parser that yields items when the selected one yields none
+    pub suggestion: Option<(String, usize)>,
+}
+
+/// Requests `check_links` sends to a single host at once when
+/// max_concurrency_per_host is unset
+const LINK_CHECK_PER_HOST: usize = 2;
+
+/// Fetch every enabled feed in `config` once, with the same retries,
+/// concurrency limits and parsing as `feedpulse fetch`, and return one
+/// result per feed. Nothing is stored: pass the results to
+/// `Storage::store_results` to keep them. Call `Config::validate` first for
+/// configs built in code
+pub async fn run_fetch(config: &Config) -> Vec<FetchResult> {
+    Fetcher::new(config.clone()).fetch_all().await
+}
+
+/// Error recorded for feeds still running when `run_deadline_secs` passes
+pub const DEADLINE_EXCEEDED: &str = "deadline exceeded";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkStatus {
+    Ok,
+    Redirected,
+    Dead,
+}
+
+impl LinkStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LinkStatus::Ok => "ok",
+            LinkStatus::Redirected => "redirected",
+            LinkStatus::Dead => "dead",
+        }
+    }
+}
+
+/// Reachability of one stored item's URL
+#[derive(Debug, Clone)]
+pub struct LinkCheck {
+    pub id: String,
+    pub url: String,
+    pub status: LinkStatus,
+    /// HTTP status (plus redirect target) or the request error
+    pub detail: String,
+}
+
+/// Parsed items plus response metadata from a single successful request
+#[derive(Default)]
+struct FetchedFeed {
+    items: Vec<FeedItem>,
+    cert_expires_at: Option<String>,
+    /// None when the server answered 304 Not Modified
+    body_hash: Option<String>,
+    denylisted: usize,
+    duplicates: usize,
+    parse_warnings: Vec<String>,
+    final_url: Option<String>,
+    feed_title: Option<String>,
+    not_modified: bool,
+    validators: CacheValidators,
+}
+
+/// Run-wide settings plus the random source of a single feed task
+struct RunContext {
+    settings: Arc<Settings>,
+    rng: Mutex<StdRng>,
+    url_denylist: Arc<Vec<Regex>>,
+    /// Validators from the feed's last successful fetch
+    validators: CacheValidators,
+    /// `capture_headers` values from the latest response, kept on failed
+    /// fetches too (e.g. rate-limit headers on a 429)
+    captured_headers: Mutex<BTreeMap<String, String>>,
+}
+
+impl RunContext {
+    /// Context for one feed, with its own RNG seeded from `seed` so its
+    /// random choices don't depend on how tasks are scheduled
+    fn for_feed(&self, seed: u64, validators: CacheValidators) -> RunContext {
+        RunContext {
+            settings: self.settings.clone(),
+            rng: Mutex::new(StdRng::seed_from_u64(seed)),
+            url_denylist: self.url_denylist.clone(),
+            validators,
+            captured_headers: Mutex::default(),
+        }
+    }
+
+    /// Record the configured headers present on `response`, or none when
+    /// the request got no response
+    fn capture_headers(&self, response: Option<&HttpResponse>) {
+        let captured = response.map(|response| {
+            self.settings.capture_headers.iter()
+                .filter_map(|name| {
+                    let value = response.headers.get(name.as_str())?.to_str().ok()?;
+                    Some((name.clone(), value.to_string()))
+                })
+                .collect()
+        });
+        *self.captured_headers.lock().unwrap() = captured.unwrap_or_default();
+    }
+
+    fn captured_headers(&self) -> BTreeMap<String, String> {
+        self.captured_headers.lock().unwrap().clone()
+    }
+
+    /// Delay before retry `attempt`, jittered from the feed's RNG
+    fn retry_delay(&self, attempt: u32) -> Duration {
+        let mut rng = self.rng.lock().unwrap();
+        Duration::from_millis(compute_backoff(attempt, &self.settings, &mut *rng))
+    }
+
+    /// Pick a User-Agent from the configured pool, if any
+    fn pick_user_agent(&self) -> Option<String> {
+        let mut rng = self.rng.lock().unwrap();
+        self.settings.user_agent_pool.choose(&mut *rng).cloned()
+    }
+
+    fn is_denylisted(&self, url: &str) -> bool {
+        self.url_denylist.iter().any(|re| re.is_match(url))
+    }
+}
+
+/// The `max_concurrency` and `max_concurrency_per_host` permits feed
+/// fetches draw from; sharing one between fetchers (see
+/// `Fetcher::with_limits`) makes their fetches count against the same limits
+#[derive(Debug)]
+pub struct FetchLimits {
+    global: Arc<Semaphore>,
+    per_host: usize,
+    hosts: Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+impl FetchLimits {
+    pub fn new(settings: &Settings) -> Self {
+        Self {
+            global: Arc::new(Semaphore::new(settings.max_concurrency)),
+            per_host: settings.max_concurrency_per_host.unwrap_or(settings.max_concurrency),
+            hosts: Mutex::default(),
+        }
+    }
+
+    fn for_host(&self, host: String) -> Arc<Semaphore> {
+        self.hosts.lock().unwrap()
+            .entry(host)
+            .or_insert_with(|| Arc::new(Semaphore::new(self.per_host)))
+            .clone()
+    }
 }
 
 pub struct Fetcher {
     config: Config,
     client: Client,
+    retry_override: Option<usize>,
+    fail_fast: bool,
+    cancel: CancellationToken,
+    seed: Option<u64>,
+    fetched_sources: Option<HashSet<String>>,
+    validators: HashMap<String, CacheValidators>,
+    only: Option<HashSet<String>>,
+    transport: Option<Arc<dyn HttpClient>>,
+    open_circuits: HashMap<String, usize>,
+    limits: Option<Arc<FetchLimits>>,
 }
 
 impl Fetcher {
     pub fn new(config: Config) -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(config.settings.default_timeout_secs))
-            .build()
-            .expect("Failed to build HTTP client");
+        let client = Self::build_client(&config.settings, None);
+
+        Self {
+            config,
+            client,
+            retry_override: None,
+            fail_fast: false,
+            cancel: CancellationToken::new(),
+            seed: None,
+            fetched_sources: None,
+            validators: HashMap::new(),
+            only: None,
+            transport: None,
+            open_circuits: HashMap::new(),
+            limits: None,
+        }
+    }
+
+    /// HTTP client routed through `proxy` (else `settings.proxy`, if set)
+    /// for every host not listed in `settings.no_proxy`
+    fn build_client(settings: &Settings, proxy: Option<&str>) -> Client {
+        // Request headers (the pool, a feed's own User-Agent) replace it
+        let mut builder = Client::builder()
+            .user_agent(settings.user_agent.as_str())
+            .timeout(Duration::from_secs(settings.default_timeout_secs))
+            .redirect(Self::redirect_policy(settings))
+            .tls_info(true);
+
+        // Proxy URLs are checked by Config::validate
+        let proxy = proxy.or(settings.proxy.as_deref());
+        if let Some(Ok(proxy)) = proxy.map(Proxy::all) {
+            builder = builder.proxy(proxy.no_proxy(NoProxy::from_string(&settings.no_proxy.join(","))));
+        }
+
+        if settings.block_private_addresses {
+            let proxy_host = proxy.and_then(|proxy| url::Url::parse(proxy).ok())
+                .and_then(|proxy| proxy.host_str().map(str::to_string));
+            builder = builder.dns_resolver(Arc::new(PublicResolver { proxy_host }));
+        }
+
+        builder.build().expect("Failed to build HTTP client")
+    }
+
+    /// At most `max_redirects` hops; with `block_private_addresses`, none to
+    /// a private or loopback IP literal or `localhost`. Host names are left to
+    /// `PublicResolver`, since the policy can't wait on a lookup
+    fn redirect_policy(settings: &Settings) -> reqwest::redirect::Policy {
+        if !settings.block_private_addresses {
+            return reqwest::redirect::Policy::limited(settings.max_redirects);
+        }
+        let max_redirects = settings.max_redirects;
+        reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() > max_redirects {
+                return attempt.error("too many redirects");
+            }
+            match check_public_host(attempt.url().as_str(), false) {
+                Ok(()) => attempt.follow(),
+                Err(e) => attempt.error(BlockedAddress(e)),
+            }
+        })
+    }
+
+    /// The injected transport, else the shared client or one through the
+    /// feed's own `proxy`
+    fn client_for(&self, feed: &Feed) -> Arc<dyn HttpClient> {
+        if let Some(transport) = &self.transport {
+            return transport.clone();
+        }
+        let client = match &feed.proxy {
+            Some(proxy) => Self::build_client(&self.config.settings, Some(proxy)),
+            None => self.client.clone(),
+        };
+        Arc::new(ReqwestClient::new(client))
+    }
+
+    /// Send feed requests (fetches and samples) through `transport` instead
+    /// of reqwest; proxy settings then no longer apply. Link checks still
+    /// use reqwest
+    pub fn with_http_client(mut self, transport: Arc<dyn HttpClient>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// Seed the run's random choices (User-Agent rotation, retry jitter) for
+    /// reproducible runs
+    pub fn with_seed(mut self, seed: Option<u64>) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Override retry_max for every feed in this run (takes precedence over
+    /// per-feed and global settings)
+    pub fn with_retries(mut self, retries: Option<usize>) -> Self {
+        self.retry_override = retries;
+        self
+    }
 
-        Self { config, client }
+    /// Sources with fetch history; feeds missing from it use
+    /// `first_run_max_pages` instead of `max_pages`
+    pub fn with_fetched_sources(mut self, sources: HashSet<String>) -> Self {
+        self.fetched_sources = Some(sources);
+        self
+    }
+
+    /// ETag / Last-Modified per source from earlier fetches; those feeds are
+    /// requested conditionally and a 304 counts as an unchanged success
+    pub fn with_cache_validators(mut self, validators: HashMap<String, CacheValidators>) -> Self {
+        self.validators = validators;
+        self
+    }
+
+    /// Feeds whose circuit is open, with their consecutive failure count
+    /// (from `Storage::get_open_circuits`); each gets a single attempt
+    pub fn with_open_circuits(mut self, open_circuits: HashMap<String, usize>) -> Self {
+        self.open_circuits = open_circuits;
+        self
+    }
+
+    /// Draw concurrency permits from `limits` instead of limits of this
+    /// fetcher's own, so concurrent fetchers share them
+    pub fn with_limits(mut self, limits: Arc<FetchLimits>) -> Self {
+        self.limits = Some(limits);
+        self
+    }
+
+    /// Fetch only the enabled feeds with these names instead of all of them
+    pub fn with_only(mut self, names: HashSet<String>) -> Self {
+        self.only = Some(names);
+        self
+    }
+
+    /// Cancel the remaining feeds as soon as any feed errors
+    pub fn with_fail_fast(mut self, fail_fast: bool) -> Self {
+        self.fail_fast = fail_fast;
+        self
+    }
+
+    /// Token that cancels pending and in-flight fetches when triggered;
+    /// cancelled feeds are omitted from the results of `fetch_all`
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancel.clone()
     }
 
     pub async fn fetch_all(&self) -> Vec<FetchResult> {
-        let feeds = self.config.feeds.clone();
+        let feeds: Vec<Feed> = self.config.feeds.iter()
+            .filter(|feed| feed.enabled)
+            .filter(|feed| self.only.as_ref().is_none_or(|only| only.contains(&feed.name)))
+            .cloned()
+            .map(|mut feed| {
+                let first_run = self.fetched_sources.as_ref()
+                    .is_some_and(|fetched| !fetched.contains(&feed.name));
+                if let (true, Some(pages)) = (first_run, feed.first_run_max_pages) {
+                    feed.max_pages = pages;
+                }
+                feed
+            })
+            .collect();
         let max_concurrency = self.config.settings.max_concurrency;
 
-        println!(
+        tracing::info!(
             "Fetching {} feeds (max concurrency: {})...",
             feeds.len(),
             max_concurrency
         );
 
-        let semaphore = Arc::new(Semaphore::new(max_concurrency));
+        let limits = self.limits.clone()
+            .unwrap_or_else(|| Arc::new(FetchLimits::new(&self.config.settings)));
+        let mut rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        };
+        let ctx = RunContext {
+            settings: Arc::new(self.config.settings.clone()),
+            // Replaced per feed by for_feed
+            rng: Mutex::new(StdRng::seed_from_u64(0)),
+            // Patterns are checked by Config::validate
+            url_denylist: Arc::new(self.config.settings.url_denylist.iter()
+                .filter_map(|pattern| Regex::new(pattern).ok())
+                .collect()),
+            validators: CacheValidators::default(),
+            captured_headers: Mutex::default(),
+        };
+        let run_start = Instant::now();
+        let deadline = self.config.settings.run_deadline_secs
+            .map(|secs| tokio::time::Instant::now() + Duration::from_secs(secs));
         let mut tasks = Vec::new();
 
         for feed in feeds {
-            let sem = semaphore.clone();
-            let client = self.client.clone();
-            let retry_max = self.config.settings.retry_max;
-            let retry_base_delay = self.config.settings.retry_base_delay_ms;
+            let source = feed.name.clone();
+            let sem = limits.global.clone();
+            let host = url::Url::parse(&feed.url).ok()
+                .and_then(|u| u.host_str().map(str::to_string))
+                .unwrap_or_default();
+            let host_sem = limits.for_host(host);
+            let client = self.client_for(&feed);
+            let open_circuit = self.open_circuits.get(&feed.name).copied();
+            let retry_max = match open_circuit {
+                Some(_) => 0,
+                None => self.retry_override
+                    .or(feed.retry_max)
+                    .unwrap_or(self.config.settings.retry_max),
+            };
+            // Drawn in config order, so a seeded run is reproducible
+            let validators = self.validators.get(&feed.name).cloned().unwrap_or_default();
+            let ctx = Arc::new(ctx.for_feed(rng.next_u64(), validators));
+            let cancel = self.cancel.clone();
+            let fail_fast = self.fail_fast;
 
             let task = tokio::spawn(async move {
+                // Host first, so feeds queued behind a busy host don't hold
+                // global permits other hosts could use
+                let _host_permit = host_sem.acquire().await.unwrap();
                 let _permit = sem.acquire().await.unwrap();
-                Self::fetch_feed(client, feed, retry_max, retry_base_delay).await
+                if cancel.is_cancelled() {
+                    return None;
+                }
+
+                let mut result = tokio::select! {
+                    result = Self::fetch_feed(client, feed, retry_max, ctx) => result,
+                    _ = cancel.cancelled() => return None,
+                };
+                if let Some(failures) = open_circuit {
+                    match result.error.take() {
+                        Some(error) => {
+                            result.error = Some(format!("circuit open ({} consecutive failures): {}", failures, error));
+                            result.circuit_open = true;
+                        }
+                        None => tracing::info!("{}: probe succeeded, circuit closed", result.source),
+                    }
+                }
+
+                // Cancel before releasing the permit so queued feeds never start
+                if fail_fast && result.error.is_some() {
+                    cancel.cancel();
+                }
+
+                Some(result)
             });
 
-            tasks.push(task);
+            tasks.push((source, task));
         }
 
         let mut results = Vec::new();
-        for task in tasks {
-            if let Ok(result) = task.await {
+        for (source, mut task) in tasks {
+            let joined = match deadline {
+                Some(deadline) => match tokio::time::timeout_at(deadline, &mut task).await {
+                    Ok(joined) => joined,
+                    Err(_) => {
+                        task.abort();
+                        results.push(FetchResult {
+                            source,
+                            error: Some(DEADLINE_EXCEEDED.to_string()),
+                            duration_ms: run_start.elapsed().as_millis() as u64,
+                            ..Default::default()
+                        });
+                        continue;
+                    }
+                },
+                None => task.await,
+            };
+            if let Ok(Some(result)) = joined {
                 results.push(result);
             }
         }
@@ -69,46 +539,80 @@ impl Fetcher {
     }
 
     async fn fetch_feed(
-        client: Client,
+        client: Arc<dyn HttpClient>,
         feed: Feed,
         retry_max: usize,
-        retry_base_delay: u64,
+        ctx: Arc<RunContext>,
     ) -> FetchResult {
         let start = Instant::now();
         let source = feed.name.clone();
 
         for attempt in 0..=retry_max {
-            match Self::try_fetch(&client, &feed).await {
-                Ok(items) => {
+            match Self::try_with_mirrors(client.as_ref(), &feed, &ctx).await {
+                Ok((fetched, mirror_url)) => {
                     let duration_ms = start.elapsed().as_millis() as u64;
+                    let warning = feed.min_expected_items
+                        .filter(|&min| !fetched.not_modified && fetched.items.len() < min)
+                        .map(|min| format!("only {} items, expected at least {}", fetched.items.len(), min));
+                    if let Some(warning) = &warning {
+                        tracing::warn!("{}: {} (recorded as degraded)", source, warning);
+                    }
                     return FetchResult {
                         source,
-                        items,
+                        items: fetched.items,
                         new_items: 0, // Will be updated by storage
                         duration_ms,
                         error: None,
+                        warning,
+                        cert_expires_at: fetched.cert_expires_at,
+                        body_hash: fetched.body_hash,
+                        captured_headers: ctx.captured_headers(),
+                        denylisted: fetched.denylisted,
+                        duplicates: fetched.duplicates,
+                        parse_warnings: fetched.parse_warnings,
+                        mirror_url,
+                        final_url: fetched.final_url,
+                        feed_title: fetched.feed_title,
+                        not_modified: fetched.not_modified,
+                        validators: fetched.validators,
+                        ..Default::default()
                     };
                 }
                 Err(e) => {
                     if attempt < retry_max {
                         // Check if we should retry based on error type
                         let should_retry = match &e {
-                            FetchError::Http(status) => {
-                                // Don't retry 4xx errors except 429
-                                if status.as_u16() == 429 {
-                                    true
-                                } else if status.is_client_error() {
-                                    false
-                                } else {
-                                    true // Retry 5xx and other errors
+                            // Only statuses in retry_on_status when it's set;
+                            // otherwise 429 and anything but a 4xx (never 410 Gone)
+                            FetchError::Http(status) | FetchError::RetryAfter(status, _) => {
+                                match &ctx.settings.retry_on_status {
+                                    Some(statuses) => statuses.contains(&status.as_u16()),
+                                    None => status.as_u16() == 429 || !status.is_client_error(),
                                 }
                             }
+                            FetchError::MissingKey(_)
+                            | FetchError::ContentType(..)
+                            | FetchError::Compressed(_)
+                            | FetchError::TooManyRedirects(_)
+                            | FetchError::TooLarge(_)
+                            | FetchError::Blocked(_) => false,
                             _ => true, // Retry network errors, timeouts, etc.
                         };
 
                         if should_retry {
-                            let delay = retry_base_delay * 2_u64.pow(attempt as u32);
-                            sleep(Duration::from_millis(delay)).await;
+                            let delay = match &e {
+                                // The server said how long to wait; cap it so a
+                                // bad header can't stall the run
+                                FetchError::RetryAfter(_, wait) => {
+                                    (*wait).min(Duration::from_secs(ctx.settings.max_retry_after_secs))
+                                }
+                                _ => ctx.retry_delay(attempt as u32 + 1),
+                            };
+                            tracing::debug!(
+                                "{}: attempt {} failed ({}), retrying in {}ms",
+                                source, attempt + 1, e, delay.as_millis()
+                            );
+                            sleep(delay).await;
                             continue;
                         }
                     }
@@ -121,6 +625,10 @@ impl Fetcher {
                         new_items: 0,
                         duration_ms,
                         error: Some(format!("{} after {} retries", e, retry_max)),
+                        captured_headers: ctx.captured_headers(),
+                        parse_failed: e.is_parse_failure(),
+                        gone: matches!(e, FetchError::Http(status) if status == reqwest::StatusCode::GONE),
+                        ..Default::default()
                     };
                 }
             }
@@ -129,68 +637,697 @@ impl Fetcher {
         unreachable!()
     }
 
-    async fn try_fetch(client: &Client, feed: &Feed) -> Result<Vec<FeedItem>, FetchError> {
-        let mut request = client.get(&feed.url);
+    /// Try the feed's URL, then each of its `mirror_urls` in order; returns
+    /// the mirror that served the feed, if any. When every URL fails the
+    /// primary's error is returned, so retries and 410 handling follow it
+    async fn try_with_mirrors(
+        client: &dyn HttpClient,
+        feed: &Feed,
+        ctx: &RunContext,
+    ) -> Result<(FetchedFeed, Option<String>), FetchError> {
+        let primary_error = match Self::try_fetch(client, feed, ctx, true).await {
+            Ok(fetched) => return Ok((fetched, None)),
+            Err(e) => e,
+        };
+        let primary_headers = ctx.captured_headers();
+
+        for mirror_url in &feed.mirror_urls {
+            let mirror = Feed { url: mirror_url.clone(), ..feed.clone() };
+            if let Ok(fetched) = Self::try_fetch(client, &mirror, ctx, false).await {
+                return Ok((fetched, Some(mirror_url.clone())));
+            }
+        }
+
+        *ctx.captured_headers.lock().unwrap() = primary_headers;
+        Err(primary_error)
+    }
+
+    /// Check whether `(id, url)` pairs are still reachable with HEAD requests
+    /// (GET when HEAD isn't allowed), without following redirects; bounded by
+    /// max_concurrency overall and max_concurrency_per_host (or
+    /// `LINK_CHECK_PER_HOST`) per host
+    pub async fn check_links(&self, links: Vec<(String, String)>) -> Vec<LinkCheck> {
+        let client = Client::builder()
+            .user_agent(self.config.settings.user_agent.as_str())
+            .timeout(Duration::from_secs(self.config.settings.default_timeout_secs))
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .expect("Failed to build HTTP client");
+
+        let semaphore = Arc::new(Semaphore::new(self.config.settings.max_concurrency));
+        let per_host = self.config.settings.max_concurrency_per_host.unwrap_or(LINK_CHECK_PER_HOST);
+        let mut host_limits: HashMap<String, Arc<Semaphore>> = HashMap::new();
+        let mut tasks = Vec::new();
+
+        for (id, url) in links {
+            let host = url::Url::parse(&url).ok()
+                .and_then(|u| u.host_str().map(str::to_string))
+                .unwrap_or_default();
+            let host_sem = host_limits.entry(host)
+                .or_insert_with(|| Arc::new(Semaphore::new(per_host)))
+                .clone();
+            let sem = semaphore.clone();
+            let client = client.clone();
+            let block_private = self.config.settings.block_private_addresses;
 
-        for (key, value) in &feed.headers {
-            request = request.header(key, value);
+            tasks.push(tokio::spawn(async move {
+                let _host_permit = host_sem.acquire().await.unwrap();
+                let _permit = sem.acquire().await.unwrap();
+                Self::check_link(&client, id, url, block_private).await
+            }));
         }
 
-        let response = request.send().await.map_err(|e| {
-            if e.is_timeout() {
-                FetchError::Timeout
-            } else if e.is_connect() {
-                FetchError::Connect
-            } else {
-                FetchError::Network(e.to_string())
+        let mut checks = Vec::new();
+        for task in tasks {
+            if let Ok(check) = task.await {
+                checks.push(check);
             }
-        })?;
+        }
 
-        let status = response.status();
+        checks
+    }
+
+    async fn check_link(client: &Client, id: String, url: String, block_private: bool) -> LinkCheck {
+        if block_private {
+            if let Err(e) = check_public_url(&url).await {
+                return LinkCheck { id, url, status: LinkStatus::Dead, detail: format!("blocked: {}", e) };
+            }
+        }
+
+        let mut response = client.head(&url).send().await;
+
+        let head_unsupported = response.as_ref().is_ok_and(|r| {
+            matches!(r.status(), reqwest::StatusCode::METHOD_NOT_ALLOWED | reqwest::StatusCode::NOT_IMPLEMENTED)
+        });
+        if head_unsupported {
+            response = client.get(&url).send().await;
+        }
+
+        let (status, detail) = match response {
+            Ok(response) => {
+                let code = response.status();
+                if code.is_success() {
+                    (LinkStatus::Ok, code.as_u16().to_string())
+                } else if code.is_redirection() {
+                    let location = response.headers()
+                        .get(reqwest::header::LOCATION)
+                        .and_then(|v| v.to_str().ok())
+                        .unwrap_or("?");
+                    (LinkStatus::Redirected, format!("{} → {}", code.as_u16(), location))
+                } else {
+                    (LinkStatus::Dead, code.as_u16().to_string())
+                }
+            }
+            Err(e) => (LinkStatus::Dead, e.to_string()),
+        };
+
+        LinkCheck { id, url, status, detail }
+    }
+
+    /// Fetch each feed once (no retries) and check that the parser selected
+    /// for it actually produces items, suggesting one that does if not
+    pub async fn sample_all(&self) -> Vec<SampleReport> {
+        let ctx = RunContext {
+            settings: Arc::new(self.config.settings.clone()),
+            rng: Mutex::new(match self.seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => StdRng::from_os_rng(),
+            }),
+            url_denylist: Arc::new(Vec::new()),
+            validators: CacheValidators::default(),
+            captured_headers: Mutex::default(),
+        };
+
+        let mut reports = Vec::new();
+        for feed in self.config.feeds.iter().filter(|feed| feed.enabled) {
+            let client = self.client_for(feed);
+            reports.push(Self::sample_feed(client.as_ref(), feed, &ctx).await);
+        }
+
+        reports
+    }
+
+    async fn sample_feed(client: &dyn HttpClient, feed: &Feed, ctx: &RunContext) -> SampleReport {
+        let is_json = feed.feed_type == "json";
+        let options = ParseOptions::from_feed(feed, &ctx.settings);
+        let parser = if is_json {
+            Parser::json_parser_for(&feed.name, &options)
+        } else {
+            feed.feed_type.as_str()
+        };
+        let mut report = SampleReport {
+            source: feed.name.clone(),
+            parser: parser.to_string(),
+            inferred: is_json && feed.parser.is_none(),
+            items: 0,
+            error: None,
+            suggestion: None,
+        };
+
+        let body = match Self::fetch_body(client, feed, ctx).await {
+            Ok(body) => body,
+            Err(e) => {
+                report.error = Some(e.to_string());
+                return report;
+            }
+        };
+
+        let parsed = if is_json {
+            Parser::parse_json_as(parser, &feed.name, &body, &options)
+        } else {
+            Parser::parse_with_options(&feed.name, &feed.feed_type, &body, &options)
+        };
+
+        match parsed {
+            Ok(items) => report.items = items.len(),
+            Err(e) => report.error = Some(e),
+        }
+
+        if is_json && report.items == 0 {
+            report.suggestion = JSON_PARSERS.iter()
+                .filter(|candidate| **candidate != parser)
+                .filter_map(|candidate| {
+                    let items = Parser::parse_json_as(candidate, &feed.name, &body, &options).ok()?;
+                    Some((candidate.to_string(), items.len()))
+                })
+                .filter(|(_, count)| *count > 0)
+                .max_by_key(|(_, count)| *count);
+        }
+
+        report
+    }
+
+    /// Send the request for a feed and return the body of a successful response
+    async fn fetch_body(client: &dyn HttpClient, feed: &Feed, ctx: &RunContext) -> Result<String, FetchError> {
+        let response = Self::send_request(client, feed, &feed.url, ctx, None).await?;
+
+        let status = response.status;
         if !status.is_success() {
             return Err(FetchError::Http(status));
         }
 
-        let body = response.text().await.map_err(|e| FetchError::Network(e.to_string()))?;
+        Self::read_body(response)
+    }
+
+    async fn send_request(
+        client: &dyn HttpClient,
+        feed: &Feed,
+        url: &str,
+        ctx: &RunContext,
+        validators: Option<&CacheValidators>,
+    ) -> Result<HttpResponse, FetchError> {
+        let mut headers = Vec::new();
+
+        if let Some(validators) = validators {
+            if let Some(etag) = &validators.etag {
+                headers.push((IF_NONE_MATCH.to_string(), etag.clone()));
+            }
+            if let Some(last_modified) = &validators.last_modified {
+                headers.push((IF_MODIFIED_SINCE.to_string(), last_modified.clone()));
+            }
+        }
+
+        // An explicit per-feed User-Agent always wins over the pool
+        let has_user_agent = feed.headers.keys().any(|k| k.eq_ignore_ascii_case("user-agent"));
+        if !has_user_agent {
+            if let Some(user_agent) = ctx.pick_user_agent() {
+                headers.push((USER_AGENT.to_string(), user_agent));
+            }
+        }
+
+        headers.extend(feed.headers.iter().map(|(key, value)| (key.clone(), value.clone())));
+
+        // Likewise an explicit Authorization header wins over `auth`
+        let has_authorization = feed.headers.keys().any(|k| k.eq_ignore_ascii_case("authorization"));
+
+        let request = HttpRequest {
+            url: url.to_string(),
+            headers,
+            auth: feed.auth.clone().filter(|_| !has_authorization),
+            // Takes precedence over the client-wide default_timeout_secs
+            timeout: feed.timeout_secs.map(Duration::from_secs),
+            max_body_bytes: Some(ctx.settings.max_response_bytes),
+        };
+
+        client.get(request).await.map_err(|e| Self::request_error(e, feed, ctx))
+    }
 
-        // Parse feed
-        Parser::parse(&feed.name, &feed.feed_type, &body)
-            .map_err(|e| FetchError::Parse(e))
+    fn request_error(e: TransportError, feed: &Feed, ctx: &RunContext) -> FetchError {
+        match e {
+            TransportError::Timeout => {
+                let secs = feed.timeout_secs.unwrap_or(ctx.settings.default_timeout_secs);
+                FetchError::Timeout(secs, feed.url.clone())
+            }
+            TransportError::Connect => FetchError::Connect,
+            TransportError::TooManyRedirects => FetchError::TooManyRedirects(ctx.settings.max_redirects),
+            TransportError::TooLarge => FetchError::TooLarge(ctx.settings.max_response_bytes),
+            TransportError::Blocked(message) => FetchError::Blocked(message),
+            TransportError::Other(message) => FetchError::Network(message),
+        }
     }
 
+    /// Fetch and parse one URL (following pagination); `conditional` sends
+    /// the feed's cache validators, which only apply to its primary URL
+    async fn try_fetch(
+        client: &dyn HttpClient,
+        feed: &Feed,
+        ctx: &RunContext,
+        conditional: bool,
+    ) -> Result<FetchedFeed, FetchError> {
+        let validators = conditional.then_some(&ctx.validators).filter(|v| !v.is_empty());
+        let response = Self::send_request(client, feed, &feed.url, ctx, validators).await;
+        ctx.capture_headers(response.as_ref().ok());
+        let mut response = response?;
+
+        let status = response.status;
+        if status == reqwest::StatusCode::NOT_MODIFIED && validators.is_some() {
+            return Ok(FetchedFeed {
+                not_modified: true,
+                validators: ctx.validators.clone(),
+                ..Default::default()
+            });
+        }
+        if !status.is_success() {
+            return Err(status_error(&response));
+        }
+
+        let content_type = response.headers
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("")
+            .to_string();
+        if ctx.settings.strict_content_type
+            && !feed.skip_content_type_check
+            && !content_type_matches(&feed.feed_type, &content_type)
+        {
+            return Err(FetchError::ContentType(content_type, feed.feed_type.clone()));
+        }
+
+        let validators = CacheValidators::from_response(&response);
+
+        let cert_expires_at = response.peer_certificate.as_deref().and_then(cert_expiry_from_der);
+
+        let final_url = url::Url::parse(&feed.url).ok()
+            .filter(|requested| *requested != response.url)
+            .map(|_| response.url.to_string());
+
+        let options = ParseOptions::from_feed(feed, &ctx.settings);
+        let mut items = Vec::new();
+        let mut hasher = Sha256::new();
+        let mut page = 1;
+        let mut parse_as: Option<&str> = None;
+        let mut feed_title = None;
+        let mut visited = HashSet::new();
+        let mut parse_warnings = Vec::new();
+
+        loop {
+            let page_url = response.url.clone();
+            let mut next_url = next_page_url(&response);
+            let body = Self::read_body(response)?;
+            if let Some(path) = &feed.next_page_path {
+                next_url = next_page_from_body(&body, path, &page_url);
+            }
+            let feed_type = *parse_as.get_or_insert_with(|| {
+                Self::detect_mislabeled_type(feed, &content_type, &body).unwrap_or(&feed.feed_type)
+            });
+
+            if let Some(key) = &feed.expect_key {
+                Self::check_expected_key(&body, key)?;
+            }
+
+            // Parse feed
+            let outcome = Parser::parse_outcome(&feed.name, feed_type, &body, &options)
+                .map_err(FetchError::Parse)?;
+            for warning in &outcome.warnings {
+                tracing::warn!("{}", warning);
+            }
+            items.extend(outcome.items);
+            parse_warnings.extend(outcome.warnings);
+            hasher.update(body.as_bytes());
+            if page == 1 {
+                feed_title = Parser::declared_title(feed_type, &body);
+            }
+
+            // A next link pointing back at a page already read would loop
+            visited.insert(page_url.to_string());
+            let Some(next_url) = next_url.filter(|url| page < feed.max_pages && !visited.contains(url)) else {
+                break;
+            };
+            // The feed's headers and credentials go with every page, so
+            // they're only sent back to the origin they were configured for
+            if url::Url::parse(&next_url).map(|next| next.origin()).ok() != Some(page_url.origin()) {
+                tracing::warn!(
+                    "{}: next page {} is on another origin than {}; stopping pagination",
+                    feed.name, url_for_error(&next_url), url_for_error(page_url.as_str())
+                );
+                break;
+            }
+            if ctx.settings.block_private_addresses {
+                check_public_url(&next_url).await.map_err(FetchError::Blocked)?;
+            }
+            page += 1;
+            response = Self::send_request(client, feed, &next_url, ctx, None).await?;
+            if !response.status.is_success() {
+                return Err(status_error(&response));
+            }
+        }
+
+        // Items that link to the feed URL itself are keyed on where it
+        // resolved, not on a short or rotating link
+        if let Some(final_url) = &final_url {
+            for item in items.iter_mut().filter(|item| item.url == feed.url) {
+                item.url = final_url.clone();
+                item.id = FeedItem::generate_id(&item.source, final_url);
+            }
+        }
+
+        let parsed = items.len();
+        items.retain(|item| !ctx.is_denylisted(&item.url));
+        let denylisted = parsed - items.len();
+
+        // A response may list an item twice (e.g. pinned and in place)
+        let before_dedup = items.len();
+        let items_in_order: Vec<FeedItem> = match ctx.settings.duplicate_keep {
+            DuplicateKeep::First => items,
+            DuplicateKeep::Last => items.into_iter().rev().collect(),
+        };
+        let mut seen = HashSet::new();
+        let mut items: Vec<FeedItem> = items_in_order.into_iter()
+            .filter(|item| seen.insert(item.id.clone()))
+            .collect();
+        if ctx.settings.duplicate_keep == DuplicateKeep::Last {
+            items.reverse();
+        }
+        let duplicates = before_dedup - items.len();
+
+        if ctx.settings.collapse_whitespace {
+            for item in &mut items {
+                let title = Parser::collapse_whitespace(&item.title);
+                if title != item.title {
+                    // Keep the original title recoverable when the parser
+                    // didn't store the raw source
+                    if item.raw_data.is_none() {
+                        item.raw_data = Some(serde_json::json!({ "title": item.title }).to_string());
+                    }
+                    item.title = title;
+                }
+                if let Some(summary) = &item.summary {
+                    item.summary = Some(Parser::collapse_whitespace(summary));
+                }
+            }
+        }
+
+        let body_hash = Some(format!("{:x}", hasher.finalize()));
+
+        Ok(FetchedFeed {
+            items,
+            cert_expires_at,
+            body_hash,
+            denylisted,
+            duplicates,
+            parse_warnings,
+            final_url,
+            feed_title,
+            not_modified: false,
+            validators,
+        })
+    }
+
+    /// Take a response body, rejecting compressed bodies this build can't
+    /// decode instead of handing binary data to the parser. reqwest decodes
+    /// gzip and brotli itself and drops their Content-Encoding header, so
+    /// any encoding still named here was left undecoded
+    fn read_body(response: HttpResponse) -> Result<String, FetchError> {
+        let encoding = response.headers
+            .get(reqwest::header::CONTENT_ENCODING)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.trim().to_ascii_lowercase())
+            .filter(|v| !v.is_empty() && v != "identity");
+        if let Some(encoding) = encoding {
+            return Err(FetchError::Compressed(encoding));
+        }
+
+        let body = response.body;
+        // Gzip magic (0x1f 0x8b) without a Content-Encoding header; no text
+        // feed starts with the 0x1f control character
+        if body.starts_with('\u{1f}') {
+            return Err(FetchError::Compressed("gzip".to_string()));
+        }
+
+        Ok(body)
+    }
+
+    /// Feed type to parse a response as when its Content-Type contradicts
+    /// the configured feed_type and the body agrees with the header (e.g.
+    /// JSON served from an `.rss` URL); warns so the config can be fixed
+    fn detect_mislabeled_type(feed: &Feed, content_type: &str, body: &str) -> Option<&'static str> {
+        if feed.skip_content_type_check || content_type_matches(&feed.feed_type, content_type) {
+            return None;
+        }
+
+        let detected = Parser::detect_feed_type(body)?;
+        if !content_type_matches(detected, content_type) {
+            return None;
+        }
+
+        tracing::warn!(
+            "feed '{}' is configured as {} but served '{}'; parsing as {} (fix feed_type in the config)",
+            feed.name,
+            feed.feed_type,
+            content_type,
+            if detected == "json" { "JSON" } else { "XML" }
+        );
+        Some(detected)
+    }
+
+    fn check_expected_key(body: &str, key: &str) -> Result<(), FetchError> {
+        let json: serde_json::Value = serde_json::from_str(body)
+            .map_err(|e| FetchError::Parse(format!("malformed JSON: {}", e)))?;
+
+        match Parser::lookup_path(&json, key) {
+            Some(_) => Ok(()),
+            None => Err(FetchError::MissingKey(key.to_string())),
+        }
+    }
+}
+
+/// `check_public_host` with name resolution, run off the async workers
+async fn check_public_url(url: &str) -> Result<(), String> {
+    let url = url.to_string();
+    tokio::task::spawn_blocking(move || check_public_host(&url, true))
+        .await
+        .unwrap_or_else(|e| Err(format!("address check failed: {}", e)))
 }
 
-pub fn print_result(result: &FetchResult) {
+/// Target of a `Link: <...>; rel="next"` header, resolved against the
+/// response URL
+fn next_page_url(response: &HttpResponse) -> Option<String> {
+    let links = response.headers.get(reqwest::header::LINK)?.to_str().ok()?;
+
+    links.split(',').find_map(|link| {
+        let (target, params) = link.split_once(';')?;
+        let is_next = params.split(';').any(|param| {
+            param.trim().strip_prefix("rel=")
+                .is_some_and(|rel| rel.trim_matches('"').split_whitespace().any(|r| r == "next"))
+        });
+        if !is_next {
+            return None;
+        }
+
+        let target = target.trim().strip_prefix('<')?.strip_suffix('>')?;
+        response.url.join(target).ok().map(String::from)
+    })
+}
+
+/// Next page URL found at `path` in a JSON body, resolved against the URL of
+/// the page it came from; null, empty or missing means no more pages
+fn next_page_from_body(body: &str, path: &str, page_url: &url::Url) -> Option<String> {
+    let json: serde_json::Value = serde_json::from_str(body).ok()?;
+    let next = Parser::lookup_path(&json, path)?.as_str()?.trim();
+    if next.is_empty() {
+        return None;
+    }
+
+    page_url.join(next).ok().map(String::from)
+}
+
+/// Whether a Content-Type header is plausible for a feed_type
+fn content_type_matches(feed_type: &str, content_type: &str) -> bool {
+    let mime = content_type.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+    match feed_type {
+        "json" => mime.ends_with("/json") || mime.ends_with("+json"),
+        "rss" | "atom" => mime.ends_with("/xml") || mime.ends_with("+xml"),
+        _ => true,
+    }
+}
+
+/// Delay in ms before retry `attempt` (1-based): the backoff clamped to
+/// `retry_max_delay_ms`, plus jitter drawn uniformly from `rng` in
+/// `0..=retry_jitter_max_ms`. Pass a seeded RNG for exact, repeatable values
+pub fn compute_backoff<R: Rng + ?Sized>(attempt: u32, settings: &Settings, rng: &mut R) -> u64 {
+    let delay_ms = settings.retry_delay_ms(attempt);
+    let jitter_ms = match settings.retry_jitter_max_ms(delay_ms) {
+        0 => 0,
+        max => rng.random_range(0..=max),
+    };
+    delay_ms.saturating_add(jitter_ms)
+}
+
+/// Wait requested by a `Retry-After` header, given either as delta-seconds
+/// (`120`) or an HTTP-date (`Wed, 21 Oct 2015 07:28:00 GMT`); a date in the
+/// past means no wait
+pub fn parse_retry_after(value: &str, now: chrono::DateTime<chrono::Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    Some((at.to_utc() - now).to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Error for a non-success response, keeping the server's `Retry-After`
+/// wait for 429 and 503 answers
+fn status_error(response: &HttpResponse) -> FetchError {
+    let status = response.status;
+    let throttled = matches!(status, reqwest::StatusCode::TOO_MANY_REQUESTS | reqwest::StatusCode::SERVICE_UNAVAILABLE);
+
+    let retry_after = response.headers
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| parse_retry_after(v, chrono::Utc::now()));
+
+    match retry_after {
+        Some(wait) if throttled => FetchError::RetryAfter(status, wait),
+        _ => FetchError::Http(status),
+    }
+}
+
+/// Extract the `notAfter` date (RFC 3339) from a DER-encoded certificate
+pub fn cert_expiry_from_der(der: &[u8]) -> Option<String> {
+    let (_, cert) = x509_parser::parse_x509_certificate(der).ok()?;
+    let not_after = cert.validity().not_after.timestamp();
+
+    chrono::DateTime::from_timestamp(not_after, 0).map(|dt| dt.to_rfc3339())
+}
+
+/// Log one feed's outcome at info level; `dry_run` omits the new-item count,
+/// which is only known once results are stored
+pub fn print_result(result: &FetchResult, dry_run: bool) {
     if let Some(error) = &result.error {
-        eprintln!("  ✗ {:<25} — error: {}", result.source, error);
+        // Parse errors already read "parse error: ..."
+        let error = error.strip_prefix("parse error: ").unwrap_or(error);
+        tracing::info!("  ✗ {:<25} — {}: {}", result.source, result.status().label(), error);
+    } else if result.not_modified {
+        tracing::info!("  ✓ {:<25} — not modified in {}ms", result.source, result.duration_ms);
     } else {
-        println!(
-            "  ✓ {:<25} — {} items ({} new) in {}ms",
+        let mut details = Vec::new();
+        if !dry_run {
+            details.push(format!("{} new", result.new_items));
+        }
+        if !result.parse_warnings.is_empty() {
+            details.push(format!("{} skipped", result.parse_warnings.len()));
+        }
+        if result.denylisted > 0 {
+            details.push(format!("{} denylisted", result.denylisted));
+        }
+        if result.duplicates > 0 {
+            details.push(format!("{} duplicates", result.duplicates));
+        }
+        if let Some(mirror_url) = &result.mirror_url {
+            details.push(format!("via mirror {}", mirror_url));
+        }
+        if let Some(final_url) = &result.final_url {
+            details.push(format!("redirected to {}", final_url));
+        }
+        if let Some(warning) = &result.warning {
+            details.push(format!("degraded: {}", warning));
+        }
+        let details = if details.is_empty() { String::new() } else { format!(" ({})", details.join(", ")) };
+        tracing::info!(
+            "  ✓ {:<25} — {} items{} in {}ms",
             result.source,
             result.items.len(),
-            result.new_items,
+            details,
             result.duration_ms
         );
     }
 }
 
+/// `url` with its query string and any password replaced by `REDACTED`,
+/// for error messages that end up in logs and the fetch log
+fn url_for_error(url: &str) -> String {
+    let Ok(mut parsed) = url::Url::parse(url) else {
+        return url.to_string();
+    };
+    if parsed.password().is_none() && parsed.query().is_none() {
+        return url.to_string();
+    }
+    if parsed.password().is_some() {
+        let _ = parsed.set_password(Some(REDACTED));
+    }
+    if parsed.query().is_some() {
+        parsed.set_query(Some(REDACTED));
+    }
+    parsed.to_string()
+}
+
 #[derive(Debug)]
 enum FetchError {
-    Timeout,
+    /// Configured timeout in seconds, feed URL
+    Timeout(u64, String),
     Connect,
     Network(String),
     Http(reqwest::StatusCode),
     Parse(String),
+    MissingKey(String),
+    /// Actual Content-Type, configured feed_type
+    ContentType(String, String),
+    /// Content-Encoding of a body that couldn't be decoded
+    Compressed(String),
+    /// The configured `max_redirects` that was exceeded
+    TooManyRedirects(usize),
+    /// The configured `max_response_bytes` that was exceeded
+    TooLarge(u64),
+    /// A redirect or next page to an address `block_private_addresses` refuses
+    Blocked(String),
+    /// 429/503 with the wait the server asked for in `Retry-After`
+    RetryAfter(reqwest::StatusCode, Duration),
+}
+
+impl FetchError {
+    /// A response arrived but its content couldn't be used
+    fn is_parse_failure(&self) -> bool {
+        matches!(
+            self,
+            FetchError::Parse(_) | FetchError::MissingKey(_) | FetchError::ContentType(..) | FetchError::Compressed(_)
+        )
+    }
 }
 
 impl std::fmt::Display for FetchError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            FetchError::Timeout => write!(f, "HTTP timeout"),
+            FetchError::Timeout(secs, url) => write!(f, "timed out after {}s fetching {}", secs, url_for_error(url)),
             FetchError::Connect => write!(f, "DNS resolution failure"),
+            FetchError::TooManyRedirects(max) => write!(f, "too many redirects (max_redirects: {})", max),
+            FetchError::TooLarge(max) => write!(f, "response exceeded max size ({} bytes)", max),
+            FetchError::Blocked(msg) => write!(f, "blocked: {}", msg),
             FetchError::Network(msg) => write!(f, "network error: {}", msg),
             FetchError::Http(status) => write!(f, "HTTP {}", status),
+            FetchError::RetryAfter(status, wait) => write!(f, "HTTP {} (Retry-After {}s)", status, wait.as_secs()),
             FetchError::Parse(msg) => write!(f, "parse error: {}", msg),
+            FetchError::MissingKey(key) => write!(f, "expected key {} missing (API changed?)", key),
+            FetchError::ContentType(actual, feed_type) => write!(
+                f,
+                "unexpected content-type '{}' for {} feed (set skip_content_type_check to trust feed_type)",
+                actual, feed_type
+            ),
+            FetchError::Compressed(encoding) => write!(
+                f,
+                "response was compressed ({}) and could not be decoded (check the feed's Accept-Encoding header)",
+                encoding
+            ),
         }
     }
 }
@@ -0,0 +1,51 @@
+use crate::fetcher::FetchResult;
+use std::process::{ExitStatus, Stdio};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+/// Run `command` through the platform shell after a fetch. The run summary
+/// is passed as `FEEDPULSE_FEEDS`, `FEEDPULSE_NEW_ITEMS` and
+/// `FEEDPULSE_ERRORS`, and the new item URLs are written to stdin one per line
+pub async fn run_post_fetch_hook(command: &str, results: &[FetchResult]) -> Result<ExitStatus, String> {
+    let new_items: usize = results.iter().map(|r| r.new_items).sum();
+    let errors = results.iter().filter(|r| r.error.is_some()).count();
+    let urls: String = results.iter()
+        .flat_map(|r| &r.new_urls)
+        .map(|url| format!("{}\n", url))
+        .collect();
+
+    let mut child = shell(command)
+        .env("FEEDPULSE_FEEDS", results.len().to_string())
+        .env("FEEDPULSE_NEW_ITEMS", new_items.to_string())
+        .env("FEEDPULSE_ERRORS", errors.to_string())
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("failed to start post_fetch_hook: {}", e))?;
+
+    // Write alongside waiting so a hook that never reads stdin can't block
+    // us on a full pipe; a hook that exits early just closes it
+    let stdin = child.stdin.take();
+    let write = async move {
+        // Dropped at the end, closing stdin so hooks reading to EOF finish
+        if let Some(mut stdin) = stdin {
+            let _ = stdin.write_all(urls.as_bytes()).await;
+        }
+    };
+    let (_, status) = tokio::join!(write, child.wait());
+
+    status.map_err(|e| format!("failed to wait for post_fetch_hook: {}", e))
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(command);
+    cmd
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.arg("/C").arg(command);
+    cmd
+}
@@ -0,0 +1,214 @@
+use crate::config::{is_private_address, FeedAuth};
+use encoding_rs::{Encoding, UTF_8};
+use reqwest::header::{HeaderMap, CONTENT_TYPE};
+use reqwest::{Client, StatusCode};
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::time::Duration;
+use url::Url;
+
+/// A GET request as the fetcher sends it
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    pub url: String,
+    /// Headers in the order they are applied; later values for the same
+    /// name are sent in addition, as with reqwest
+    pub headers: Vec<(String, String)>,
+    /// Credentials for the `Authorization` header
+    pub auth: Option<FeedAuth>,
+    /// Overrides the client's default timeout for this request
+    pub timeout: Option<Duration>,
+    /// Give up with `TransportError::TooLarge` once the body passes this
+    /// many bytes, without reading the rest
+    pub max_body_bytes: Option<u64>,
+}
+
+/// A response with its body already read
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    /// Final URL, after any redirects
+    pub url: Url,
+    pub headers: HeaderMap,
+    /// Body decoded as text (charset from `Content-Type`, else UTF-8)
+    pub body: String,
+    /// DER-encoded peer certificate for HTTPS responses
+    pub peer_certificate: Option<Vec<u8>>,
+}
+
+impl HttpResponse {
+    /// A `200 OK` response for `url` with `body`, for fake clients
+    pub fn ok(url: &str, body: impl Into<String>) -> Self {
+        Self::with_status(url, StatusCode::OK, body)
+    }
+
+    pub fn with_status(url: &str, status: StatusCode, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            url: Url::parse(url).expect("fake response URL must be valid"),
+            headers: HeaderMap::new(),
+            body: body.into(),
+            peer_certificate: None,
+        }
+    }
+}
+
+/// Why a request got no response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransportError {
+    Timeout,
+    /// DNS or TCP/TLS connection failure
+    Connect,
+    /// The redirect chain was longer than the client allows
+    TooManyRedirects,
+    /// The body was longer than `HttpRequest::max_body_bytes`
+    TooLarge,
+    /// A host `block_private_addresses` refused to connect to
+    Blocked(String),
+    Other(String),
+}
+
+pub type HttpFuture<'a> = Pin<Box<dyn Future<Output = Result<HttpResponse, TransportError>> + Send + 'a>>;
+
+/// Transport the fetcher sends feed requests through. The default is
+/// `ReqwestClient`; tests inject fakes with canned responses and errors
+pub trait HttpClient: Send + Sync {
+    fn get(&self, request: HttpRequest) -> HttpFuture<'_>;
+}
+
+/// `HttpClient` backed by a reqwest `Client`
+#[derive(Clone)]
+pub struct ReqwestClient {
+    client: Client,
+}
+
+impl ReqwestClient {
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+impl HttpClient for ReqwestClient {
+    fn get(&self, request: HttpRequest) -> HttpFuture<'_> {
+        Box::pin(async move {
+            let mut builder = self.client.get(&request.url);
+            if let Some(timeout) = request.timeout {
+                builder = builder.timeout(timeout);
+            }
+            match &request.auth {
+                Some(FeedAuth::Bearer(token)) => builder = builder.bearer_auth(token),
+                Some(FeedAuth::Basic { username, password }) => builder = builder.basic_auth(username, Some(password)),
+                None => {}
+            }
+            for (name, value) in &request.headers {
+                builder = builder.header(name, value);
+            }
+
+            let mut response = builder.send().await.map_err(transport_error)?;
+            let status = response.status();
+            let url = response.url().clone();
+            let headers = response.headers().clone();
+            let peer_certificate = response.extensions()
+                .get::<reqwest::tls::TlsInfo>()
+                .and_then(|info| info.peer_certificate())
+                .map(<[u8]>::to_vec);
+
+            // Refuse an oversized declared length up front, then count the
+            // bytes actually received, which may have no declared length
+            let limit = request.max_body_bytes.unwrap_or(u64::MAX);
+            if response.content_length().is_some_and(|len| len > limit) {
+                return Err(TransportError::TooLarge);
+            }
+            let mut bytes = Vec::new();
+            while let Some(chunk) = response.chunk().await.map_err(transport_error)? {
+                if (bytes.len() + chunk.len()) as u64 > limit {
+                    return Err(TransportError::TooLarge);
+                }
+                bytes.extend_from_slice(&chunk);
+            }
+            let body = decode_body(&headers, &bytes);
+
+            Ok(HttpResponse { status, url, headers, body, peer_certificate })
+        })
+    }
+}
+
+/// Decode with the `Content-Type` charset, else UTF-8, replacing invalid
+/// sequences (as `reqwest::Response::text` does)
+fn decode_body(headers: &HeaderMap, bytes: &[u8]) -> String {
+    let encoding = headers.get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| {
+            v.split(';')
+                .filter_map(|param| param.trim().split_once('='))
+                .find(|(key, _)| key.trim().eq_ignore_ascii_case("charset"))
+                .map(|(_, value)| value.trim().trim_matches('"').to_string())
+        })
+        .and_then(|label| Encoding::for_label(label.as_bytes()))
+        .unwrap_or(UTF_8);
+    encoding.decode(bytes).0.into_owned()
+}
+
+/// Error a client's redirect policy or resolver returns for a host it
+/// won't connect to; surfaces as `TransportError::Blocked`
+#[derive(Debug)]
+pub struct BlockedAddress(pub String);
+
+impl std::fmt::Display for BlockedAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for BlockedAddress {}
+
+/// Resolver for `block_private_addresses` that fails host names resolving to
+/// a private or loopback address, so every connection is checked, redirects
+/// included, without a blocking lookup. The proxy's own host is exempt,
+/// since a proxy on the local network is expected
+pub struct PublicResolver {
+    pub proxy_host: Option<String>,
+}
+
+impl reqwest::dns::Resolve for PublicResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let exempt = self.proxy_host.as_deref() == Some(name.as_str());
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0)).await?.collect();
+            if let Some(addr) = addrs.iter().find(|addr| !exempt && is_private_address(addr.ip())) {
+                return Err(Box::new(BlockedAddress(format!(
+                    "host '{}' resolves to a private or loopback address ({}) and block_private_addresses is set",
+                    name.as_str(), addr.ip()
+                ))) as Box<dyn std::error::Error + Send + Sync>);
+            }
+            Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+/// The `BlockedAddress` anywhere in `e`'s chain of causes
+fn blocked_cause(e: &reqwest::Error) -> Option<String> {
+    let mut cause = std::error::Error::source(e);
+    while let Some(err) = cause {
+        if let Some(blocked) = err.downcast_ref::<BlockedAddress>() {
+            return Some(blocked.0.clone());
+        }
+        cause = err.source();
+    }
+    None
+}
+
+fn transport_error(e: reqwest::Error) -> TransportError {
+    if let Some(message) = blocked_cause(&e) {
+        TransportError::Blocked(message)
+    } else if e.is_timeout() {
+        TransportError::Timeout
+    } else if e.is_connect() {
+        TransportError::Connect
+    } else if e.is_redirect() {
+        TransportError::TooManyRedirects
+    } else {
+        TransportError::Other(e.to_string())
+    }
+}
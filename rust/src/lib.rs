@@ -1,14 +1,21 @@
 // Library crate for feedpulse - exposes public API for testing and reuse
 
+pub mod browse;
 pub mod config;
 pub mod fetcher;
+pub mod hooks;
+pub mod http;
+pub mod logging;
 pub mod models;
+pub mod opml;
 pub mod parser;
 pub mod reporter;
 pub mod storage;
+pub mod watch;
 
 // Re-export commonly used types
 pub use config::{Config, Feed, Settings};
-pub use models::FeedItem;
+pub use fetcher::{run_fetch, FetchResult};
+pub use models::{FeedItem, FetchStatus};
 pub use parser::Parser;
 pub use storage::Storage;
@@ -0,0 +1,78 @@
+use std::fmt;
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields, MakeWriter};
+use tracing_subscriber::registry::LookupSpan;
+
+/// How log events are written to stderr
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Plain lines as the CLI has always printed them; warnings and errors
+    /// carry a `Warning:`/`Error:` prefix
+    Text,
+    /// One JSON object per line with `timestamp`, `level`, `target` and `message`
+    Json,
+}
+
+impl std::str::FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, String> {
+        match value {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(format!("Unknown log format: {} (expected text or json)", value)),
+        }
+    }
+}
+
+/// Event format for `LogFormat::Text`: the message alone, prefixed by
+/// severity for anything but info
+struct TextFormat;
+
+impl<S, N> FormatEvent<S, N> for TextFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(&self, ctx: &FmtContext<'_, S, N>, mut writer: Writer<'_>, event: &Event<'_>) -> fmt::Result {
+        match *event.metadata().level() {
+            Level::ERROR => write!(writer, "Error: ")?,
+            Level::WARN => write!(writer, "Warning: ")?,
+            Level::INFO => {}
+            level => write!(writer, "{}: ", level.as_str().to_lowercase())?,
+        }
+        ctx.field_format().format_fields(writer.by_ref(), event)?;
+        writeln!(writer)
+    }
+}
+
+/// Build a subscriber that writes events at `level` and above to `writer`
+pub fn subscriber<W>(level: LevelFilter, format: LogFormat, writer: W) -> Box<dyn Subscriber + Send + Sync>
+where
+    W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+{
+    let builder = tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_ansi(false)
+        .with_writer(writer);
+
+    match format {
+        LogFormat::Text => Box::new(builder.event_format(TextFormat).finish()),
+        LogFormat::Json => Box::new(
+            builder.json()
+                .flatten_event(true)
+                .with_current_span(false)
+                .with_span_list(false)
+                .finish(),
+        ),
+    }
+}
+
+/// Route `tracing` events to stderr at `level` and above, keeping stdout for
+/// command output
+pub fn init(level: LevelFilter, format: LogFormat) -> Result<(), String> {
+    tracing::subscriber::set_global_default(subscriber(level, format, std::io::stderr))
+        .map_err(|e| format!("Failed to initialize logging: {}", e))
+}
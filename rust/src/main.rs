@@ -1,24 +1,47 @@
 use clap::{Parser, Subcommand};
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::process;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio_util::sync::CancellationToken;
 
-mod config;
-mod fetcher;
-mod parser;
-mod storage;
-mod reporter;
-mod models;
+use feedpulse::browse;
+use feedpulse::config::{self, Config};
+use feedpulse::fetcher::{self, FetchLimits, Fetcher};
+use feedpulse::hooks;
+use feedpulse::logging::{self, LogFormat};
+use feedpulse::opml;
+use feedpulse::storage::{ItemQuery, Storage, StoreOutcome};
+use feedpulse::reporter::{self, Reporter};
+use feedpulse::watch::{self, ConfigWatcher, Schedule};
 
-use config::Config;
-use fetcher::Fetcher;
-use storage::Storage;
-use reporter::Reporter;
+/// Exit status after Ctrl+C (128 + SIGINT, as shells report it)
+const EXIT_INTERRUPTED: i32 = 130;
+
+/// Rows read from the database per page when streaming `items --format ndjson`
+const ITEMS_PAGE_SIZE: usize = 1000;
 
 #[derive(Parser)]
 #[command(name = "feedpulse")]
 #[command(version = "1.0.0")]
 #[command(about = "Concurrent Feed Aggregator CLI", long_about = None)]
 struct Cli {
+    /// Print errors to stderr as a JSON object instead of plain text
+    #[arg(long, global = true)]
+    json_errors: bool,
+    /// Seed every random choice in the run (User-Agent rotation, retry
+    /// jitter) so it can be reproduced exactly
+    #[arg(long, global = true)]
+    seed: Option<u64>,
+    /// Least severe progress/warning messages to print to stderr
+    #[arg(long, global = true, default_value = "info",
+          value_parser = ["off", "error", "warn", "info", "debug", "trace"])]
+    log_level: String,
+    /// Print progress and warnings as plain text or as one JSON object per line
+    #[arg(long, global = true, default_value = "text", value_parser = ["text", "json"])]
+    log_format: String,
     #[command(subcommand)]
     command: Commands,
 }
@@ -29,6 +52,32 @@ enum Commands {
     Fetch {
         #[arg(long, default_value = "config.yaml")]
         config: PathBuf,
+        /// Override retry_max for this run
+        #[arg(long)]
+        retries: Option<usize>,
+        /// Stop fetching remaining feeds as soon as any feed errors
+        #[arg(long)]
+        fail_fast: bool,
+        /// Per-request timeout for every feed this run, overriding default_timeout_secs and timeout_secs
+        #[arg(long)]
+        timeout: Option<u64>,
+        /// Give up on feeds still running this many seconds into the run
+        /// (overrides run_deadline_secs); finished feeds are still stored
+        #[arg(long)]
+        deadline: Option<u64>,
+        /// Fetch and parse but don't open or write the database
+        #[arg(long)]
+        dry_run: bool,
+        /// Report the run as human-readable log lines (text) or as one JSON
+        /// object on stdout (json)
+        #[arg(long, default_value = "text", value_parser = ["text", "json"])]
+        summary_format: String,
+    },
+    /// Keep running, re-fetching each feed whenever its refresh_interval_secs
+    /// elapses and storing every cycle's results, until Ctrl+C
+    Watch {
+        #[arg(long, default_value = "config.yaml")]
+        config: PathBuf,
     },
     /// Generate summary report
     Report {
@@ -38,86 +87,614 @@ enum Commands {
         format: String,
         #[arg(long)]
         source: Option<String>,
+        /// Only count fetches within this window (e.g. 24h, 7d) or after an RFC 3339 timestamp
         #[arg(long)]
         since: Option<String>,
+        /// Render tables at this many columns instead of the terminal width
+        #[arg(long)]
+        width: Option<u16>,
+        /// Only count each source's N most recent fetches (0 = no limit)
+        #[arg(long, conflicts_with_all = ["duplicates", "tags"])]
+        limit: Option<usize>,
+        /// Show stored item counts per time bucket (hour, day or week) instead
+        #[arg(long, value_parser = ["hour", "day", "week"])]
+        timeseries: Option<String>,
+        /// Split the timeseries by source
+        #[arg(long, requires = "timeseries")]
+        by_source: bool,
+        /// List URLs stored under more than one source instead
+        #[arg(long, conflicts_with = "timeseries")]
+        duplicates: bool,
+        /// List each failing source's most recent errors (table and json)
+        #[arg(long, conflicts_with_all = ["timeseries", "duplicates"])]
+        show_errors: bool,
+        /// Count only stored items with this tag, per source (repeat to require several)
+        #[arg(long = "tag", conflicts_with_all = ["timeseries", "duplicates"])]
+        tags: Vec<String>,
     },
     /// List configured sources and their status
     Sources {
         #[arg(long, default_value = "config.yaml")]
         config: PathBuf,
     },
+    /// Show recent fetch attempts from the fetch log
+    History {
+        #[arg(long, default_value = "config.yaml")]
+        config: PathBuf,
+        #[arg(long)]
+        source: Option<String>,
+        /// Number of most recent entries to show
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+        #[arg(long, default_value = "table")]
+        format: String,
+    },
+    /// List stored items (title, URL, timestamp), newest first
+    Items {
+        #[arg(long, default_value = "config.yaml")]
+        config: PathBuf,
+        #[arg(long)]
+        source: Option<String>,
+        /// Only include items stored within this window (e.g. 24h, 7d)
+        #[arg(long)]
+        since: Option<String>,
+        /// Show at most this many items
+        #[arg(long)]
+        limit: Option<usize>,
+        /// Only items with this tag (repeat to require several)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+        /// Show items from several sources with the same canonical URL once
+        #[arg(long)]
+        dedup: bool,
+        #[arg(long, default_value = "table")]
+        format: String,
+    },
+    /// Search stored item titles
+    Search {
+        #[arg(long, default_value = "config.yaml")]
+        config: PathBuf,
+        /// Words that must all appear in the title
+        query: String,
+        /// Show at most this many matches
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+        #[arg(long, default_value = "table")]
+        format: String,
+    },
+    /// Page through stored items interactively
+    Browse {
+        #[arg(long, default_value = "config.yaml")]
+        config: PathBuf,
+        #[arg(long)]
+        source: Option<String>,
+    },
+    /// Render recent items as a human-readable digest grouped by source
+    Digest {
+        #[arg(long, default_value = "config.yaml")]
+        config: PathBuf,
+        #[arg(long)]
+        source: Option<String>,
+        /// Only include items stored within this window (e.g. 24h, 7d)
+        #[arg(long)]
+        since: Option<String>,
+        #[arg(long, default_value = "markdown")]
+        format: String,
+    },
+    /// Check that stored item URLs are still reachable
+    CheckLinks {
+        #[arg(long, default_value = "config.yaml")]
+        config: PathBuf,
+        #[arg(long)]
+        source: Option<String>,
+        /// Check at most this many of the newest items
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// Export all stored items as JSON Lines or CSV, paging through the
+    /// database, or the configured feeds as OPML
+    Export {
+        #[arg(long, default_value = "config.yaml")]
+        config: PathBuf,
+        #[arg(long, default_value = "jsonl")]
+        format: String,
+        #[arg(long)]
+        source: Option<String>,
+        /// Rows read from the database per page
+        #[arg(long, default_value_t = 1000)]
+        page_size: usize,
+    },
+    /// Write a config file with the feeds from an OPML export (e.g. from another reader)
+    Import {
+        /// OPML file to read
+        opml: PathBuf,
+        /// Config file to create; an existing file is never overwritten
+        #[arg(long, default_value = "config.yaml")]
+        output: PathBuf,
+    },
+    /// Export raw fetch_log rows as JSON Lines or CSV
+    ExportLog {
+        #[arg(long, default_value = "config.yaml")]
+        config: PathBuf,
+        #[arg(long, default_value = "jsonl")]
+        format: String,
+        /// Only include fetches within this window (e.g. 24h, 7d)
+        #[arg(long)]
+        since: Option<String>,
+        #[arg(long)]
+        source: Option<String>,
+    },
+    /// Merge stored items that point at the same canonical URL
+    Dedupe {
+        #[arg(long, default_value = "config.yaml")]
+        config: PathBuf,
+    },
+    /// Delete items older than their source's retention_days, or with
+    /// --older-than every item and fetch log entry older than a cutoff
+    Prune {
+        #[arg(long, default_value = "config.yaml")]
+        config: PathBuf,
+        /// Delete items and fetch log entries older than this (e.g. 30d, 2w) or an RFC 3339 timestamp
+        #[arg(long)]
+        older_than: Option<String>,
+        /// Report what --older-than would delete without deleting it
+        #[arg(long, requires = "older_than")]
+        dry_run: bool,
+        /// Compact the database file afterwards (VACUUM); temporarily needs
+        /// free disk space roughly equal to the database size
+        #[arg(long, conflicts_with = "dry_run")]
+        vacuum: bool,
+    },
+    /// Check the config and, with --sample, each feed's parser against a live response
+    Doctor {
+        #[arg(long, default_value = "config.yaml")]
+        config: PathBuf,
+        /// Fetch every feed once and report which parser handled it
+        #[arg(long)]
+        sample: bool,
+    },
+    /// Show the effective configuration with defaults applied and secrets redacted
+    ShowConfig {
+        #[arg(long, default_value = "config.yaml")]
+        config: PathBuf,
+        #[arg(long, default_value = "yaml")]
+        format: String,
+    },
+    /// List sources present in the database, flagging ones no longer configured
+    DbSources {
+        #[arg(long, default_value = "config.yaml")]
+        config: PathBuf,
+    },
 }
 
 #[tokio::main]
 async fn main() {
     let cli = Cli::parse();
 
-    match cli.command {
-        Commands::Fetch { config } => {
-            if let Err(e) = run_fetch(config).await {
-                eprintln!("Error: {}", e);
-                process::exit(1);
-            }
+    // Both values are restricted by clap, so parsing can't fail
+    let level = cli.log_level.parse().unwrap_or(tracing_subscriber::filter::LevelFilter::INFO);
+    let format = cli.log_format.parse().unwrap_or(LogFormat::Text);
+    if let Err(e) = logging::init(level, format) {
+        print_error(&e, cli.json_errors);
+        process::exit(1);
+    }
+
+    let result = match cli.command {
+        Commands::Fetch { config, retries, fail_fast, timeout, deadline, dry_run, summary_format } => {
+            let options = FetchOptions { retries, fail_fast, timeout, deadline, dry_run, json_summary: summary_format == "json" };
+            run_fetch(config, options, cli.seed).await
         }
-        Commands::Report { config, format, source, since } => {
-            if let Err(e) = run_report(config, format, source, since).await {
-                eprintln!("Error: {}", e);
-                process::exit(1);
+        Commands::Watch { config } => run_watch(config, cli.seed).await,
+        Commands::Report { config, format, source, since, width, limit, timeseries, by_source, duplicates, show_errors, tags } => {
+            match timeseries {
+                Some(bucket) => run_timeseries(config, format, source, bucket, by_source, width),
+                None if duplicates => run_duplicates(config, format, source, since, width),
+                None => {
+                    let options = ReportOptions { since, width, limit, show_errors, tags };
+                    run_report(config, format, source, options).await
+                }
             }
         }
-        Commands::Sources { config } => {
-            if let Err(e) = run_sources(config).await {
-                eprintln!("Error: {}", e);
-                process::exit(1);
-            }
+        Commands::Sources { config } => run_sources(config).await,
+        Commands::History { config, source, limit, format } => run_history(config, source, limit, format),
+        Commands::Items { config, source, since, limit, tags, dedup, format } => {
+            let query = ItemQuery { source, since, limit, tags };
+            run_items(config, query, dedup, format)
         }
+        Commands::Search { config, query, limit, format } => run_search(config, query, limit, format),
+        Commands::Browse { config, source } => run_browse(config, source),
+        Commands::Digest { config, source, since, format } => run_digest(config, source, since, format),
+        Commands::CheckLinks { config, source, limit } => run_check_links(config, source, limit).await,
+        Commands::Export { config, format, source, page_size } => run_export(config, format, source, page_size),
+        Commands::Import { opml, output } => run_import(opml, output),
+        Commands::ExportLog { config, format, since, source } => run_export_log(config, format, since, source),
+        Commands::Dedupe { config } => run_dedupe(config),
+        Commands::Prune { config, older_than, dry_run, vacuum } => run_prune(config, older_than, dry_run, vacuum),
+        Commands::Doctor { config, sample } => run_doctor(config, sample, cli.seed).await,
+        Commands::ShowConfig { config, format } => run_show_config(config, format),
+        Commands::DbSources { config } => run_db_sources(config).await,
+    };
+
+    if let Err(e) = result {
+        print_error(&e, cli.json_errors);
+        process::exit(1);
+    }
+}
+
+fn print_error(message: &str, json: bool) {
+    if json {
+        let error = serde_json::json!({ "error": message });
+        eprintln!("{}", error);
+    } else {
+        eprintln!("Error: {}", message);
     }
 }
 
-async fn run_fetch(config_path: PathBuf) -> Result<(), String> {
+/// `fetch` flags
+struct FetchOptions {
+    retries: Option<usize>,
+    fail_fast: bool,
+    timeout: Option<u64>,
+    deadline: Option<u64>,
+    dry_run: bool,
+    json_summary: bool,
+}
+
+async fn run_fetch(config_path: PathBuf, options: FetchOptions, seed: Option<u64>) -> Result<(), String> {
+    let FetchOptions { retries, fail_fast, timeout, deadline, dry_run, json_summary } = options;
+
     // Load config
-    let config = Config::load(&config_path)?;
+    let mut config = Config::load(&config_path)?;
     
     config.validate()?;
 
-    // Initialize storage
-    let storage = Storage::new(&config.settings.database_path)
-        .map_err(|e| format!("Failed to initialize database: {}", e))?;
+    for feed in config.remove_unknown_feed_types() {
+        tracing::warn!(
+            "skipping feed '{}': unknown feed_type '{}'",
+            feed.name, feed.feed_type
+        );
+    }
+
+    if let Some(retries) = retries {
+        config::validate_retry_max(retries).map_err(|e| format!("--retries: {}", e))?;
+    }
+
+    if let Some(timeout) = timeout {
+        if timeout == 0 {
+            return Err("--timeout must be positive".to_string());
+        }
+        config.override_timeout(timeout);
+    }
+
+    if let Some(deadline) = deadline {
+        if deadline == 0 {
+            return Err("--deadline must be positive".to_string());
+        }
+        config.settings.run_deadline_secs = Some(deadline);
+    }
+
+    // Initialize storage; a dry run never creates, migrates or writes the
+    // database, but still reads fetch history from it when it can
+    let storage = if dry_run {
+        None
+    } else {
+        Some(Storage::new(&config.settings.database_path)
+            .map_err(|e| format!("Failed to initialize database: {}", e))?
+            .with_config(&config))
+    };
 
     // Fetch feeds
-    let fetcher = Fetcher::new(config.clone());
+    let mut fetcher = Fetcher::new(config.clone())
+        .with_retries(retries)
+        .with_fail_fast(fail_fast)
+        .with_seed(seed);
+    let history = match &storage {
+        Some(storage) => Some(load_history(storage)?),
+        None if Path::new(&config.settings.database_path).exists() => {
+            match Storage::open_read_only(&config.settings.database_path)
+                .and_then(|storage| load_history(&storage.with_config(&config)))
+            {
+                Ok(history) => Some(history),
+                Err(e) => {
+                    tracing::warn!("Dry run continues without fetch history: {}", e);
+                    None
+                }
+            }
+        }
+        None => None,
+    };
+    if let Some((fetched_sources, validators, open_circuits)) = history {
+        fetcher = fetcher
+            .with_fetched_sources(fetched_sources)
+            .with_cache_validators(validators)
+            .with_open_circuits(open_circuits);
+    }
+
+    // Ctrl+C cancels pending and in-flight feeds; whatever already finished
+    // is still stored below
+    let interrupted = CancellationToken::new();
+    tokio::spawn({
+        let interrupted = interrupted.clone();
+        let cancel = fetcher.cancellation_token();
+        async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                interrupted.cancel();
+                cancel.cancel();
+            }
+        }
+    });
+
     let mut results = fetcher.fetch_all().await;
 
     // Store results (updates new_items count)
-    storage.store_results(&mut results)
+    let outcome = storage.as_ref()
+        .map(|storage| storage.store_results(&mut results))
+        .transpose()
         .map_err(|e| format!("Failed to store results: {}", e))?;
 
-    // Print individual results
-    for result in &results {
-        fetcher::print_result(result);
+    if json_summary {
+        println!("{}", reporter::fetch_summary_json(&results, outcome.as_ref()));
+    } else {
+        for result in &results {
+            fetcher::print_result(result, dry_run);
+        }
+        print_fetch_summary(&results, outcome.as_ref());
+    }
+
+    let enabled = config.feeds.iter().filter(|feed| feed.enabled).count();
+    if interrupted.is_cancelled() {
+        tracing::warn!(
+            "Interrupted: {} of {} feeds completed{}, {} cancelled",
+            results.len(),
+            enabled,
+            if dry_run { "" } else { " and stored" },
+            enabled - results.len()
+        );
+        drop(storage);
+        process::exit(EXIT_INTERRUPTED);
+    }
+
+    if !dry_run {
+        run_hook(&config.settings, &results).await;
+    }
+
+    if fail_fast {
+        if let Some(failed) = results.iter().find(|r| r.error.is_some()) {
+            let cancelled = enabled - results.len();
+            return Err(format!(
+                "fail-fast: feed '{}' failed ({}), {} feed{} cancelled",
+                failed.source,
+                failed.error.as_deref().unwrap_or_default(),
+                cancelled,
+                if cancelled != 1 { "s" } else { "" }
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Run `post_fetch_hook`, if configured and allowed; its outcome never fails the fetch
+async fn run_hook(settings: &config::Settings, results: &[fetcher::FetchResult]) {
+    let Some(hook) = &settings.post_fetch_hook else {
+        return;
+    };
+    if !settings.allow_exec {
+        tracing::warn!("post_fetch_hook not run: set `allow_exec: true` to enable it");
+        return;
+    }
+    match hooks::run_post_fetch_hook(hook, results).await {
+        Ok(status) if status.success() => {}
+        Ok(status) => tracing::warn!("post_fetch_hook exited with {}", status),
+        Err(e) => tracing::warn!("{}", e),
+    }
+}
+
+async fn run_watch(config_path: PathBuf, seed: Option<u64>) -> Result<(), String> {
+    let mut config = watch::load_config(&config_path)?;
+
+    let mut storage = Storage::new(&config.settings.database_path)
+        .map_err(|e| format!("Failed to initialize database: {}", e))?
+        .with_config(&config);
+
+    let mut schedule = Schedule::new(&config.feeds, Instant::now());
+    if schedule.next_wakeup().is_none() {
+        return Err("no enabled feeds to watch".to_string());
+    }
+
+    // The first Ctrl+C lets in-flight fetches finish and be stored; a
+    // second one exits immediately
+    let shutdown = CancellationToken::new();
+    tokio::spawn({
+        let shutdown = shutdown.clone();
+        async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                tracing::info!("Stopping after in-flight fetches (Ctrl+C again to quit now)");
+                shutdown.cancel();
+                if tokio::signal::ctrl_c().await.is_ok() {
+                    process::exit(EXIT_INTERRUPTED);
+                }
+            }
+        }
+    });
+
+    let mut config_watcher = ConfigWatcher::new(&config_path)?;
+
+    tracing::info!("Watching feeds (Ctrl+C to stop)");
+
+    // Each due feed is fetched in its own task, all drawing from the same
+    // concurrency limits, so a slow feed doesn't hold back the others
+    let mut limits = Arc::new(FetchLimits::new(&config.settings));
+    let mut in_flight = tokio::task::JoinSet::new();
+    let mut started_at = HashMap::new();
+
+    while !shutdown.is_cancelled() {
+        let now = Instant::now();
+        for name in schedule.due(now) {
+            let fetcher = Fetcher::new(config.clone())
+                .with_only(HashSet::from([name.clone()]))
+                .with_seed(seed)
+                .with_fetched_sources(storage.get_fetched_sources()?)
+                .with_cache_validators(storage.get_fetch_cache()?)
+                .with_open_circuits(storage.get_open_circuits()?)
+                .with_limits(limits.clone());
+            schedule.mark_started(&name);
+            let task = in_flight.spawn(async move { fetcher.fetch_all().await });
+            started_at.insert(task.id(), (name, now));
+        }
+
+        // With no feeds left after a reload, wait for the next config change
+        let next = schedule.next_wakeup();
+        tokio::select! {
+            Some(joined) = in_flight.join_next_with_id() => {
+                let (id, results) = match joined {
+                    Ok((id, results)) => (id, Some(results)),
+                    Err(e) => {
+                        tracing::error!("Fetch task failed: {}", e);
+                        (e.id(), None)
+                    }
+                };
+                if let Some((name, started)) = started_at.remove(&id) {
+                    if let Some(results) = results {
+                        store_watch_results(&storage, &config, results).await;
+                    }
+                    schedule.mark_fetched(&name, started);
+                }
+            }
+            _ = tokio::time::sleep_until(tokio::time::Instant::from_std(next.unwrap_or(now))), if next.is_some() => {}
+            _ = config_watcher.changed() => {
+                // A config that fails to load or validate leaves the running one in place
+                match watch::load_config(&config_path) {
+                    Ok(reloaded) => {
+                        if reloaded.settings.database_path != config.settings.database_path {
+                            tracing::warn!("database_path changes take effect on restart");
+                        }
+                        schedule.update(&reloaded.feeds, Instant::now());
+                        limits = Arc::new(FetchLimits::new(&reloaded.settings));
+                        storage = storage.with_config(&reloaded);
+                        config = reloaded;
+                        tracing::info!("Reloaded config ({} feeds)", config.feeds.len());
+                    }
+                    Err(e) => tracing::error!("Config reload failed, keeping the previous config: {}", e),
+                }
+            }
+            _ = shutdown.cancelled() => {}
+        }
+    }
+
+    // Fetches already running finish and are stored before exiting
+    while let Some(joined) = in_flight.join_next().await {
+        match joined {
+            Ok(results) => store_watch_results(&storage, &config, results).await,
+            Err(e) => tracing::error!("Fetch task failed: {}", e),
+        }
     }
 
-    // Print summary
-    print_fetch_summary(&results);
+    tracing::info!("Stopped watching");
 
     Ok(())
 }
 
+/// Store, print and run the hook for one finished watch-mode fetch
+async fn store_watch_results(storage: &Storage, config: &Config, mut results: Vec<fetcher::FetchResult>) {
+    // A storage failure skips this fetch's results but keeps watching
+    match storage.store_results(&mut results) {
+        Ok(outcome) => {
+            for result in &results {
+                fetcher::print_result(result, false);
+            }
+            print_fetch_summary(&results, Some(&outcome));
+            run_hook(&config.settings, &results).await;
+        }
+        Err(e) => tracing::error!("Failed to store results: {}", e),
+    }
+}
+
+/// `report` flags for the per-source summary
+struct ReportOptions {
+    since: Option<String>,
+    width: Option<u16>,
+    limit: Option<usize>,
+    show_errors: bool,
+    tags: Vec<String>,
+}
+
 async fn run_report(
     config_path: PathBuf,
     format: String,
     source: Option<String>,
-    since: Option<String>,
+    options: ReportOptions,
 ) -> Result<(), String> {
+    let ReportOptions { since, width, limit, show_errors, tags } = options;
+    let cutoff = since.as_deref().map(reporter::parse_since).transpose()?;
+
     let config = Config::load(&config_path)?;
 
     let storage = Storage::new(&config.settings.database_path)
-        .map_err(|e| format!("Failed to initialize database: {}", e))?;
+        .map_err(|e| format!("Failed to initialize database: {}", e))?
+        .with_config(&config);
 
-    let reporter = Reporter::new(storage);
-    reporter.generate_report(&format, source.as_deref(), since.as_deref())
+    let reporter = Reporter::new(storage)
+        .with_width(width)
+        .with_limit(limit)
+        .with_show_errors(show_errors)
+        .with_tags(tags);
+    reporter.generate_report(&format, source.as_deref(), cutoff.as_deref())
         .map_err(|e| format!("Failed to generate report: {}", e))?;
 
+    for warning in reporter.cert_warnings(config.settings.cert_expiry_warn_days)? {
+        tracing::warn!(
+            "TLS certificate for '{}' expires {} ({} days)",
+            warning.source, warning.expires_at, warning.days_left
+        );
+    }
+
+    for warning in reporter.stale_warnings(config.settings.stale_body_threshold)? {
+        tracing::warn!(
+            "'{}' returned an identical response {} fetches in a row (stuck endpoint?)",
+            warning.source, warning.repeats
+        );
+    }
+
+    Ok(())
+}
+
+fn run_timeseries(
+    config_path: PathBuf,
+    format: String,
+    source: Option<String>,
+    bucket: String,
+    by_source: bool,
+    width: Option<u16>,
+) -> Result<(), String> {
+    let config = Config::load(&config_path)?;
+
+    let storage = Storage::new(&config.settings.database_path)
+        .map_err(|e| format!("Failed to initialize database: {}", e))?;
+
+    let reporter = Reporter::new(storage).with_width(width);
+    println!("{}", reporter.render_timeseries(&format, &bucket, source.as_deref(), by_source)?);
+
+    Ok(())
+}
+
+fn run_duplicates(
+    config_path: PathBuf,
+    format: String,
+    source: Option<String>,
+    since: Option<String>,
+    width: Option<u16>,
+) -> Result<(), String> {
+    let cutoff = since.as_deref().map(reporter::parse_since).transpose()?;
+    let config = Config::load(&config_path)?;
+
+    let storage = Storage::new(&config.settings.database_path)
+        .map_err(|e| format!("Failed to initialize database: {}", e))?
+        .with_config(&config);
+
+    let reporter = Reporter::new(storage).with_width(width);
+    println!("{}", reporter.render_duplicates(&format, source.as_deref(), cutoff.as_deref())?);
+
     Ok(())
 }
 
@@ -127,7 +704,8 @@ async fn run_sources(config_path: PathBuf) -> Result<(), String> {
     config.validate()?;
 
     let storage = Storage::new(&config.settings.database_path)
-        .map_err(|e| format!("Failed to initialize database: {}", e))?;
+        .map_err(|e| format!("Failed to initialize database: {}", e))?
+        .with_config(&config);
 
     let reporter = Reporter::new(storage);
     reporter.list_sources(&config)
@@ -136,13 +714,354 @@ async fn run_sources(config_path: PathBuf) -> Result<(), String> {
     Ok(())
 }
 
-fn print_fetch_summary(results: &[fetcher::FetchResult]) {
+fn run_history(
+    config_path: PathBuf,
+    source: Option<String>,
+    limit: usize,
+    format: String,
+) -> Result<(), String> {
+    let config = Config::load(&config_path)?;
+
+    let storage = Storage::new(&config.settings.database_path)
+        .map_err(|e| format!("Failed to initialize database: {}", e))?;
+
+    let reporter = Reporter::new(storage);
+    println!("{}", reporter.render_history(&format, source.as_deref(), limit)?);
+
+    Ok(())
+}
+
+/// `query.since` is the raw `--since` value
+fn run_items(config_path: PathBuf, query: ItemQuery, dedup: bool, format: String) -> Result<(), String> {
+    let since = query.since.as_deref().map(reporter::parse_since).transpose()?;
+    let query = ItemQuery { since, ..query };
+
+    let config = Config::load(&config_path)?;
+
+    let storage = Storage::new(&config.settings.database_path)
+        .map_err(|e| format!("Failed to initialize database: {}", e))?
+        .with_config(&config);
+
+    let reporter = Reporter::new(storage);
+    if format == "ndjson" {
+        let stdout = std::io::stdout();
+        reporter.write_items_ndjson(&mut stdout.lock(), &query, dedup, ITEMS_PAGE_SIZE)?;
+        return Ok(());
+    }
+
+    let output = if dedup {
+        reporter.render_deduped_items(&format, &query)?
+    } else {
+        reporter.render_items(&format, &query)?
+    };
+    println!("{}", output);
+
+    Ok(())
+}
+
+fn run_search(config_path: PathBuf, query: String, limit: usize, format: String) -> Result<(), String> {
+    let config = Config::load(&config_path)?;
+
+    let storage = Storage::new(&config.settings.database_path)
+        .map_err(|e| format!("Failed to initialize database: {}", e))?
+        .with_config(&config);
+
+    if !storage.has_full_text_search()? {
+        tracing::info!("Note: this SQLite build lacks FTS5; using a slower substring search");
+    }
+
+    let reporter = Reporter::new(storage);
+    println!("{}", reporter.render_search(&format, &query, limit)?);
+
+    Ok(())
+}
+
+fn run_browse(config_path: PathBuf, source: Option<String>) -> Result<(), String> {
+    let config = Config::load(&config_path)?;
+
+    let storage = Storage::new(&config.settings.database_path)
+        .map_err(|e| format!("Failed to initialize database: {}", e))?;
+
+    let items = storage.get_items(source.as_deref(), None)?;
+    browse::browse(&items)
+}
+
+fn run_digest(
+    config_path: PathBuf,
+    source: Option<String>,
+    since: Option<String>,
+    format: String,
+) -> Result<(), String> {
+    if format != "markdown" {
+        return Err(format!("Unknown digest format: {} (supported: markdown)", format));
+    }
+
+    let cutoff = since.as_deref().map(reporter::parse_since).transpose()?;
+
+    let config = Config::load(&config_path)?;
+
+    let storage = Storage::new(&config.settings.database_path)
+        .map_err(|e| format!("Failed to initialize database: {}", e))?;
+
+    let reporter = Reporter::new(storage);
+    print!("{}", reporter.render_digest(source.as_deref(), cutoff.as_deref())?);
+
+    Ok(())
+}
+
+async fn run_doctor(config_path: PathBuf, sample: bool, seed: Option<u64>) -> Result<(), String> {
+    let config = Config::load(&config_path)?;
+
+    config.validate()?;
+    println!("Config OK: {} feeds", config.feeds.len());
+
+    let storage = Storage::new(&config.settings.database_path)
+        .map_err(|e| format!("Failed to initialize database: {}", e))?;
+    for warning in Reporter::new(storage).gone_warnings(&config)? {
+        tracing::warn!("{}", warning);
+    }
+
+    if !sample {
+        return Ok(());
+    }
+
+    tracing::info!("Sampling feeds...");
+    for report in Fetcher::new(config).with_seed(seed).sample_all().await {
+        let selection = if report.inferred {
+            format!("{} (inferred from name)", report.parser)
+        } else {
+            report.parser.clone()
+        };
+
+        match &report.error {
+            Some(error) => println!("  ✗ {:<25} — parser: {}, error: {}", report.source, selection, error),
+            None => println!("  ✓ {:<25} — parser: {}, {} items", report.source, selection, report.items),
+        }
+
+        if let Some((parser, items)) = &report.suggestion {
+            println!("      consider setting `parser: {}` ({} items from this response)", parser, items);
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_check_links(
+    config_path: PathBuf,
+    source: Option<String>,
+    limit: Option<usize>,
+) -> Result<(), String> {
+    let config = Config::load(&config_path)?;
+
+    config.validate()?;
+
+    let storage = Storage::new(&config.settings.database_path)
+        .map_err(|e| format!("Failed to initialize database: {}", e))?;
+
+    let items = storage.query_items(&ItemQuery { source, limit, ..Default::default() })?;
+    let links: Vec<_> = items.into_iter()
+        .map(|item| (item.id, item.url))
+        .collect();
+
+    tracing::info!("Checking {} links...", links.len());
+    let checks = Fetcher::new(config).check_links(links).await;
+
+    let (mut ok, mut redirected, mut dead) = (0, 0, 0);
+    for check in &checks {
+        storage.set_link_status(&check.id, check.status.as_str())?;
+        match check.status {
+            fetcher::LinkStatus::Ok => ok += 1,
+            fetcher::LinkStatus::Redirected => redirected += 1,
+            fetcher::LinkStatus::Dead => {
+                dead += 1;
+                println!("  ✗ {} — {}", check.url, check.detail);
+            }
+        }
+    }
+
+    println!("\n{} ok, {} redirected, {} dead", ok, redirected, dead);
+
+    Ok(())
+}
+
+fn run_export(
+    config_path: PathBuf,
+    format: String,
+    source: Option<String>,
+    page_size: usize,
+) -> Result<(), String> {
+    let config = Config::load(&config_path)?;
+
+    if format == "opml" {
+        print!("{}", opml::render_opml(&config, source.as_deref()));
+        return Ok(());
+    }
+
+    let storage = Storage::new(&config.settings.database_path)
+        .map_err(|e| format!("Failed to initialize database: {}", e))?;
+
+    let reporter = Reporter::new(storage);
+    let stdout = std::io::stdout();
+    let mut out = std::io::BufWriter::new(stdout.lock());
+    let count = reporter.export_items(&mut out, &format, source.as_deref(), page_size)?;
+    std::io::Write::flush(&mut out).map_err(|e| format!("Failed to write export: {}", e))?;
+
+    tracing::info!("Exported {} items", count);
+
+    Ok(())
+}
+
+fn run_import(opml_path: PathBuf, output: PathBuf) -> Result<(), String> {
+    let body = fs::read_to_string(&opml_path)
+        .map_err(|e| format!("Failed to read {}: {}", opml_path.display(), e))?;
+    let feeds = opml::parse_opml(&body)?;
+    let rendered = opml::render_config(&feeds)?;
+
+    let mut file = fs::OpenOptions::new().write(true).create_new(true).open(&output)
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::AlreadyExists => format!("{} already exists; choose another --output", output.display()),
+            _ => format!("Failed to create {}: {}", output.display(), e),
+        })?;
+    std::io::Write::write_all(&mut file, rendered.as_bytes())
+        .map_err(|e| format!("Failed to write {}: {}", output.display(), e))?;
+
+    let json = feeds.iter().filter(|f| f.feed_type == "json").count();
+    println!("Imported {} feeds ({} rss, {} json) into {}", feeds.len(), feeds.len() - json, json, output.display());
+
+    Ok(())
+}
+
+fn run_export_log(
+    config_path: PathBuf,
+    format: String,
+    since: Option<String>,
+    source: Option<String>,
+) -> Result<(), String> {
+    let cutoff = since.as_deref().map(reporter::parse_since).transpose()?;
+
+    let config = Config::load(&config_path)?;
+
+    let storage = Storage::new(&config.settings.database_path)
+        .map_err(|e| format!("Failed to initialize database: {}", e))?;
+
+    let reporter = Reporter::new(storage);
+    let stdout = std::io::stdout();
+    let mut out = std::io::BufWriter::new(stdout.lock());
+    let count = reporter.export_fetch_log(&mut out, &format, source.as_deref(), cutoff.as_deref())?;
+    std::io::Write::flush(&mut out).map_err(|e| format!("Failed to write export: {}", e))?;
+
+    tracing::info!("Exported {} fetch log rows", count);
+
+    Ok(())
+}
+
+fn run_dedupe(config_path: PathBuf) -> Result<(), String> {
+    let config = Config::load(&config_path)?;
+
+    let storage = Storage::new(&config.settings.database_path)
+        .map_err(|e| format!("Failed to initialize database: {}", e))?;
+
+    let merged = storage.dedupe()?;
+    println!("Merged {} duplicate items", merged);
+
+    Ok(())
+}
+
+fn run_prune(config_path: PathBuf, older_than: Option<String>, dry_run: bool, vacuum: bool) -> Result<(), String> {
+    let cutoff = older_than.as_deref()
+        .map(|value| reporter::parse_since(value).map_err(|_| format!(
+            "invalid --older-than value '{}' (expected e.g. 12h, 30d, 2w or an RFC 3339 timestamp)", value
+        )))
+        .transpose()?;
+
+    let config = Config::load(&config_path)?;
+
+    config.validate()?;
+
+    let storage = Storage::new(&config.settings.database_path)
+        .map_err(|e| format!("Failed to initialize database: {}", e))?
+        .with_config(&config);
+
+    if let Some(cutoff) = cutoff {
+        let (items, log_entries) = storage.prune(&cutoff, dry_run)?;
+        if dry_run {
+            println!("Would delete {} items and {} fetch log entries older than {} (dry run — nothing deleted)", items, log_entries, cutoff);
+        } else {
+            println!("Deleted {} items and {} fetch log entries older than {}", items, log_entries, cutoff);
+        }
+    } else {
+        let pruned = storage.prune_expired()?;
+        for (source, deleted) in &pruned {
+            println!("  {:<25} — {} items deleted", source, deleted);
+        }
+        let total: usize = pruned.iter().map(|(_, deleted)| deleted).sum();
+        println!("Pruned {} items", total);
+    }
+
+    // Runs after the prune has committed: VACUUM fails inside a transaction
+    if vacuum {
+        let size_before = fs::metadata(&config.settings.database_path).map(|m| m.len()).ok();
+        let start = std::time::Instant::now();
+        storage.vacuum()?;
+        let size_after = fs::metadata(&config.settings.database_path).map(|m| m.len()).ok();
+
+        match (size_before, size_after) {
+            (Some(before), Some(after)) => println!(
+                "Vacuumed database in {}ms ({} → {} bytes)",
+                start.elapsed().as_millis(), before, after
+            ),
+            _ => println!("Vacuumed database in {}ms", start.elapsed().as_millis()),
+        }
+    }
+
+    Ok(())
+}
+
+fn run_show_config(config_path: PathBuf, format: String) -> Result<(), String> {
+    let config = Config::load(&config_path)?;
+
+    print!("{}", config.render_resolved(&format)?);
+
+    Ok(())
+}
+
+async fn run_db_sources(config_path: PathBuf) -> Result<(), String> {
+    let config = Config::load(&config_path)?;
+
+    let storage = Storage::new(&config.settings.database_path)
+        .map_err(|e| format!("Failed to initialize database: {}", e))?;
+
+    let reporter = Reporter::new(storage);
+    reporter.list_db_sources(&config)
+        .map_err(|e| format!("Failed to list database sources: {}", e))?;
+
+    Ok(())
+}
+
+/// What earlier runs left behind that changes how feeds are fetched:
+/// previously fetched sources, cache validators and open circuits
+type FetchHistory = (HashSet<String>, HashMap<String, fetcher::CacheValidators>, HashMap<String, usize>);
+
+fn load_history(storage: &Storage) -> Result<FetchHistory, String> {
+    Ok((storage.get_fetched_sources()?, storage.get_fetch_cache()?, storage.get_open_circuits()?))
+}
+
+/// `outcome` is None for a dry run
+fn print_fetch_summary(results: &[fetcher::FetchResult], outcome: Option<&StoreOutcome>) {
     let total = results.len();
     let succeeded = results.iter().filter(|r| r.error.is_none()).count();
-    let total_items: usize = results.iter().map(|r| r.items.len()).sum();
-    let new_items: usize = results.iter().map(|r| r.new_items).sum();
     let errors = total - succeeded;
+    let not_modified = results.iter().filter(|r| r.not_modified).count();
+    let not_modified = if not_modified > 0 { format!(", {} not modified", not_modified) } else { String::new() };
+    let plural = if errors != 1 { "s" } else { "" };
 
-    println!("\nDone: {}/{} succeeded, {} items ({} new), {} error{}",
-        succeeded, total, total_items, new_items, errors, if errors != 1 { "s" } else { "" });
+    match outcome {
+        Some(outcome) => tracing::info!("Done: {}/{} succeeded{}, {} items ({} new, {} updated), {} error{}",
+            succeeded, total, not_modified, outcome.total, outcome.new, outcome.updated, errors, plural),
+        None => {
+            let items: usize = results.iter().map(|r| r.items.len()).sum();
+            tracing::info!("Done: {}/{} succeeded{}, {} items parsed, {} error{} (dry run — nothing stored)",
+                succeeded, total, not_modified, items, errors, plural);
+        }
+    }
 }
@@ -1,5 +1,126 @@
 use serde::{Deserialize, Serialize};
 use sha2::{Sha256, Digest};
+use std::collections::{BTreeMap, HashMap};
+use url::Url;
+
+/// Reading speed assumed for reading-time estimates
+const WORDS_PER_MINUTE: usize = 200;
+
+/// Rough reading time of a text (HTML tags ignored) in whole minutes,
+/// rounded up; None when it has no words
+pub fn reading_time_minutes(text: &str) -> Option<u32> {
+    let mut plain = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                plain.push(' ');
+            }
+            _ if !in_tag => plain.push(c),
+            _ => {}
+        }
+    }
+
+    let words = plain.split_whitespace().count();
+    (words > 0).then(|| words.div_ceil(WORDS_PER_MINUTE) as u32)
+}
+
+/// Normalize a URL so trivially different links to the same page compare
+/// equal: on top of `normalize_url` (fragment and `tracking_params`), drops
+/// a leading `www.` and a trailing slash, and sorts the remaining query
+/// parameters
+pub fn canonical_url(raw: &str, tracking_params: &[String]) -> String {
+    let raw = raw.trim();
+    let Ok(mut url) = Url::parse(&normalize_url(raw, tracking_params)) else {
+        return raw.to_string();
+    };
+
+    if let Some(host) = url.host_str().and_then(|h| h.strip_prefix("www.")).map(str::to_string) {
+        let _ = url.set_host(Some(&host));
+    }
+
+    let mut query: Vec<(String, String)> = url.query_pairs()
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    query.sort();
+    if query.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(&query);
+    }
+
+    if url.path().len() > 1 && url.path().ends_with('/') {
+        let trimmed = url.path().trim_end_matches('/').to_string();
+        url.set_path(&trimmed);
+    }
+
+    url.to_string()
+}
+
+/// Remove query parameters matching `tracking_params` (a trailing `*` is a
+/// prefix match) and the fragment, keeping the remaining parameters in their
+/// original order and encoding. URLs with nothing to strip, or that don't
+/// parse, come back unchanged
+pub fn normalize_url(raw: &str, tracking_params: &[String]) -> String {
+    let Ok(mut url) = Url::parse(raw.trim()) else {
+        return raw.to_string();
+    };
+
+    let mut changed = url.fragment().is_some();
+    url.set_fragment(None);
+
+    if let Some(query) = url.query() {
+        let kept: Vec<&str> = query.split('&')
+            .filter(|pair| {
+                let key = pair.split('=').next().unwrap_or_default();
+                !pair.is_empty() && !is_tracking_param(key, tracking_params)
+            })
+            .collect();
+        if kept.len() != query.split('&').count() {
+            changed = true;
+            let kept = kept.join("&");
+            url.set_query((!kept.is_empty()).then_some(kept.as_str()));
+        }
+    }
+
+    if changed { url.to_string() } else { raw.to_string() }
+}
+
+fn is_tracking_param(key: &str, tracking_params: &[String]) -> bool {
+    tracking_params.iter().any(|param| match param.strip_suffix('*') {
+        Some(prefix) => key.starts_with(prefix),
+        None => key == param,
+    })
+}
+
+/// Collapse items that share a canonical URL into the first of them (the
+/// newest, for items listed newest first), paired with every source that
+/// carried the URL in order of appearance
+pub fn group_by_canonical_url(items: Vec<FeedItem>, tracking_params: &[String]) -> Vec<(FeedItem, Vec<String>)> {
+    let mut groups: Vec<(FeedItem, Vec<String>)> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    for item in items {
+        let key = canonical_url(&item.url, tracking_params);
+        match index.get(&key) {
+            Some(&i) => {
+                let sources = &mut groups[i].1;
+                if !sources.contains(&item.source) {
+                    sources.push(item.source);
+                }
+            }
+            None => {
+                index.insert(key, groups.len());
+                let sources = vec![item.source.clone()];
+                groups.push((item, sources));
+            }
+        }
+    }
+
+    groups
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FeedItem {
@@ -13,6 +134,11 @@ pub struct FeedItem {
     pub tags: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub raw_data: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// Estimated minutes to read the summary, None without one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reading_time_minutes: Option<u32>,
 }
 
 impl FeedItem {
@@ -26,6 +152,8 @@ impl FeedItem {
             timestamp: None,
             tags: Vec::new(),
             raw_data: None,
+            summary: None,
+            reading_time_minutes: None,
         }
     }
 
@@ -50,9 +178,58 @@ impl FeedItem {
         self.raw_data = Some(raw_data);
         self
     }
+
+    /// Set the summary/content and its reading-time estimate
+    pub fn with_summary(mut self, summary: Option<String>) -> Self {
+        self.summary = summary.filter(|s| !s.trim().is_empty());
+        self.reading_time_minutes = self.summary.as_deref().and_then(reading_time_minutes);
+        self
+    }
+}
+
+/// Outcome of one fetch as recorded in fetch_log's `status` column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FetchStatus {
+    Success,
+    /// Fetched and stored, but suspicious (fewer items than the feed's
+    /// `min_expected_items`)
+    Degraded,
+    /// The request failed (network, timeout, HTTP status)
+    Error,
+    /// A response arrived but couldn't be used (unparseable body, missing
+    /// `expect_key`, wrong Content-Type, undecodable compression)
+    ParseError,
+}
+
+impl FetchStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FetchStatus::Success => "success",
+            FetchStatus::Degraded => "degraded",
+            FetchStatus::Error => "error",
+            FetchStatus::ParseError => "parse_error",
+        }
+    }
+
+    /// The status stored as `value` in fetch_log
+    pub fn from_db(value: &str) -> Option<Self> {
+        [FetchStatus::Success, FetchStatus::Degraded, FetchStatus::Error, FetchStatus::ParseError]
+            .into_iter()
+            .find(|status| status.as_str() == value)
+    }
+
+    /// Human-readable form for tables and progress lines
+    pub fn label(&self) -> &'static str {
+        match self {
+            FetchStatus::Success => "success",
+            FetchStatus::Degraded => "degraded",
+            FetchStatus::Error => "fetch error",
+            FetchStatus::ParseError => "parse error",
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct FetchLog {
     pub source: String,
     pub fetched_at: String,
@@ -60,4 +237,11 @@ pub struct FetchLog {
     pub items_count: usize,
     pub error_message: Option<String>,
     pub duration_ms: u64,
+    pub captured_headers: BTreeMap<String, String>,
+    pub cert_expires_at: Option<String>,
+    pub body_hash: Option<String>,
+    pub gone: bool,
+    pub mirror_url: Option<String>,
+    /// Items the parser skipped (e.g. missing a URL)
+    pub skipped_count: usize,
 }
@@ -0,0 +1,149 @@
+use crate::config::Config;
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::Serialize;
+
+/// Separator between folder and feed titles in imported names
+const FOLDER_SEPARATOR: &str = " / ";
+
+/// A feed read from an OPML `<outline>`, as written to the generated config
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OpmlFeed {
+    pub name: String,
+    pub url: String,
+    pub feed_type: String,
+}
+
+#[derive(Serialize)]
+struct ImportedConfig<'a> {
+    feeds: &'a [OpmlFeed],
+}
+
+/// Read every `<outline>` with an `xmlUrl` from an OPML document. Outlines
+/// without one are folders: their feeds are flattened with the folder titles
+/// prefixed to the name. Repeated names get a ` (2)`, ` (3)`... suffix
+pub fn parse_opml(body: &str) -> Result<Vec<OpmlFeed>, String> {
+    let mut reader = Reader::from_str(body);
+    reader.config_mut().trim_text(true);
+
+    let mut feeds: Vec<OpmlFeed> = Vec::new();
+    // Titles of the open outlines; None for feed outlines, which never prefix
+    let mut folders: Vec<Option<String>> = Vec::new();
+
+    loop {
+        let event = reader.read_event()
+            .map_err(|e| format!("malformed OPML at position {}: {}", reader.buffer_position(), e))?;
+
+        match event {
+            Event::Start(start) if start.local_name().as_ref() == b"outline" => {
+                let feed = outline_feed(&start, &folders);
+                folders.push(match feed {
+                    Some(feed) => {
+                        push_unique(&mut feeds, feed);
+                        None
+                    }
+                    None => outline_title(&start),
+                });
+            }
+            Event::Empty(start) if start.local_name().as_ref() == b"outline" => {
+                if let Some(feed) = outline_feed(&start, &folders) {
+                    push_unique(&mut feeds, feed);
+                }
+            }
+            Event::End(end) if end.local_name().as_ref() == b"outline" => {
+                folders.pop();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if feeds.is_empty() {
+        return Err("no feeds found in OPML (no <outline> has an xmlUrl)".to_string());
+    }
+    Ok(feeds)
+}
+
+/// Render imported feeds as a config file; every other option keeps its default
+pub fn render_config(feeds: &[OpmlFeed]) -> Result<String, String> {
+    serde_yaml::to_string(&ImportedConfig { feeds })
+        .map_err(|e| format!("Failed to render config: {}", e))
+}
+
+/// Render the configured feeds as an OPML 2.0 document with one `<outline>`
+/// per feed, optionally only the feed named `source`. Passwords embedded in
+/// feed URLs are redacted
+pub fn render_opml(config: &Config, source: Option<&str>) -> String {
+    let config = config.redacted();
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<opml version=\"2.0\">\n");
+    out.push_str("  <head>\n    <title>feedpulse feeds</title>\n  </head>\n  <body>\n");
+    for feed in config.feeds.iter().filter(|f| source.is_none_or(|s| f.name == s)) {
+        let name = escape(feed.name.as_str());
+        out.push_str(&format!(
+            "    <outline text=\"{}\" title=\"{}\" type=\"{}\" xmlUrl=\"{}\"/>\n",
+            name,
+            name,
+            escape(feed.feed_type.as_str()),
+            escape(feed.url.as_str()),
+        ));
+    }
+    out.push_str("  </body>\n</opml>\n");
+    out
+}
+
+/// `json` for URLs that clearly point at a JSON document, `rss` otherwise
+/// (the parser reads Atom feeds configured as `rss` too)
+pub fn infer_feed_type(url: &str) -> &'static str {
+    let lower = url.to_ascii_lowercase();
+    let path = lower.split(['?', '#']).next().unwrap_or_default();
+    let query = lower.split_once('?').map(|(_, q)| q).unwrap_or_default();
+    if path.ends_with(".json") || query.split('&').any(|pair| pair == "format=json") {
+        "json"
+    } else {
+        "rss"
+    }
+}
+
+fn outline_feed(start: &BytesStart, folders: &[Option<String>]) -> Option<OpmlFeed> {
+    let url = attribute(start, "xmlUrl")?.trim().to_string();
+    if url.is_empty() {
+        return None;
+    }
+    let title = outline_title(start).unwrap_or_else(|| url.clone());
+    let name = folders.iter()
+        .flatten()
+        .map(String::as_str)
+        .chain(std::iter::once(title.as_str()))
+        .collect::<Vec<_>>()
+        .join(FOLDER_SEPARATOR);
+    let feed_type = infer_feed_type(&url).to_string();
+    Some(OpmlFeed { name, url, feed_type })
+}
+
+/// `title`, falling back to the required-but-often-terse `text`
+fn outline_title(start: &BytesStart) -> Option<String> {
+    ["title", "text"].iter()
+        .filter_map(|key| attribute(start, key))
+        .map(|value| value.trim().to_string())
+        .find(|value| !value.is_empty())
+}
+
+/// Attribute lookup ignoring case, since exporters disagree on `xmlUrl`/`xmlurl`
+fn attribute(start: &BytesStart, key: &str) -> Option<String> {
+    start.attributes()
+        .filter_map(|a| a.ok())
+        .find(|a| a.key.local_name().as_ref().eq_ignore_ascii_case(key.as_bytes()))
+        .and_then(|a| a.unescape_value().ok())
+        .map(|v| v.to_string())
+}
+
+fn push_unique(feeds: &mut Vec<OpmlFeed>, mut feed: OpmlFeed) {
+    let base = feed.name.clone();
+    let mut n = 1;
+    while feeds.iter().any(|f| f.name == feed.name) {
+        n += 1;
+        feed.name = format!("{} ({})", base, n);
+    }
+    feeds.push(feed);
+}
@@ -1,33 +1,545 @@
-use crate::models::FeedItem;
+use crate::config::{Feed, JsonParser, Settings};
+use crate::models::{normalize_url, FeedItem};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
 use serde_json::Value;
 
+/// Timestamp fields tried, in order, by the generic JSON parser
+const GENERIC_TIMESTAMP_FIELDS: &[&str] = &["published", "date", "created_at", "updated", "pubDate"];
+
+/// JSON parser kinds, in the order name-based detection checks them
+pub const JSON_PARSERS: &[&str] = &["hackernews", "github", "reddit", "lobsters", "generic"];
+
+/// Per-feed options that influence parsing
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    /// Explicit parser for JSON bodies, overriding name-based detection
+    pub parser: Option<JsonParser>,
+    /// Explicit timestamp field (a `lookup_path` path) for generic JSON items
+    pub timestamp_field: Option<String>,
+    /// Paths to the title and URL within each generic JSON item, replacing
+    /// the usual `title`/`name`/`headline` and `url`/`link`/`href` keys
+    pub title_path: Option<String>,
+    pub url_path: Option<String>,
+    /// Stop after this many items (XML feeds stop reading early)
+    pub max_items: Option<usize>,
+    /// Prefix tags with their kind (`sub:`, `flair:`, or an RSS category's
+    /// `domain`/Atom `scheme`)
+    pub namespaced_tags: bool,
+    /// Dotted path to the item array for the built-in GitHub/Reddit parsers
+    /// (empty for a top-level array)
+    pub items_path: Option<String>,
+    /// Tracking parameters stripped from item URLs (with the fragment)
+    /// before ids are derived; `None` leaves URLs untouched
+    pub strip_tracking_params: Option<Vec<String>>,
+}
+
+impl ParseOptions {
+    pub fn from_feed(feed: &Feed, settings: &Settings) -> Self {
+        Self {
+            parser: feed.parser,
+            timestamp_field: feed.timestamp_field.clone(),
+            title_path: feed.title_path.clone(),
+            url_path: feed.url_path.clone(),
+            max_items: feed.max_items_per_fetch,
+            items_path: feed.items_path.clone(),
+            namespaced_tags: settings.namespaced_tags,
+            strip_tracking_params: settings.normalize_urls.then(|| settings.tracking_params.clone()),
+        }
+    }
+}
+
+/// Fields collected from a single RSS `<item>` or Atom `<entry>`
+#[derive(Default)]
+struct XmlEntry {
+    title: Option<String>,
+    link: Option<String>,
+    alternate_link: Option<String>,
+    updated: Option<String>,
+    published: Option<String>,
+    summary: Option<String>,
+    content: Option<String>,
+    /// Category values with their RSS `domain` / Atom `scheme`, if any
+    categories: Vec<(Option<String>, String)>,
+    /// `domain` of the RSS `<category>` whose text comes next
+    category_domain: Option<String>,
+}
+
+/// Parsed items plus one message per item that was skipped (e.g. missing
+/// its URL), in feed order
+#[derive(Debug, Clone, Default)]
+pub struct ParseOutcome {
+    pub items: Vec<FeedItem>,
+    pub warnings: Vec<String>,
+}
+
 pub struct Parser;
 
 impl Parser {
     pub fn parse(source: &str, feed_type: &str, body: &str) -> Result<Vec<FeedItem>, String> {
-        match feed_type {
-            "json" => Self::parse_json(source, body),
-            "rss" | "atom" => Err("RSS/Atom parsing not yet implemented".to_string()),
-            _ => Err(format!("Unknown feed type: {}", feed_type)),
+        Self::parse_with_options(source, feed_type, body, &ParseOptions::default())
+    }
+
+    /// Parse and log a warning for each skipped item
+    pub fn parse_with_options(
+        source: &str,
+        feed_type: &str,
+        body: &str,
+        options: &ParseOptions,
+    ) -> Result<Vec<FeedItem>, String> {
+        let outcome = Self::parse_outcome(source, feed_type, body, options)?;
+        log_warnings(&outcome.warnings);
+        Ok(outcome.items)
+    }
+
+    /// Parse, returning skipped-item warnings alongside the items instead of
+    /// logging them
+    pub fn parse_outcome(
+        source: &str,
+        feed_type: &str,
+        body: &str,
+        options: &ParseOptions,
+    ) -> Result<ParseOutcome, String> {
+        let mut warnings = Vec::new();
+        let mut items = match feed_type {
+            "json" => {
+                let parser = Self::json_parser_for(source, options);
+                let mut items = Self::parse_json_with_warnings(parser, source, body, options, &mut warnings)?;
+                if let Some(max) = options.max_items {
+                    items.truncate(max);
+                }
+                items
+            }
+            "rss" | "atom" => Self::parse_xml(source, body, options, &mut warnings)?,
+            _ => return Err(format!("Unknown feed type: {}", feed_type)),
+        };
+
+        if let Some(params) = &options.strip_tracking_params {
+            for item in &mut items {
+                let url = normalize_url(&item.url, params);
+                if url != item.url {
+                    item.id = FeedItem::generate_id(&item.source, &url);
+                    item.url = url;
+                }
+            }
         }
+        Ok(ParseOutcome { items, warnings })
     }
 
-    fn parse_json(source: &str, body: &str) -> Result<Vec<FeedItem>, String> {
-        let json: Value = serde_json::from_str(body)
-            .map_err(|e| format!("malformed JSON: {}", e))?;
+    /// Stream RSS `<item>` and Atom `<entry>` elements without building a
+    /// DOM, emitting each item as its element closes
+    fn parse_xml(source: &str, body: &str, options: &ParseOptions, warnings: &mut Vec<String>) -> Result<Vec<FeedItem>, String> {
+        let mut reader = Reader::from_str(body);
+        reader.config_mut().trim_text(true);
+
+        let mut items = Vec::new();
+        let mut entry: Option<XmlEntry> = None;
+        let mut entry_idx = 0;
+        // Element path below the current entry, e.g. ["title"]
+        let mut path: Vec<String> = Vec::new();
+
+        loop {
+            let event = reader.read_event()
+                .map_err(|e| format!("malformed XML at position {}: {}", reader.buffer_position(), e))?;
+
+            match event {
+                Event::Start(start) => {
+                    let name = Self::local_name(&start);
+                    match entry.as_mut() {
+                        Some(current) => {
+                            if path.is_empty() {
+                                Self::read_entry_attributes(current, &name, &start);
+                            }
+                            path.push(name);
+                        }
+                        None if name == "item" || name == "entry" => {
+                            entry = Some(XmlEntry::default());
+                        }
+                        None => {}
+                    }
+                }
+                Event::Empty(start) => {
+                    if let Some(current) = entry.as_mut() {
+                        if path.is_empty() {
+                            let name = Self::local_name(&start);
+                            Self::read_entry_attributes(current, &name, &start);
+                        }
+                    }
+                }
+                Event::Text(text) => {
+                    if let (Some(current), [name]) = (entry.as_mut(), path.as_slice()) {
+                        let value = text.unescape()
+                            .map_err(|e| format!("malformed XML text: {}", e))?;
+                        Self::read_entry_text(current, name, value.trim());
+                    }
+                }
+                Event::CData(data) => {
+                    if let (Some(current), [name]) = (entry.as_mut(), path.as_slice()) {
+                        let value = String::from_utf8_lossy(&data);
+                        Self::read_entry_text(current, name, value.trim());
+                    }
+                }
+                Event::End(_) => {
+                    // Closing a child element, or an element outside any entry
+                    if path.pop().is_some() {
+                        continue;
+                    }
+                    let Some(finished) = entry.take() else {
+                        continue;
+                    };
+
+                    match Self::build_xml_item(source, finished, options) {
+                        Ok(item) => items.push(item),
+                        Err(missing) => warnings.push(format!("{} item {}: {}", source, entry_idx, missing)),
+                    }
+                    entry_idx += 1;
+
+                    if options.max_items.is_some_and(|max| items.len() >= max) {
+                        break;
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        Ok(items)
+    }
+
+    fn local_name(start: &BytesStart) -> String {
+        String::from_utf8_lossy(start.local_name().as_ref()).to_string()
+    }
+
+    fn attribute(start: &BytesStart, key: &str) -> Option<String> {
+        start.attributes()
+            .filter_map(|a| a.ok())
+            .find(|a| a.key.local_name().as_ref() == key.as_bytes())
+            .and_then(|a| a.unescape_value().ok())
+            .map(|v| v.to_string())
+    }
+
+    fn read_entry_attributes(entry: &mut XmlEntry, name: &str, start: &BytesStart) {
+        match name {
+            "link" => {
+                if let Some(href) = Self::attribute(start, "href") {
+                    let rel = Self::attribute(start, "rel");
+                    if matches!(rel.as_deref(), None | Some("alternate")) && entry.alternate_link.is_none() {
+                        entry.alternate_link = Some(href.clone());
+                    }
+                    if entry.link.is_none() {
+                        entry.link = Some(href);
+                    }
+                }
+            }
+            "category" => {
+                match Self::attribute(start, "term") {
+                    Some(term) => entry.categories.push((Self::attribute(start, "scheme"), term)),
+                    None => entry.category_domain = Self::attribute(start, "domain"),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn read_entry_text(entry: &mut XmlEntry, name: &str, value: &str) {
+        if value.is_empty() {
+            return;
+        }
+
+        match name {
+            "title" => entry.title = Some(value.to_string()),
+            "link" if entry.link.is_none() => entry.link = Some(value.to_string()),
+            "pubDate" | "published" | "date" => entry.published = Some(value.to_string()),
+            "updated" => entry.updated = Some(value.to_string()),
+            "category" => {
+                let domain = entry.category_domain.take();
+                entry.categories.push((domain, value.to_string()));
+            }
+            "description" | "summary" if entry.summary.is_none() => entry.summary = Some(value.to_string()),
+            "content" | "encoded" if entry.content.is_none() => entry.content = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    /// Item for a finished entry; an untitled entry is titled with its link,
+    /// and one without a link is skipped, naming what was missing
+    fn build_xml_item(source: &str, entry: XmlEntry, options: &ParseOptions) -> Result<FeedItem, String> {
+        let Some(url) = entry.alternate_link.or(entry.link) else {
+            return Err(match entry.title {
+                Some(_) => "missing link".to_string(),
+                None => "missing title and link".to_string(),
+            });
+        };
+        let title = entry.title.unwrap_or_else(|| url.clone());
+
+        let timestamp = entry.updated.or(entry.published)
+            .and_then(|ts| Self::normalize_timestamp(&ts));
+
+        let tags = entry.categories.into_iter()
+            .map(|(domain, value)| match domain {
+                Some(domain) if options.namespaced_tags => format!("{}:{}", domain, value),
+                _ => value,
+            })
+            .collect();
+
+        Ok(
+            FeedItem::new(title, url, source.to_string())
+                .with_timestamp(timestamp)
+                .with_tags(tags)
+                .with_summary(entry.summary.or(entry.content))
+        )
+    }
+
+    /// Look up a dotted path (`data.children.0.title`) in a JSON value;
+    /// numeric segments index into arrays, also written JSONPath-style
+    /// (`$.data.children[0].title`). A key equal to the whole path wins, so
+    /// plain field names containing dots or brackets still match; a leading
+    /// `/` selects RFC 6901 JSON Pointer syntax (`/data/children`)
+    pub fn lookup_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+        if path.starts_with('/') {
+            return value.pointer(path);
+        }
+        if let Some(exact) = value.get(path) {
+            return Some(exact);
+        }
+
+        let path = path.strip_prefix('$').unwrap_or(path).replace('[', ".").replace(']', "");
+        path.split('.')
+            .filter(|segment| !segment.is_empty())
+            .try_fold(value, |current, segment| match current {
+                Value::Array(arr) => segment.parse::<usize>().ok().and_then(|i| arr.get(i)),
+                _ => current.get(segment),
+            })
+    }
+
+    /// Trim and collapse runs of whitespace (including newlines and tabs)
+    /// into single spaces
+    pub fn collapse_whitespace(text: &str) -> String {
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    /// Normalize a timestamp in a common format (RFC 3339, RFC 2822, unix
+    /// seconds, or a plain date/datetime) to RFC 3339 in UTC
+    pub fn normalize_timestamp(value: &str) -> Option<String> {
+        let value = value.trim();
+
+        if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+            return Some(dt.with_timezone(&Utc).to_rfc3339());
+        }
+
+        if let Ok(dt) = DateTime::parse_from_rfc2822(value) {
+            return Some(dt.with_timezone(&Utc).to_rfc3339());
+        }
 
-        // Detect feed type based on source name or structure
+        if let Ok(secs) = value.parse::<f64>() {
+            return DateTime::from_timestamp(secs as i64, 0).map(|dt| dt.to_rfc3339());
+        }
+
+        if let Ok(dt) = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S") {
+            return Some(dt.and_utc().to_rfc3339());
+        }
+
+        if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            return date.and_hms_opt(0, 0, 0).map(|dt| dt.and_utc().to_rfc3339());
+        }
+
+        None
+    }
+
+    /// Feed type suggested by a body's first non-whitespace character:
+    /// `json` for `{`/`[`, `rss` (the XML parser, which also reads Atom) for `<`
+    pub fn detect_feed_type(body: &str) -> Option<&'static str> {
+        match body.trim_start_matches('\u{feff}').trim_start().chars().next()? {
+            '{' | '[' => Some("json"),
+            '<' => Some("rss"),
+            _ => None,
+        }
+    }
+
+    /// Title the feed declares for itself: the RSS `<channel><title>`, Atom
+    /// `<feed><title>` or JSON Feed top-level `title`
+    pub fn declared_title(feed_type: &str, body: &str) -> Option<String> {
+        let title = match feed_type {
+            "json" => serde_json::from_str::<Value>(body).ok()?
+                .get("title")?
+                .as_str()?
+                .to_string(),
+            "rss" | "atom" => Self::xml_declared_title(body)?,
+            _ => return None,
+        };
+
+        let title = Self::collapse_whitespace(&title);
+        (!title.is_empty()).then_some(title)
+    }
+
+    fn xml_declared_title(body: &str) -> Option<String> {
+        let mut reader = Reader::from_str(body);
+        let mut path: Vec<String> = Vec::new();
+        let mut title = String::new();
+
+        loop {
+            match reader.read_event().ok()? {
+                Event::Start(start) => {
+                    let name = Self::local_name(&start);
+                    // The feed's own title always precedes its items
+                    if name == "item" || name == "entry" {
+                        return None;
+                    }
+                    path.push(name);
+                }
+                Event::Text(text) if Self::is_feed_title(&path) => {
+                    title.push_str(&text.unescape().ok()?);
+                }
+                Event::CData(data) if Self::is_feed_title(&path) => {
+                    title.push_str(&String::from_utf8_lossy(&data));
+                }
+                Event::End(_) => {
+                    if Self::is_feed_title(&path) {
+                        return Some(title);
+                    }
+                    path.pop();
+                }
+                Event::Eof => return None,
+                _ => {}
+            }
+        }
+    }
+
+    fn is_feed_title(path: &[String]) -> bool {
+        matches!(
+            path.iter().map(String::as_str).collect::<Vec<_>>().as_slice(),
+            ["rss", "channel", "title"] | ["RDF", "channel", "title"] | ["feed", "title"]
+        )
+    }
+
+    /// JSON parser kind selected for a source from its name
+    pub fn infer_json_parser(source: &str) -> &'static str {
         if source.contains("HackerNews") || source.contains("Hacker News") {
-            Self::parse_hackernews(source, &json)
+            "hackernews"
         } else if source.contains("GitHub") {
-            Self::parse_github(source, &json)
+            "github"
         } else if source.contains("Reddit") {
-            Self::parse_reddit(source, &json)
+            "reddit"
         } else if source.contains("Lobsters") {
-            Self::parse_lobsters(source, &json)
+            "lobsters"
         } else {
-            // Generic JSON parsing - try to extract items
-            Ok(Vec::new())
+            "generic"
+        }
+    }
+
+    /// JSON parser kind for a source: the explicit `parser` option if set,
+    /// else the one inferred from its name
+    pub fn json_parser_for(source: &str, options: &ParseOptions) -> &'static str {
+        options.parser.map_or_else(|| Self::infer_json_parser(source), JsonParser::as_str)
+    }
+
+    /// Parse a JSON body with a specific parser kind from `JSON_PARSERS`,
+    /// logging a warning for each skipped item
+    pub fn parse_json_as(
+        parser: &str,
+        source: &str,
+        body: &str,
+        options: &ParseOptions,
+    ) -> Result<Vec<FeedItem>, String> {
+        let mut warnings = Vec::new();
+        let items = Self::parse_json_with_warnings(parser, source, body, options, &mut warnings)?;
+        log_warnings(&warnings);
+        Ok(items)
+    }
+
+    fn parse_json_with_warnings(
+        parser: &str,
+        source: &str,
+        body: &str,
+        options: &ParseOptions,
+        warnings: &mut Vec<String>,
+    ) -> Result<Vec<FeedItem>, String> {
+        let json: Value = serde_json::from_str(body)
+            .map_err(|e| format!("malformed JSON: {}", e))?;
+
+        match parser {
+            "hackernews" => Self::parse_hackernews(source, &json),
+            "github" => Self::parse_github(source, &json, options, warnings),
+            "reddit" => Self::parse_reddit(source, &json, options, warnings),
+            "lobsters" => Self::parse_lobsters(source, &json, warnings),
+            "generic" => Self::parse_generic(source, &json, options, warnings),
+            _ => Err(format!("Unknown parser: {}", parser)),
+        }
+    }
+
+    fn parse_generic(source: &str, json: &Value, options: &ParseOptions, warnings: &mut Vec<String>) -> Result<Vec<FeedItem>, String> {
+        let mut items = Vec::new();
+
+        let item_array = match (json, options.items_path.as_deref()) {
+            (_, Some(path)) => match Self::lookup_path(json, path).and_then(|v| v.as_array()) {
+                Some(arr) => arr,
+                None => return Err(format!("no item array at {}", path)),
+            },
+            (Value::Array(arr), None) => arr,
+            _ => match json.get("items").and_then(|v| v.as_array()) {
+                Some(arr) => arr,
+                None => return Ok(items),
+            },
+        };
+
+        for (idx, item_value) in item_array.iter().enumerate() {
+            match Self::extract_generic_item(source, item_value, options) {
+                Ok(item) => items.push(item),
+                Err(missing) => warnings.push(format!("{} item {}: {}", source, idx, missing)),
+            }
+        }
+
+        Ok(items)
+    }
+
+    /// Item for one generic JSON value, or the missing field that stops it
+    fn extract_generic_item(source: &str, item: &Value, options: &ParseOptions) -> Result<FeedItem, String> {
+        let missing = |path: &Option<String>, default: &str| format!("missing {}", path.as_deref().unwrap_or(default));
+
+        let title_value = match &options.title_path {
+            Some(path) => Self::lookup_path(item, path),
+            None => ["title", "name", "headline"].iter()
+                .find_map(|key| item.get(*key).filter(|v| !v.is_null())),
+        };
+        let title = match title_value {
+            Some(Value::String(s)) => s.clone(),
+            None | Some(Value::Null) => return Err(missing(&options.title_path, "title")),
+            Some(other) => other.to_string(),
+        };
+
+        let url = match &options.url_path {
+            Some(path) => Self::lookup_path(item, path).and_then(|v| v.as_str()),
+            None => ["url", "link", "href"].iter()
+                .find_map(|key| item.get(*key).and_then(|v| v.as_str())),
+        }.map(|s| s.to_string()).ok_or_else(|| missing(&options.url_path, "url"))?;
+
+        let timestamp = match &options.timestamp_field {
+            Some(field) => Self::extract_timestamp(Self::lookup_path(item, field)),
+            None => GENERIC_TIMESTAMP_FIELDS.iter()
+                .find_map(|field| item.get(*field).filter(|v| !v.is_null()))
+                .and_then(|v| Self::extract_timestamp(Some(v))),
+        };
+
+        let summary = ["summary", "description", "content", "body"].iter()
+            .find_map(|key| item.get(*key).and_then(|v| v.as_str()))
+            .map(|s| s.to_string());
+
+        let raw_data = serde_json::to_string(item).ok();
+
+        Ok(
+            FeedItem::new(title, url, source.to_string())
+                .with_timestamp(timestamp)
+                .with_summary(summary)
+                .with_raw_data(raw_data.unwrap_or_default())
+        )
+    }
+
+    fn extract_timestamp(value: Option<&Value>) -> Option<String> {
+        match value? {
+            Value::String(s) => Self::normalize_timestamp(s),
+            Value::Number(n) => Self::normalize_timestamp(&n.to_string()),
+            _ => None,
         }
     }
 
@@ -48,19 +560,15 @@ impl Parser {
         Ok(items)
     }
 
-    fn parse_github(source: &str, json: &Value) -> Result<Vec<FeedItem>, String> {
+    fn parse_github(source: &str, json: &Value, options: &ParseOptions, warnings: &mut Vec<String>) -> Result<Vec<FeedItem>, String> {
         let mut items = Vec::new();
+        let items_path = options.items_path.as_deref().unwrap_or("items");
 
-        if let Some(item_array) = json.get("items").and_then(|v| v.as_array()) {
+        if let Some(item_array) = Self::lookup_path(json, items_path).and_then(|v| v.as_array()) {
             for (idx, item_value) in item_array.iter().enumerate() {
                 match Self::extract_github_item(source, item_value) {
-                    Ok(Some(item)) => items.push(item),
-                    Ok(None) => {
-                        eprintln!("Warning: {} item {}: missing required field", source, idx);
-                    }
-                    Err(e) => {
-                        eprintln!("Warning: {} item {}: {}", source, idx, e);
-                    }
+                    Ok(item) => items.push(item),
+                    Err(missing) => warnings.push(format!("{} item {}: {}", source, idx, missing)),
                 }
             }
         }
@@ -68,17 +576,17 @@ impl Parser {
         Ok(items)
     }
 
-    fn extract_github_item(source: &str, item: &Value) -> Result<Option<FeedItem>, String> {
+    fn extract_github_item(source: &str, item: &Value) -> Result<FeedItem, String> {
         let title = match item.get("full_name") {
             Some(Value::String(s)) => s.clone(),
             Some(other) => other.to_string(),
-            None => return Ok(None),
+            None => return Err("missing full_name".to_string()),
         };
 
         let url = match item.get("html_url") {
             Some(Value::String(s)) => s.clone(),
             Some(other) => other.to_string(),
-            None => return Ok(None),
+            None => return Err("missing html_url".to_string()),
         };
 
         let timestamp = item.get("updated_at")
@@ -95,33 +603,27 @@ impl Parser {
 
         let raw_data = serde_json::to_string(item).ok();
 
-        Ok(Some(
+        Ok(
             FeedItem::new(title, url, source.to_string())
                 .with_timestamp(timestamp)
                 .with_tags(tags)
                 .with_raw_data(raw_data.unwrap_or_default())
-        ))
+        )
     }
 
-    fn parse_reddit(source: &str, json: &Value) -> Result<Vec<FeedItem>, String> {
+    fn parse_reddit(source: &str, json: &Value, options: &ParseOptions, warnings: &mut Vec<String>) -> Result<Vec<FeedItem>, String> {
         let mut items = Vec::new();
+        let items_path = options.items_path.as_deref().unwrap_or("data.children");
 
-        let children = json
-            .get("data")
-            .and_then(|d| d.get("children"))
+        let children = Self::lookup_path(json, items_path)
             .and_then(|c| c.as_array())
-            .ok_or("Reddit feed missing data.children")?;
+            .ok_or_else(|| format!("Reddit feed missing {}", items_path))?;
 
         for (idx, child) in children.iter().enumerate() {
             if let Some(data) = child.get("data") {
-                match Self::extract_reddit_item(source, data) {
-                    Ok(Some(item)) => items.push(item),
-                    Ok(None) => {
-                        eprintln!("Warning: {} item {}: missing required field", source, idx);
-                    }
-                    Err(e) => {
-                        eprintln!("Warning: {} item {}: {}", source, idx, e);
-                    }
+                match Self::extract_reddit_item(source, data, options) {
+                    Ok(item) => items.push(item),
+                    Err(missing) => warnings.push(format!("{} item {}: {}", source, idx, missing)),
                 }
             }
         }
@@ -129,17 +631,17 @@ impl Parser {
         Ok(items)
     }
 
-    fn extract_reddit_item(source: &str, data: &Value) -> Result<Option<FeedItem>, String> {
+    fn extract_reddit_item(source: &str, data: &Value, options: &ParseOptions) -> Result<FeedItem, String> {
         let title = match data.get("title") {
             Some(Value::String(s)) => s.clone(),
             Some(other) => other.to_string(),
-            None => return Ok(None),
+            None => return Err("missing title".to_string()),
         };
 
         let url = match data.get("url") {
             Some(Value::String(s)) => s.clone(),
             Some(other) => other.to_string(),
-            None => return Ok(None),
+            None => return Err("missing url".to_string()),
         };
 
         let timestamp = data.get("created_utc")
@@ -150,23 +652,32 @@ impl Parser {
                 dt.to_rfc3339()
             });
 
-        let tags = if let Some(Value::String(flair)) = data.get("link_flair_text") {
-            vec![flair.clone()]
+        let flair = data.get("link_flair_text").and_then(|v| v.as_str());
+        let tags = if options.namespaced_tags {
+            let subreddit = data.get("subreddit").and_then(|v| v.as_str());
+            subreddit.map(|s| format!("sub:{}", s)).into_iter()
+                .chain(flair.map(|f| format!("flair:{}", f)))
+                .collect()
         } else {
-            Vec::new()
+            flair.map(|f| vec![f.to_string()]).unwrap_or_default()
         };
 
+        let summary = data.get("selftext")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
         let raw_data = serde_json::to_string(data).ok();
 
-        Ok(Some(
+        Ok(
             FeedItem::new(title, url, source.to_string())
                 .with_timestamp(timestamp)
                 .with_tags(tags)
+                .with_summary(summary)
                 .with_raw_data(raw_data.unwrap_or_default())
-        ))
+        )
     }
 
-    fn parse_lobsters(source: &str, json: &Value) -> Result<Vec<FeedItem>, String> {
+    fn parse_lobsters(source: &str, json: &Value, warnings: &mut Vec<String>) -> Result<Vec<FeedItem>, String> {
         let mut items = Vec::new();
 
         let item_array = json.as_array()
@@ -174,24 +685,19 @@ impl Parser {
 
         for (idx, item_value) in item_array.iter().enumerate() {
             match Self::extract_lobsters_item(source, item_value) {
-                Ok(Some(item)) => items.push(item),
-                Ok(None) => {
-                    eprintln!("Warning: {} item {}: missing required field", source, idx);
-                }
-                Err(e) => {
-                    eprintln!("Warning: {} item {}: {}", source, idx, e);
-                }
+                Ok(item) => items.push(item),
+                Err(missing) => warnings.push(format!("{} item {}: {}", source, idx, missing)),
             }
         }
 
         Ok(items)
     }
 
-    fn extract_lobsters_item(source: &str, item: &Value) -> Result<Option<FeedItem>, String> {
+    fn extract_lobsters_item(source: &str, item: &Value) -> Result<FeedItem, String> {
         let title = match item.get("title") {
             Some(Value::String(s)) => s.clone(),
             Some(other) => other.to_string(),
-            None => return Ok(None),
+            None => return Err("missing title".to_string()),
         };
 
         let url = match item.get("url") {
@@ -201,7 +707,7 @@ impl Parser {
                 match item.get("comments_url") {
                     Some(Value::String(s)) => s.clone(),
                     Some(other) => other.to_string(),
-                    None => return Ok(None),
+                    None => return Err("missing url and comments_url".to_string()),
                 }
             }
         };
@@ -220,11 +726,17 @@ impl Parser {
 
         let raw_data = serde_json::to_string(item).ok();
 
-        Ok(Some(
+        Ok(
             FeedItem::new(title, url, source.to_string())
                 .with_timestamp(timestamp)
                 .with_tags(tags)
                 .with_raw_data(raw_data.unwrap_or_default())
-        ))
+        )
+    }
+}
+
+fn log_warnings(warnings: &[String]) {
+    for warning in warnings {
+        tracing::warn!("{}", warning);
     }
 }
@@ -1,14 +1,344 @@
 use crate::config::Config;
-use crate::storage::Storage;
-use comfy_table::{Table, Cell, Color, Attribute};
+use crate::models::{canonical_url, group_by_canonical_url, FeedItem, FetchStatus};
+use crate::fetcher::FetchResult;
+use crate::storage::{ItemQuery, SourceStat, Storage, StoreOutcome};
+use chrono::{DateTime, Duration, Utc};
+use comfy_table::{Table, Cell, Attribute, ContentArrangement};
+use quick_xml::escape::escape;
+use std::collections::HashMap;
+use url::Url;
+
+/// Convert a `--since` value (relative like "30m", "24h", "7d", "2w", or an
+/// absolute RFC 3339 timestamp) into an RFC 3339 cutoff
+pub fn parse_since(value: &str) -> Result<String, String> {
+    let invalid = || format!("invalid --since value '{}' (expected e.g. 30m, 24h, 7d, 2w or an RFC 3339 timestamp)", value);
+    let value = value.trim();
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Ok(dt.with_timezone(&Utc).to_rfc3339());
+    }
+
+    if value.len() < 2 || !value.is_char_boundary(value.len() - 1) {
+        return Err(invalid());
+    }
+    let (amount, unit) = value.split_at(value.len() - 1);
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+    if amount < 0 {
+        return Err(invalid());
+    }
+
+    let duration = match unit {
+        "m" => Duration::try_minutes(amount),
+        "h" => Duration::try_hours(amount),
+        "d" => Duration::try_days(amount),
+        "w" => Duration::try_weeks(amount),
+        _ => None,
+    }.ok_or_else(invalid)?;
+
+    Utc::now().checked_sub_signed(duration)
+        .map(|cutoff| cutoff.to_rfc3339())
+        .ok_or_else(invalid)
+}
+
+/// Escape characters that would otherwise be read as Markdown syntax
+pub fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|' | '!') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Titles longer than this are cut short in the `items` table
+const ITEM_TITLE_WIDTH: usize = 60;
+
+/// Errors listed per failing source with `report --show-errors`
+const RECENT_ERRORS: usize = 5;
+
+/// Inline errors longer than this are cut short in the report table
+const INLINE_ERROR_WIDTH: usize = 60;
+
+/// Item summaries longer than this are cut short in Markdown digests
+const DIGEST_SUMMARY_WIDTH: usize = 200;
+
+/// Shorten `text` to at most `max` characters, ending with an ellipsis when cut
+fn truncate_chars(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(max.saturating_sub(1)).collect();
+    cut.push('…');
+    cut
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or newline
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// `# Feed Digest` with a `## Source` section per source (alphabetical) of
+/// `- [title](url) (timestamp)` bullets, each followed by the item's summary
+/// on one indented line when it has one; sources without items don't appear
+fn render_markdown(items: &[FeedItem]) -> String {
+    let mut by_source: std::collections::BTreeMap<&str, Vec<&FeedItem>> = std::collections::BTreeMap::new();
+    for item in items {
+        by_source.entry(item.source.as_str()).or_default().push(item);
+    }
+
+    let mut out = String::from("# Feed Digest\n");
+    if by_source.is_empty() {
+        out.push_str("\nNo new items.\n");
+    }
+
+    for (source, items) in by_source {
+        out.push_str(&format!("\n## {}\n\n", escape_markdown(source)));
+        for item in items {
+            let url = item.url.replace(' ', "%20").replace('(', "%28").replace(')', "%29");
+            out.push_str(&format!("- [{}]({})", escape_markdown(&item.title), url));
+            if let Some(ts) = &item.timestamp {
+                out.push_str(&format!(" ({})", ts));
+            }
+            out.push('\n');
+            let summary = item.summary.as_deref()
+                .map(|summary| summary.split_whitespace().collect::<Vec<_>>().join(" "))
+                .filter(|summary| !summary.is_empty());
+            if let Some(summary) = summary {
+                out.push_str(&format!("  {}\n", escape_markdown(&truncate_chars(&summary, DIGEST_SUMMARY_WIDTH))));
+            }
+        }
+    }
+
+    out
+}
+
+/// Machine-readable summary of a fetch run: one entry per feed plus totals.
+/// `outcome` is None for a dry run, where new/updated counts are unknown
+/// and reported as null
+pub fn fetch_summary_json(results: &[FetchResult], outcome: Option<&StoreOutcome>) -> serde_json::Value {
+    let feeds: Vec<serde_json::Value> = results.iter()
+        .map(|result| {
+            let status = match (result.status(), result.not_modified) {
+                (FetchStatus::Error, _) => "error",
+                (FetchStatus::ParseError, _) => "parse_error",
+                (_, true) => "not_modified",
+                (FetchStatus::Degraded, false) => "degraded",
+                (FetchStatus::Success, false) => "ok",
+            };
+            serde_json::json!({
+                "source": result.source,
+                "status": status,
+                "item_count": result.items.len(),
+                "skipped_count": result.parse_warnings.len(),
+                "new_count": outcome.map(|_| result.new_items),
+                "duration_ms": result.duration_ms,
+                "error": result.error,
+                "warning": result.warning,
+            })
+        })
+        .collect();
+
+    let errors = results.iter().filter(|r| r.error.is_some()).count();
+    serde_json::json!({
+        "feeds": feeds,
+        "totals": {
+            "feeds": results.len(),
+            "succeeded": results.len() - errors,
+            "errors": errors,
+            "items": results.iter().map(|r| r.items.len()).sum::<usize>(),
+            "skipped": results.iter().map(|r| r.parse_warnings.len()).sum::<usize>(),
+            "new": outcome.map(|o| o.new),
+            "updated": outcome.map(|o| o.updated),
+        },
+        "dry_run": outcome.is_none(),
+    })
+}
+
+/// Render items as an RSS 2.0 document with each item's source(s) as
+/// categories. The channel links to the newest item's site, the closest
+/// thing an aggregate of several sources has to a home page
+fn render_rss(items: &[FeedItem], item_sources: impl Fn(usize) -> Vec<String>) -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rss version=\"2.0\">\n<channel>\n");
+    out.push_str("  <title>feedpulse</title>\n");
+    if let Some(site) = items.first().and_then(|item| Url::parse(&item.url).ok()) {
+        out.push_str(&format!("  <link>{}</link>\n", escape(site.origin().ascii_serialization())));
+    }
+    out.push_str(&format!("  <description>{} items aggregated by feedpulse</description>\n", items.len()));
+    out.push_str("  <generator>feedpulse</generator>\n");
+
+    for (i, item) in items.iter().enumerate() {
+        out.push_str("  <item>\n");
+        out.push_str(&format!("    <title>{}</title>\n", escape(item.title.as_str())));
+        out.push_str(&format!("    <link>{}</link>\n", escape(item.url.as_str())));
+        out.push_str(&format!("    <guid isPermaLink=\"false\">{}</guid>\n", item.id));
+        if let Some(date) = item.timestamp.as_deref().and_then(|ts| DateTime::parse_from_rfc3339(ts).ok()) {
+            out.push_str(&format!("    <pubDate>{}</pubDate>\n", date.to_rfc2822()));
+        }
+        for source in item_sources(i) {
+            out.push_str(&format!("    <category>{}</category>\n", escape(source.as_str())));
+        }
+        if let Some(summary) = &item.summary {
+            out.push_str(&format!("    <description>{}</description>\n", escape(summary.as_str())));
+        }
+        out.push_str("  </item>\n");
+    }
+
+    out.push_str("</channel>\n</rss>");
+    out
+}
+
+/// Write `value` as one compact line and flush, so streaming consumers see it now
+fn write_ndjson_line<W: std::io::Write>(out: &mut W, value: &serde_json::Value) -> Result<(), String> {
+    writeln!(out, "{}", value)
+        .and_then(|_| out.flush())
+        .map_err(|e| format!("Failed to write output: {}", e))
+}
+
+/// Error rate (percent) at which a source stops being `healthy`
+const DEGRADED_ERROR_RATE: f64 = 10.0;
+/// Error rate (percent) at which a source is `failing`
+const FAILING_ERROR_RATE: f64 = 50.0;
+
+/// Overall state of a source in the JSON report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceHealth {
+    Healthy,
+    Degraded,
+    /// High error rate, or no successful fetch at all
+    Failing,
+    /// No fetch history (`log_fetches: false`)
+    Unknown,
+}
+
+impl SourceHealth {
+    pub fn from_stat(stat: &SourceStat) -> Self {
+        let Some(rate) = stat.error_rate() else {
+            return SourceHealth::Unknown;
+        };
+
+        if stat.last_success.is_none() || rate >= FAILING_ERROR_RATE {
+            SourceHealth::Failing
+        } else if rate >= DEGRADED_ERROR_RATE || stat.degraded.unwrap_or(0) > 0 {
+            SourceHealth::Degraded
+        } else {
+            SourceHealth::Healthy
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SourceHealth::Healthy => "healthy",
+            SourceHealth::Degraded => "degraded",
+            SourceHealth::Failing => "failing",
+            SourceHealth::Unknown => "unknown",
+        }
+    }
+}
+
+/// Health for the Status column, noting an open circuit breaker
+fn status_label(stat: &SourceStat) -> String {
+    let health = SourceHealth::from_stat(stat).as_str();
+    match stat.open_circuit {
+        Some(failures) => format!("{} (circuit open, {} failures)", health, failures),
+        None => health.to_string(),
+    }
+}
+
+/// Item count for display, noting items the parser skipped (`48 (2 skipped)`)
+fn with_skipped(items: impl std::fmt::Display, skipped: u64) -> String {
+    match skipped {
+        0 => items.to_string(),
+        n => format!("{} ({} skipped)", items, n),
+    }
+}
+
+/// Error count, error rate and last success for display ("n/a" without
+/// fetch history)
+fn stat_fields(stat: &SourceStat) -> (String, String, String) {
+    match (stat.errors, stat.error_rate()) {
+        (Some(errors), Some(rate)) => (
+            errors.to_string(),
+            format!("{:.1}%", rate),
+            stat.last_success.clone().unwrap_or_else(|| "never".to_string()),
+        ),
+        _ => ("n/a".to_string(), "n/a".to_string(), "n/a".to_string()),
+    }
+}
+
+#[derive(Debug)]
+pub struct DbSource {
+    pub source: String,
+    pub orphaned: bool,
+}
+
+#[derive(Debug)]
+pub struct CertWarning {
+    pub source: String,
+    pub expires_at: String,
+    pub days_left: i64,
+}
+
+#[derive(Debug)]
+pub struct StaleWarning {
+    pub source: String,
+    pub repeats: usize,
+}
 
 pub struct Reporter {
     storage: Storage,
+    width: Option<u16>,
+    limit: Option<usize>,
+    show_errors: bool,
+    tags: Vec<String>,
 }
 
 impl Reporter {
     pub fn new(storage: Storage) -> Self {
-        Self { storage }
+        Self { storage, width: None, limit: None, show_errors: false, tags: Vec::new() }
+    }
+
+    /// Base the report on stored items carrying every one of `tags`
+    /// (counted per source) instead of the fetch log; `with_limit` then
+    /// has no effect
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// List each failing source's most recent error messages in table and
+    /// JSON reports
+    pub fn with_show_errors(mut self, show_errors: bool) -> Self {
+        self.show_errors = show_errors;
+        self
+    }
+
+    /// Render tables at a fixed width instead of auto-detecting the terminal
+    pub fn with_width(mut self, width: Option<u16>) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Base the report on each source's N most recent fetches (0 or None
+    /// for all of them)
+    pub fn with_limit(mut self, limit: Option<usize>) -> Self {
+        self.limit = limit.filter(|&n| n > 0);
+        self
+    }
+
+    fn new_table(&self) -> Table {
+        let mut table = Table::new();
+        if let Some(width) = self.width {
+            table.set_content_arrangement(ContentArrangement::Dynamic);
+            table.set_width(width);
+        }
+        table
     }
 
     pub fn generate_report(
@@ -17,26 +347,66 @@ impl Reporter {
         source: Option<&str>,
         since: Option<&str>,
     ) -> Result<(), String> {
-        let stats = self.storage.get_source_stats()?;
+        if format == "ndjson" {
+            let stdout = std::io::stdout();
+            return self.write_report_ndjson(&mut stdout.lock(), source, since);
+        }
+
+        let output = self.render_report(format, source, since)?;
+        println!("{}", output);
+
+        Ok(())
+    }
+
+    /// Write the report as NDJSON: one `json`-format source object per line,
+    /// flushed as each is written so consumers can stream it
+    pub fn write_report_ndjson<W: std::io::Write>(
+        &self,
+        out: &mut W,
+        source: Option<&str>,
+        since: Option<&str>,
+    ) -> Result<(), String> {
+        let stats = self.report_stats(since)?;
+        for stat in stats.iter().filter(|s| source.is_none_or(|src| s.source == src)) {
+            write_ndjson_line(out, &self.json_source(stat)?)?;
+        }
+        Ok(())
+    }
+
+    pub fn render_report(
+        &self,
+        format: &str,
+        source: Option<&str>,
+        since: Option<&str>,
+    ) -> Result<String, String> {
+        let stats = self.report_stats(since)?;
 
         match format {
-            "table" => self.print_table_report(&stats, source),
-            "json" => self.print_json_report(&stats, source),
-            "csv" => self.print_csv_report(&stats, source),
-            _ => return Err(format!("Unknown format: {}", format)),
+            "table" => self.render_table_report(&stats, source),
+            "json" => self.render_json_report(&stats, source),
+            "csv" => Ok(self.render_csv_report(&stats, source)),
+            _ => Err(format!("Unknown format: {}", format)),
         }
+    }
 
-        Ok(())
+    fn report_stats(&self, since: Option<&str>) -> Result<Vec<SourceStat>, String> {
+        let cutoff = since.map(parse_since).transpose()?;
+        if self.tags.is_empty() {
+            self.storage.get_source_stats(self.limit, cutoff.as_deref())
+        } else {
+            self.storage.get_tagged_source_stats(&self.tags, cutoff.as_deref())
+        }
     }
 
-    fn print_table_report(&self, stats: &[crate::storage::SourceStat], filter_source: Option<&str>) {
-        let mut table = Table::new();
+    fn render_table_report(&self, stats: &[SourceStat], filter_source: Option<&str>) -> Result<String, String> {
+        let mut table = self.new_table();
         table.set_header(vec![
             Cell::new("Source").add_attribute(Attribute::Bold),
             Cell::new("Items").add_attribute(Attribute::Bold),
             Cell::new("Errors").add_attribute(Attribute::Bold),
             Cell::new("Error Rate").add_attribute(Attribute::Bold),
             Cell::new("Last Success").add_attribute(Attribute::Bold),
+            Cell::new("Status").add_attribute(Attribute::Bold),
         ]);
 
         let filtered_stats: Vec<_> = if let Some(src) = filter_source {
@@ -46,106 +416,156 @@ impl Reporter {
         };
 
         let mut total_items = 0;
-        let mut total_errors = 0;
+        let mut error_sections = Vec::new();
 
         for stat in &filtered_stats {
-            let total = stat.items + stat.errors;
-            let error_rate = if total > 0 {
-                (stat.errors as f64 / total as f64) * 100.0
+            let (mut errors, error_rate, mut last_success) = stat_fields(stat);
+            if let Some(parse_errors) = stat.parse_errors.filter(|&n| n > 0) {
+                errors = format!("{} ({} parse)", errors, parse_errors);
+            }
+            let recent_errors = if stat.errors.unwrap_or(0) > 0 {
+                self.storage.get_recent_errors(&stat.source, RECENT_ERRORS)?
             } else {
-                0.0
+                Vec::new()
             };
 
-            let last_success = stat.last_success.as_deref().unwrap_or("never");
+            // A source that never worked shows why right in the table
+            if let (None, Some((_, message))) = (&stat.last_success, recent_errors.first()) {
+                last_success = format!("never: {}", truncate_chars(message, INLINE_ERROR_WIDTH));
+            }
+
+            if self.show_errors && !recent_errors.is_empty() {
+                let lines: Vec<String> = recent_errors.iter()
+                    .map(|(fetched_at, message)| format!("  {}  {}", fetched_at, message))
+                    .collect();
+                error_sections.push(format!("Recent errors for {}:\n{}", stat.source, lines.join("\n")));
+            }
 
             table.add_row(vec![
                 Cell::new(&stat.source),
-                Cell::new(stat.items.to_string()),
-                Cell::new(stat.errors.to_string()),
-                Cell::new(format!("{:.1}%", error_rate)),
+                Cell::new(with_skipped(stat.items, stat.skipped.unwrap_or(0) as u64)),
+                Cell::new(errors),
+                Cell::new(error_rate),
                 Cell::new(last_success),
+                Cell::new(status_label(stat)),
             ]);
 
             total_items += stat.items;
-            total_errors += stat.errors;
         }
 
-        println!("{}", table);
-        println!("\nTotal: {} items across {} sources", total_items, filtered_stats.len());
+        let mut output = format!("{}\n\nTotal: {} items across {} sources", table, total_items, filtered_stats.len());
+        for section in error_sections {
+            output.push_str("\n\n");
+            output.push_str(&section);
+        }
+
+        Ok(output)
     }
 
-    fn print_json_report(&self, stats: &[crate::storage::SourceStat], filter_source: Option<&str>) {
+    fn render_json_report(&self, stats: &[SourceStat], filter_source: Option<&str>) -> Result<String, String> {
         let filtered_stats: Vec<_> = if let Some(src) = filter_source {
             stats.iter().filter(|s| s.source == src).collect()
         } else {
             stats.iter().collect()
         };
 
-        let json = serde_json::json!({
-            "sources": filtered_stats.iter().map(|stat| {
-                let total = stat.items + stat.errors;
-                let error_rate = if total > 0 {
-                    (stat.errors as f64 / total as f64) * 100.0
-                } else {
-                    0.0
-                };
+        let sources = filtered_stats.into_iter()
+            .map(|stat| self.json_source(stat))
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(serde_json::to_string_pretty(&serde_json::json!({ "sources": sources })).unwrap())
+    }
 
-                serde_json::json!({
-                    "source": stat.source,
-                    "items": stat.items,
-                    "errors": stat.errors,
-                    "error_rate": format!("{:.1}%", error_rate),
-                    "last_success": stat.last_success,
-                })
-            }).collect::<Vec<_>>(),
+    fn json_source(&self, stat: &SourceStat) -> Result<serde_json::Value, String> {
+        let status = SourceHealth::from_stat(stat);
+
+        let mut source = serde_json::json!({
+            "source": stat.source,
+            "items": stat.items,
+            "errors": stat.errors,
+            "error_rate": stat.error_rate(),
+            "error_rate_display": stat.error_rate().map(|rate| format!("{:.1}%", rate)),
+            "status": status,
+            "is_error": status == SourceHealth::Failing,
+            "parse_errors": stat.parse_errors,
+            "skipped_items": stat.skipped,
+            "degraded_fetches": stat.degraded,
+            "circuit_open": stat.open_circuit.is_some(),
+            "consecutive_failures": stat.open_circuit,
+            "last_success": stat.last_success,
         });
 
-        println!("{}", serde_json::to_string_pretty(&json).unwrap());
+        if self.show_errors {
+            let recent_errors = match stat.errors {
+                Some(errors) if errors > 0 => self.storage.get_recent_errors(&stat.source, RECENT_ERRORS)?,
+                _ => Vec::new(),
+            };
+            source["recent_errors"] = recent_errors.into_iter()
+                .map(|(fetched_at, error)| serde_json::json!({ "fetched_at": fetched_at, "error": error }))
+                .collect();
+        }
+
+        Ok(source)
     }
 
-    fn print_csv_report(&self, stats: &[crate::storage::SourceStat], filter_source: Option<&str>) {
+    fn render_csv_report(&self, stats: &[SourceStat], filter_source: Option<&str>) -> String {
         let filtered_stats: Vec<_> = if let Some(src) = filter_source {
             stats.iter().filter(|s| s.source == src).collect()
         } else {
             stats.iter().collect()
         };
 
-        println!("Source,Items,Errors,Error Rate,Last Success");
+        let mut lines = vec!["Source,Items,Errors,Error Rate,Last Success,Status".to_string()];
 
         for stat in filtered_stats {
-            let total = stat.items + stat.errors;
-            let error_rate = if total > 0 {
-                (stat.errors as f64 / total as f64) * 100.0
-            } else {
-                0.0
-            };
+            let (errors, error_rate, last_success) = stat_fields(stat);
 
-            let last_success = stat.last_success.as_deref().unwrap_or("never");
-
-            println!(
-                "{},{},{},{:.1}%,{}",
-                stat.source, stat.items, stat.errors, error_rate, last_success
-            );
+            lines.push(format!(
+                "{},{},{},{},{},{}",
+                stat.source, stat.items, errors, error_rate, last_success, status_label(stat)
+            ));
         }
+
+        lines.join("\n")
     }
 
     pub fn list_sources(&self, config: &Config) -> Result<(), String> {
-        let stats = self.storage.get_source_stats()?;
+        println!("{}", self.render_sources(config)?);
+
+        for warning in self.gone_warnings(config)? {
+            tracing::warn!("{}", warning);
+        }
+
+        Ok(())
+    }
+
+    /// Table of configured feeds with their status and declared feed title
+    pub fn render_sources(&self, config: &Config) -> Result<String, String> {
+        let stats = self.storage.get_source_stats(None, None)?;
         let stats_map: std::collections::HashMap<_, _> = stats.iter()
             .map(|s| (s.source.as_str(), s))
             .collect();
+        let gone = self.storage.get_gone_sources()?;
+        let feed_titles = self.storage.get_feed_titles()?;
 
-        let mut table = Table::new();
+        let mut table = self.new_table();
         table.set_header(vec![
             Cell::new("Source").add_attribute(Attribute::Bold),
+            Cell::new("Feed Title").add_attribute(Attribute::Bold),
             Cell::new("URL").add_attribute(Attribute::Bold),
             Cell::new("Type").add_attribute(Attribute::Bold),
             Cell::new("Status").add_attribute(Attribute::Bold),
         ]);
 
         for feed in &config.feeds {
-            let status = if let Some(stat) = stats_map.get(feed.name.as_str()) {
-                if stat.last_success.is_some() {
+            let status = if !feed.enabled {
+                "○ Disabled"
+            } else if gone.contains(&feed.name) {
+                "✗ Gone (410)"
+            } else if let Some(stat) = stats_map.get(feed.name.as_str()) {
+                if stat.errors.is_none() {
+                    "○ Stored (fetch log off)"
+                } else if stat.last_success.is_some() {
                     "✓ Active"
                 } else {
                     "✗ Failing"
@@ -156,14 +576,500 @@ impl Reporter {
 
             table.add_row(vec![
                 Cell::new(&feed.name),
+                Cell::new(feed_titles.get(&feed.name).map(String::as_str).unwrap_or("")),
                 Cell::new(&feed.url),
                 Cell::new(&feed.feed_type),
                 Cell::new(status),
             ]);
         }
 
+        Ok(table.to_string())
+    }
+
+    /// Suggestions for enabled feeds whose last fetch answered 410 Gone
+    pub fn gone_warnings(&self, config: &Config) -> Result<Vec<String>, String> {
+        let gone = self.storage.get_gone_sources()?;
+
+        Ok(config.feeds.iter()
+            .filter(|feed| feed.enabled && gone.contains(&feed.name))
+            .map(|feed| format!(
+                "Feed '{}' returned 410 Gone; remove it or set `enabled: false`",
+                feed.name
+            ))
+            .collect())
+    }
+
+    /// Sources whose latest TLS certificate expires within `warn_days`
+    pub fn cert_warnings(&self, warn_days: u64) -> Result<Vec<CertWarning>, String> {
+        let now = chrono::Utc::now();
+        let mut warnings = Vec::new();
+
+        for (source, expires_at) in self.storage.get_cert_expiries()? {
+            let Ok(expiry) = chrono::DateTime::parse_from_rfc3339(&expires_at) else {
+                continue;
+            };
+
+            let days_left = (expiry.with_timezone(&chrono::Utc) - now).num_days();
+            if days_left <= warn_days as i64 {
+                warnings.push(CertWarning { source, expires_at, days_left });
+            }
+        }
+
+        Ok(warnings)
+    }
+
+    /// Sources whose last `threshold` or more successful fetches all returned
+    /// a byte-identical body
+    pub fn stale_warnings(&self, threshold: usize) -> Result<Vec<StaleWarning>, String> {
+        Ok(self.storage.get_repeated_body_counts()?
+            .into_iter()
+            .filter(|(_, repeats)| *repeats >= threshold)
+            .map(|(source, repeats)| StaleWarning { source, repeats })
+            .collect())
+    }
+
+    /// Render recent items as a Markdown digest grouped under source headings
+    pub fn render_digest(&self, source: Option<&str>, since: Option<&str>) -> Result<String, String> {
+        let items = self.storage.get_items(source, since)?;
+        Ok(render_markdown(&items))
+    }
+
+    /// Write every stored item as `jsonl` or `csv`, reading `page_size` rows
+    /// at a time so memory stays bounded; returns the number of items written
+    pub fn export_items<W: std::io::Write>(
+        &self,
+        out: &mut W,
+        format: &str,
+        source: Option<&str>,
+        page_size: usize,
+    ) -> Result<usize, String> {
+        if !matches!(format, "jsonl" | "csv") {
+            return Err(format!("Unknown export format: {} (expected jsonl or csv)", format));
+        }
+        if page_size == 0 {
+            return Err("page size must be positive".to_string());
+        }
+
+        let write_err = |e: std::io::Error| format!("Failed to write export: {}", e);
+
+        if format == "csv" {
+            writeln!(out, "id,title,url,source,timestamp,tags,created_at").map_err(write_err)?;
+        }
+
+        let mut cursor: Option<(String, String)> = None;
+        let mut written = 0;
+
+        loop {
+            let after = cursor.as_ref().map(|(created_at, id)| (created_at.as_str(), id.as_str()));
+            let page = self.storage.get_items_page(source, after, page_size)?;
+
+            for (item, created_at) in &page {
+                if format == "jsonl" {
+                    let mut value = serde_json::to_value(item)
+                        .map_err(|e| format!("Failed to serialize item: {}", e))?;
+                    value["created_at"] = serde_json::Value::String(created_at.clone());
+                    writeln!(out, "{}", value).map_err(write_err)?;
+                } else {
+                    writeln!(
+                        out,
+                        "{},{},{},{},{},{},{}",
+                        csv_field(&item.id),
+                        csv_field(&item.title),
+                        csv_field(&item.url),
+                        csv_field(&item.source),
+                        csv_field(item.timestamp.as_deref().unwrap_or("")),
+                        csv_field(&item.tags.join(";")),
+                        csv_field(created_at),
+                    ).map_err(write_err)?;
+                }
+            }
+
+            written += page.len();
+            if page.len() < page_size {
+                break;
+            }
+            cursor = page.last().map(|(item, created_at)| (created_at.clone(), item.id.clone()));
+        }
+
+        Ok(written)
+    }
+
+    /// Write fetch_log rows, oldest first, as `jsonl` or `csv`; returns the
+    /// number of rows written
+    pub fn export_fetch_log<W: std::io::Write>(
+        &self,
+        out: &mut W,
+        format: &str,
+        source: Option<&str>,
+        since: Option<&str>,
+    ) -> Result<usize, String> {
+        if !matches!(format, "jsonl" | "csv") {
+            return Err(format!("Unknown export format: {} (expected jsonl or csv)", format));
+        }
+
+        let write_err = |e: std::io::Error| format!("Failed to write export: {}", e);
+        let log = self.storage.get_fetch_log(source, since)?;
+
+        if format == "csv" {
+            writeln!(
+                out,
+                "fetched_at,source,status,items_count,error_message,duration_ms,cert_expires_at,body_hash,gone,mirror_url,captured_headers,skipped_count"
+            ).map_err(write_err)?;
+        }
+
+        for entry in &log {
+            if format == "jsonl" {
+                let line = serde_json::to_string(entry)
+                    .map_err(|e| format!("Failed to serialize fetch log: {}", e))?;
+                writeln!(out, "{}", line).map_err(write_err)?;
+            } else {
+                let captured_headers = if entry.captured_headers.is_empty() {
+                    String::new()
+                } else {
+                    serde_json::to_string(&entry.captured_headers).unwrap_or_default()
+                };
+                writeln!(
+                    out,
+                    "{},{},{},{},{},{},{},{},{},{},{},{}",
+                    csv_field(&entry.fetched_at),
+                    csv_field(&entry.source),
+                    csv_field(&entry.status),
+                    entry.items_count,
+                    csv_field(entry.error_message.as_deref().unwrap_or("")),
+                    entry.duration_ms,
+                    csv_field(entry.cert_expires_at.as_deref().unwrap_or("")),
+                    csv_field(entry.body_hash.as_deref().unwrap_or("")),
+                    entry.gone,
+                    csv_field(entry.mirror_url.as_deref().unwrap_or("")),
+                    csv_field(&captured_headers),
+                    entry.skipped_count,
+                ).map_err(write_err)?;
+            }
+        }
+
+        Ok(log.len())
+    }
+
+    /// Render item counts per time bucket as `table` or `json`
+    pub fn render_timeseries(
+        &self,
+        format: &str,
+        bucket: &str,
+        source: Option<&str>,
+        by_source: bool,
+    ) -> Result<String, String> {
+        let points = self.storage.get_item_timeseries(bucket, source, by_source)?;
+
+        match format {
+            "table" => {
+                let mut header = vec![Cell::new("Bucket").add_attribute(Attribute::Bold)];
+                if by_source {
+                    header.push(Cell::new("Source").add_attribute(Attribute::Bold));
+                }
+                header.push(Cell::new("Items").add_attribute(Attribute::Bold));
+
+                let mut table = self.new_table();
+                table.set_header(header);
+
+                for point in &points {
+                    let mut row = vec![Cell::new(&point.bucket)];
+                    if let Some(source) = &point.source {
+                        row.push(Cell::new(source));
+                    }
+                    row.push(Cell::new(point.count.to_string()));
+                    table.add_row(row);
+                }
+
+                Ok(table.to_string())
+            }
+            "json" => serde_json::to_string_pretty(&serde_json::json!({
+                "bucket": bucket,
+                "series": points,
+            })).map_err(|e| format!("Failed to render timeseries: {}", e)),
+            _ => Err(format!("Unknown format: {}", format)),
+        }
+    }
+
+    /// Render cross-source duplicate clusters as `table`, `json` or `csv`
+    /// (sources joined with `;`)
+    pub fn render_duplicates(&self, format: &str, source: Option<&str>, since: Option<&str>) -> Result<String, String> {
+        let clusters = self.storage.get_duplicate_clusters(source, since)?;
+
+        match format {
+            "table" => {
+                let mut table = self.new_table();
+                table.set_header(vec![
+                    Cell::new("Canonical URL").add_attribute(Attribute::Bold),
+                    Cell::new("Sources").add_attribute(Attribute::Bold),
+                    Cell::new("Items").add_attribute(Attribute::Bold),
+                ]);
+
+                for cluster in &clusters {
+                    table.add_row(vec![
+                        Cell::new(&cluster.canonical_url),
+                        Cell::new(cluster.sources.join(", ")),
+                        Cell::new(cluster.items.to_string()),
+                    ]);
+                }
+
+                Ok(format!("{}\n\n{} URLs carried by more than one source", table, clusters.len()))
+            }
+            "json" => serde_json::to_string_pretty(&serde_json::json!({ "clusters": clusters }))
+                .map_err(|e| format!("Failed to render duplicates: {}", e)),
+            "csv" => {
+                let mut lines = vec!["canonical_url,sources,items".to_string()];
+                for cluster in &clusters {
+                    lines.push(format!(
+                        "{},{},{}",
+                        csv_field(&cluster.canonical_url),
+                        csv_field(&cluster.sources.join(";")),
+                        cluster.items,
+                    ));
+                }
+                Ok(lines.join("\n"))
+            }
+            _ => Err(format!("Unknown format: {}", format)),
+        }
+    }
+
+    /// Render recent fetch attempts as `table` or `json` (JSON includes
+    /// captured response headers)
+    pub fn render_history(&self, format: &str, source: Option<&str>, limit: usize) -> Result<String, String> {
+        let history = self.storage.get_fetch_history(source, limit)?;
+
+        match format {
+            "table" => {
+                let mut table = self.new_table();
+                table.set_header(vec![
+                    Cell::new("Fetched At").add_attribute(Attribute::Bold),
+                    Cell::new("Source").add_attribute(Attribute::Bold),
+                    Cell::new("Status").add_attribute(Attribute::Bold),
+                    Cell::new("Items").add_attribute(Attribute::Bold),
+                    Cell::new("Duration").add_attribute(Attribute::Bold),
+                    Cell::new("Error").add_attribute(Attribute::Bold),
+                ]);
+
+                for entry in &history {
+                    table.add_row(vec![
+                        Cell::new(&entry.fetched_at),
+                        Cell::new(&entry.source),
+                        Cell::new(FetchStatus::from_db(&entry.status).map_or(entry.status.as_str(), |s| s.label())),
+                        Cell::new(with_skipped(entry.items_count, entry.skipped_count as u64)),
+                        Cell::new(format!("{}ms", entry.duration_ms)),
+                        Cell::new(entry.error_message.as_deref().unwrap_or("")),
+                    ]);
+                }
+
+                Ok(table.to_string())
+            }
+            "json" => serde_json::to_string_pretty(&serde_json::json!({ "history": history }))
+                .map_err(|e| format!("Failed to render history: {}", e)),
+            _ => Err(format!("Unknown format: {}", format)),
+        }
+    }
+
+    /// Render stored items matching `query`, newest first, as `table`
+    /// (titles truncated), `json` or `csv` (full records)
+    pub fn render_items(&self, format: &str, query: &ItemQuery) -> Result<String, String> {
+        let items = self.storage.query_items(query)?;
+
+        self.format_items(format, &items, None)
+    }
+
+    /// Like `render_items`, but items from different sources that share a
+    /// canonical URL are shown once, listing every source that carried them.
+    /// Stored rows are left as they are
+    pub fn render_deduped_items(&self, format: &str, query: &ItemQuery) -> Result<String, String> {
+        let (items, sources): (Vec<FeedItem>, Vec<Vec<String>>) =
+            group_by_canonical_url(self.storage.query_items(query)?, self.storage.tracking_params()).into_iter().unzip();
+
+        self.format_items(format, &items, Some(&sources))
+    }
+
+    /// Write stored items matching `query` as NDJSON, one `json`-format item
+    /// per line (with `sources` when deduplicating), reading `page_size` rows
+    /// at a time and flushing after each line; returns the number of items
+    /// written. Deduplicating reads the items twice: first to collect each
+    /// canonical URL's sources, then to write the newest item of each
+    pub fn write_items_ndjson<W: std::io::Write>(
+        &self,
+        out: &mut W,
+        query: &ItemQuery,
+        dedup: bool,
+        page_size: usize,
+    ) -> Result<usize, String> {
+        if page_size == 0 {
+            return Err("page size must be positive".to_string());
+        }
+        let tracking_params = self.storage.tracking_params();
+
+        // Canonical URL -> (id of its newest item, every source that carried it)
+        let mut groups: HashMap<String, (String, Vec<String>)> = HashMap::new();
+        if dedup {
+            self.for_each_item(query, page_size, |item| {
+                let (_, sources) = groups.entry(canonical_url(&item.url, tracking_params))
+                    .or_insert_with(|| (item.id.clone(), Vec::new()));
+                if !sources.contains(&item.source) {
+                    sources.push(item.source.clone());
+                }
+                Ok(())
+            })?;
+        }
+
+        let mut written = 0;
+        self.for_each_item(query, page_size, |item| {
+            let mut value = serde_json::to_value(item)
+                .map_err(|e| format!("Failed to serialize item: {}", e))?;
+            if dedup {
+                match groups.get(&canonical_url(&item.url, tracking_params)) {
+                    Some((id, sources)) if *id == item.id => value["sources"] = serde_json::json!(sources),
+                    _ => return Ok(()),
+                }
+            }
+            write_ndjson_line(out, &value)?;
+            written += 1;
+            Ok(())
+        })?;
+
+        Ok(written)
+    }
+
+    /// Call `f` with each stored item matching `query` (up to its `limit`),
+    /// reading `page_size` rows at a time
+    fn for_each_item(
+        &self,
+        query: &ItemQuery,
+        page_size: usize,
+        mut f: impl FnMut(&FeedItem) -> Result<(), String>,
+    ) -> Result<(), String> {
+        let mut remaining = query.limit.unwrap_or(usize::MAX);
+        let mut last: Option<FeedItem> = None;
+
+        while remaining > 0 {
+            let size = page_size.min(remaining);
+            let page = self.storage.query_items_page(query, last.as_ref(), size)?;
+            for item in &page {
+                f(item)?;
+            }
+
+            remaining -= page.len();
+            if page.len() < size {
+                break;
+            }
+            last = page.into_iter().last();
+        }
+
+        Ok(())
+    }
+
+    /// Render the best title matches for `query` like `render_items`
+    pub fn render_search(&self, format: &str, query: &str, limit: usize) -> Result<String, String> {
+        let items = self.storage.search(query, limit)?;
+        self.format_items(format, &items, None)
+    }
+
+    /// `sources`, when given, lists every source of each item in place of
+    /// its own
+    fn format_items(&self, format: &str, items: &[FeedItem], sources: Option<&[Vec<String>]>) -> Result<String, String> {
+        let item_sources = |i: usize, separator: &str| match sources {
+            Some(sources) => sources[i].join(separator),
+            None => items[i].source.clone(),
+        };
+
+        match format {
+            "table" => {
+                let mut table = self.new_table();
+                table.set_header(vec![
+                    Cell::new(if sources.is_some() { "Sources" } else { "Source" }).add_attribute(Attribute::Bold),
+                    Cell::new("Title").add_attribute(Attribute::Bold),
+                    Cell::new("URL").add_attribute(Attribute::Bold),
+                    Cell::new("Timestamp").add_attribute(Attribute::Bold),
+                ]);
+
+                for (i, item) in items.iter().enumerate() {
+                    table.add_row(vec![
+                        Cell::new(item_sources(i, ", ")),
+                        Cell::new(truncate_chars(&item.title, ITEM_TITLE_WIDTH)),
+                        Cell::new(&item.url),
+                        Cell::new(item.timestamp.as_deref().unwrap_or("")),
+                    ]);
+                }
+
+                Ok(format!("{}\n\n{} items", table, items.len()))
+            }
+            "json" => {
+                let mut json = serde_json::json!({ "items": items });
+                if let Some(sources) = sources {
+                    for (item, sources) in json["items"].as_array_mut().into_iter().flatten().zip(sources) {
+                        item["sources"] = serde_json::json!(sources);
+                    }
+                }
+                serde_json::to_string_pretty(&json).map_err(|e| format!("Failed to render items: {}", e))
+            }
+            "csv" => {
+                let source_header = if sources.is_some() { "sources" } else { "source" };
+                let mut lines = vec![format!("id,title,url,{},timestamp,tags", source_header)];
+                for (i, item) in items.iter().enumerate() {
+                    lines.push(format!(
+                        "{},{},{},{},{},{}",
+                        csv_field(&item.id),
+                        csv_field(&item.title),
+                        csv_field(&item.url),
+                        csv_field(&item_sources(i, ";")),
+                        csv_field(item.timestamp.as_deref().unwrap_or("")),
+                        csv_field(&item.tags.join(";")),
+                    ));
+                }
+                Ok(lines.join("\n"))
+            }
+            "markdown" => Ok(render_markdown(items)),
+            "rss" => Ok(render_rss(items, |i| match sources {
+                Some(sources) => sources[i].clone(),
+                None => vec![items[i].source.clone()],
+            })),
+            _ => Err(format!("Unknown format: {}", format)),
+        }
+    }
+
+    /// Sources present in the database, flagged when no longer in the config
+    pub fn db_sources(&self, config: &Config) -> Result<Vec<DbSource>, String> {
+        let sources = self.storage.get_db_sources()?;
+
+        Ok(sources.into_iter()
+            .map(|source| {
+                let orphaned = !config.feeds.iter().any(|f| f.name == source);
+                DbSource { source, orphaned }
+            })
+            .collect())
+    }
+
+    pub fn list_db_sources(&self, config: &Config) -> Result<(), String> {
+        let sources = self.db_sources(config)?;
+
+        let mut table = self.new_table();
+        table.set_header(vec![
+            Cell::new("Source").add_attribute(Attribute::Bold),
+            Cell::new("Status").add_attribute(Attribute::Bold),
+        ]);
+
+        for db_source in &sources {
+            let status = if db_source.orphaned {
+                "✗ Orphaned"
+            } else {
+                "✓ In config"
+            };
+
+            table.add_row(vec![
+                Cell::new(&db_source.source),
+                Cell::new(status),
+            ]);
+        }
+
         println!("{}", table);
 
+        let orphaned = sources.iter().filter(|s| s.orphaned).count();
+        println!("\n{} sources in database, {} orphaned", sources.len(), orphaned);
+
         Ok(())
     }
 }
@@ -1,11 +1,163 @@
-use crate::fetcher::FetchResult;
-use crate::models::{FeedItem, FetchLog};
-use rusqlite::{params, Connection, Result as SqliteResult};
+use crate::config::{Config, Feed};
+use crate::fetcher::{CacheValidators, FetchResult};
+use crate::models::{canonical_url, FeedItem, FetchLog, FetchStatus};
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::Path;
-use std::time::SystemTime;
+
+/// Columns read by `item_from_row`, in order
+const ITEM_COLUMNS: &str = "id, title, url, source, timestamp, tags, raw_data, summary, reading_time";
+const ITEM_COLUMN_COUNT: usize = 9;
+const FETCH_LOG_COLUMNS: &str = "source, fetched_at, status, items_count, error_message, duration_ms, captured_headers, cert_expires_at, body_hash, gone, mirror_url, skipped_count";
+
+type Migration = fn(&Connection) -> Result<(), String>;
+
+/// Schema migrations in order; `schema_version` records how many have run.
+/// Databases created before versioning start at 0 but may already have some
+/// of these tables and columns, so every step must be safe to re-apply
+const MIGRATIONS: &[Migration] = &[
+    migrate_initial_schema,
+    migrate_item_details,
+    migrate_fetch_log_details,
+    migrate_source_tables,
+    migrate_content_hash,
+    migrate_title_search,
+    migrate_skipped_count,
+];
+
+/// Schema version of a fully migrated database
+pub const SCHEMA_VERSION: usize = MIGRATIONS.len();
+
+fn migrate_initial_schema(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS feed_items (
+            id TEXT PRIMARY KEY,
+            title TEXT NOT NULL,
+            url TEXT NOT NULL,
+            source TEXT NOT NULL,
+            timestamp TEXT,
+            tags TEXT,
+            raw_data TEXT,
+            created_at TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS fetch_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            source TEXT NOT NULL,
+            fetched_at TEXT NOT NULL,
+            status TEXT NOT NULL,
+            items_count INTEGER DEFAULT 0,
+            error_message TEXT,
+            duration_ms INTEGER
+        );
+        CREATE INDEX IF NOT EXISTS idx_feed_items_source ON feed_items(source);
+        CREATE INDEX IF NOT EXISTS idx_feed_items_timestamp ON feed_items(timestamp);
+        CREATE INDEX IF NOT EXISTS idx_fetch_log_source ON fetch_log(source);"
+    ).map_err(|e| format!("Failed to create tables: {}", e))
+}
+
+fn migrate_item_details(conn: &Connection) -> Result<(), String> {
+    add_column_if_missing(conn, "feed_items", "summary", "TEXT")?;
+    add_column_if_missing(conn, "feed_items", "reading_time", "INTEGER")?;
+    add_column_if_missing(conn, "feed_items", "link_status", "TEXT")?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_feed_items_created_at ON feed_items(created_at, id)",
+        [],
+    ).map_err(|e| format!("Failed to create index: {}", e))?;
+    Ok(())
+}
+
+fn migrate_fetch_log_details(conn: &Connection) -> Result<(), String> {
+    add_column_if_missing(conn, "fetch_log", "cert_expires_at", "TEXT")?;
+    add_column_if_missing(conn, "fetch_log", "body_hash", "TEXT")?;
+    add_column_if_missing(conn, "fetch_log", "captured_headers", "TEXT")?;
+    add_column_if_missing(conn, "fetch_log", "gone", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(conn, "fetch_log", "mirror_url", "TEXT")
+}
+
+fn migrate_source_tables(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS source_meta (
+            source TEXT PRIMARY KEY,
+            feed_title TEXT,
+            updated_at TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS fetch_cache (
+            source TEXT PRIMARY KEY,
+            etag TEXT,
+            last_modified TEXT,
+            updated_at TEXT NOT NULL
+        );"
+    ).map_err(|e| format!("Failed to create source tables: {}", e))
+}
+
+fn migrate_content_hash(conn: &Connection) -> Result<(), String> {
+    add_column_if_missing(conn, "feed_items", "content_hash", "TEXT")
+}
+
+/// FTS5 index over item titles, kept in sync by triggers (REPLACE deletes
+/// included, see `recursive_triggers`). Skipped when SQLite lacks FTS5, in
+/// which case `Storage::search` falls back to LIKE
+fn migrate_title_search(conn: &Connection) -> Result<(), String> {
+    let created = conn.execute(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS feed_items_fts USING fts5(title, content='feed_items')",
+        [],
+    );
+    match created {
+        Ok(_) => {}
+        Err(e) if e.to_string().contains("no such module") => return Ok(()),
+        Err(e) => return Err(format!("Failed to create search index: {}", e)),
+    }
+
+    conn.execute_batch(
+        "CREATE TRIGGER IF NOT EXISTS feed_items_fts_insert AFTER INSERT ON feed_items BEGIN
+            INSERT INTO feed_items_fts(rowid, title) VALUES (new.rowid, new.title);
+        END;
+        CREATE TRIGGER IF NOT EXISTS feed_items_fts_delete AFTER DELETE ON feed_items BEGIN
+            INSERT INTO feed_items_fts(feed_items_fts, rowid, title) VALUES ('delete', old.rowid, old.title);
+        END;
+        CREATE TRIGGER IF NOT EXISTS feed_items_fts_update AFTER UPDATE OF title ON feed_items BEGIN
+            INSERT INTO feed_items_fts(feed_items_fts, rowid, title) VALUES ('delete', old.rowid, old.title);
+            INSERT INTO feed_items_fts(rowid, title) VALUES (new.rowid, new.title);
+        END;
+        INSERT INTO feed_items_fts(feed_items_fts) VALUES ('rebuild');"
+    ).map_err(|e| format!("Failed to build search index: {}", e))
+}
+
+fn migrate_skipped_count(conn: &Connection) -> Result<(), String> {
+    add_column_if_missing(conn, "fetch_log", "skipped_count", "INTEGER NOT NULL DEFAULT 0")
+}
+
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, decl: &str) -> Result<(), String> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))
+        .map_err(|e| format!("Failed to inspect {} table: {}", table, e))?;
+
+    let exists = stmt.query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| format!("Failed to inspect {} table: {}", table, e))?
+        .filter_map(|r| r.ok())
+        .any(|name| name == column);
+
+    if !exists {
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, decl),
+            [],
+        ).map_err(|e| format!("Failed to add {}.{} column: {}", table, column, e))?;
+    }
+
+    Ok(())
+}
 
 pub struct Storage {
     conn: Connection,
+    feeds: HashMap<String, Feed>,
+    /// Former feed name -> current name
+    aliases: HashMap<String, String>,
+    retention_days: Option<u64>,
+    prune_on_store: bool,
+    log_fetches: bool,
+    circuit_break_threshold: Option<usize>,
+    /// Query parameters ignored when comparing item URLs
+    tracking_params: Vec<String>,
 }
 
 impl Storage {
@@ -13,156 +165,689 @@ impl Storage {
         let conn = Connection::open(path)
             .map_err(|e| format!("Failed to open database: {}", e))?;
 
-        let storage = Self { conn };
+        // So INSERT OR REPLACE fires the search index's delete trigger
+        conn.pragma_update(None, "recursive_triggers", true)
+            .map_err(|e| format!("Failed to configure database: {}", e))?;
+
+        let storage = Self::from_connection(conn);
         storage.init_schema()?;
         Ok(storage)
     }
 
+    /// Open an existing database without creating, migrating or writing to
+    /// it, e.g. to read fetch history for a dry run. Fails unless the schema
+    /// is exactly `SCHEMA_VERSION`
+    pub fn open_read_only<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX)
+            .map_err(|e| format!("Failed to open database: {}", e))?;
+
+        let storage = Self::from_connection(conn);
+        let version = storage.schema_version()?;
+        if version != SCHEMA_VERSION {
+            return Err(format!(
+                "database schema version {} doesn't match this feedpulse ({})",
+                version, SCHEMA_VERSION
+            ));
+        }
+        Ok(storage)
+    }
+
+    fn from_connection(conn: Connection) -> Self {
+        Self {
+            conn,
+            feeds: HashMap::new(),
+            aliases: HashMap::new(),
+            retention_days: None,
+            prune_on_store: false,
+            log_fetches: true,
+            circuit_break_threshold: None,
+            tracking_params: crate::config::default_tracking_params(),
+        }
+    }
+
+    /// Make per-feed storage options (e.g. `merge_tags`) and retention
+    /// settings available when storing and pruning
+    pub fn with_config(mut self, config: &Config) -> Self {
+        self.feeds = config.feeds.iter()
+            .map(|f| (f.name.clone(), f.clone()))
+            .collect();
+        self.aliases = config.feeds.iter()
+            .flat_map(|f| f.aliases.iter().map(|alias| (alias.clone(), f.name.clone())))
+            .collect();
+        self.retention_days = config.settings.retention_days;
+        self.prune_on_store = config.settings.prune_on_store;
+        self.log_fetches = config.settings.log_fetches;
+        self.circuit_break_threshold = config.settings.circuit_break_threshold;
+        self.tracking_params = config.settings.tracking_params.clone();
+        self
+    }
+
+    /// Configured `tracking_params`, for grouping items by canonical URL
+    pub fn tracking_params(&self) -> &[String] {
+        &self.tracking_params
+    }
+
+    fn feed(&self, source: &str) -> Option<&Feed> {
+        self.feeds.get(source)
+    }
+
+    /// Current feed name for a stored source name (itself unless it is one
+    /// of a feed's `aliases`)
+    fn canonical_source<'a>(&'a self, source: &'a str) -> &'a str {
+        self.aliases.get(source).map_or(source, String::as_str)
+    }
+
+    /// A source name plus its feed's former names
+    fn source_names<'a>(&'a self, source: &'a str) -> Vec<&'a str> {
+        let aliases = self.feed(source).map(|f| f.aliases.as_slice()).unwrap_or_default();
+        std::iter::once(source).chain(aliases.iter().map(String::as_str)).collect()
+    }
+
+    /// Fold stats recorded under former feed names into the current name
+    fn merge_aliased_stats(&self, stats: Vec<SourceStat>) -> Vec<SourceStat> {
+        let mut merged: Vec<SourceStat> = Vec::new();
+
+        for stat in stats {
+            let source = self.canonical_source(&stat.source).to_string();
+            match merged.iter_mut().find(|m| m.source == source) {
+                Some(existing) => {
+                    existing.items += stat.items;
+                    existing.errors = existing.errors.zip(stat.errors).map(|(a, b)| a + b);
+                    existing.last_success = existing.last_success.take().max(stat.last_success);
+                    existing.degraded = existing.degraded.zip(stat.degraded).map(|(a, b)| a + b);
+                    existing.parse_errors = existing.parse_errors.zip(stat.parse_errors).map(|(a, b)| a + b);
+                    existing.skipped = existing.skipped.zip(stat.skipped).map(|(a, b)| a + b);
+                }
+                None => merged.push(SourceStat { source, ..stat }),
+            }
+        }
+
+        merged.sort_by(|a, b| a.source.cmp(&b.source));
+        merged
+    }
+
+    /// Bring the database up to `SCHEMA_VERSION`, applying each pending
+    /// migration in order inside a single transaction
     fn init_schema(&self) -> Result<(), String> {
-        self.conn.execute(
-            "CREATE TABLE IF NOT EXISTS feed_items (
-                id TEXT PRIMARY KEY,
-                title TEXT NOT NULL,
-                url TEXT NOT NULL,
-                source TEXT NOT NULL,
-                timestamp TEXT,
-                tags TEXT,
-                raw_data TEXT,
-                created_at TEXT NOT NULL
-            )",
-            [],
-        ).map_err(|e| format!("Failed to create feed_items table: {}", e))?;
+        let tx = self.conn.unchecked_transaction()
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
 
-        self.conn.execute(
-            "CREATE TABLE IF NOT EXISTS fetch_log (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                source TEXT NOT NULL,
-                fetched_at TEXT NOT NULL,
-                status TEXT NOT NULL,
-                items_count INTEGER DEFAULT 0,
-                error_message TEXT,
-                duration_ms INTEGER
-            )",
+        tx.execute(
+            "CREATE TABLE IF NOT EXISTS schema_version (version INTEGER NOT NULL)",
             [],
-        ).map_err(|e| format!("Failed to create fetch_log table: {}", e))?;
+        ).map_err(|e| format!("Failed to create schema_version table: {}", e))?;
 
-        self.conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_feed_items_source ON feed_items(source)",
-            [],
-        ).map_err(|e| format!("Failed to create index: {}", e))?;
+        let current = Self::read_schema_version(&tx)?;
+        if current > SCHEMA_VERSION {
+            return Err(format!(
+                "database schema version {} is newer than this feedpulse supports ({})",
+                current, SCHEMA_VERSION
+            ));
+        }
 
-        self.conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_feed_items_timestamp ON feed_items(timestamp)",
-            [],
-        ).map_err(|e| format!("Failed to create index: {}", e))?;
+        for (version, migrate) in MIGRATIONS.iter().enumerate().skip(current) {
+            migrate(&tx).map_err(|e| format!("Migration to schema version {} failed: {}", version + 1, e))?;
+        }
 
-        self.conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_fetch_log_source ON fetch_log(source)",
-            [],
-        ).map_err(|e| format!("Failed to create index: {}", e))?;
+        if current < SCHEMA_VERSION {
+            tx.execute("DELETE FROM schema_version", [])
+                .and_then(|_| tx.execute("INSERT INTO schema_version (version) VALUES (?1)", params![SCHEMA_VERSION as i64]))
+                .map_err(|e| format!("Failed to record schema version: {}", e))?;
+        }
 
-        Ok(())
+        tx.commit().map_err(|e| format!("Failed to commit transaction: {}", e))
     }
 
-    pub fn store_results(&self, results: &mut [FetchResult]) -> Result<(), String> {
+    /// Schema version recorded in the database (0 before any migration ran)
+    pub fn schema_version(&self) -> Result<usize, String> {
+        Self::read_schema_version(&self.conn)
+    }
+
+    fn read_schema_version(conn: &Connection) -> Result<usize, String> {
+        conn.query_row("SELECT COALESCE(MAX(version), 0) FROM schema_version", [], |row| row.get::<_, i64>(0))
+            .map(|version| version as usize)
+            .map_err(|e| format!("Failed to read schema version: {}", e))
+    }
+
+    /// Store each result's items and log the fetch. Sets each result's
+    /// `new_items`/`new_urls` and returns the counts for the whole batch
+    pub fn store_results(&self, results: &mut [FetchResult]) -> Result<StoreOutcome, String> {
         let tx = self.conn.unchecked_transaction()
             .map_err(|e| format!("Failed to start transaction: {}", e))?;
 
         let now = Self::current_timestamp();
+        let mut outcome = StoreOutcome::default();
 
         for result in results.iter_mut() {
-            // Count existing items
-            let mut new_count = 0;
+            let mut new_urls = Vec::new();
+
+            let merge_tags = self.feed(&result.source).is_some_and(|f| f.merge_tags);
+            let mirror_latest = self.feed(&result.source).is_some_and(|f| f.mirror_latest);
+
+            // Store items
             for item in &result.items {
-                let exists: bool = tx.query_row(
-                    "SELECT 1 FROM feed_items WHERE id = ?1",
+                let tags = if merge_tags {
+                    Self::merged_tags(&tx, item)?
+                } else {
+                    item.tags.clone()
+                };
+                let tags_json = serde_json::to_string(&tags).unwrap_or_default();
+
+                let hash = content_hash(&item.title, item.timestamp.as_deref(), &tags_json, item.summary.as_deref());
+
+                let stored: Option<StoredFields> = tx.query_row(
+                    "SELECT title, timestamp, tags, summary, content_hash FROM feed_items WHERE id = ?1",
                     params![&item.id],
-                    |_| Ok(true),
-                ).unwrap_or(false);
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+                ).optional().map_err(|e| format!("Failed to read existing item: {}", e))?;
+
+                match stored {
+                    None => {
+                        new_urls.push(item.url.clone());
+                        outcome.new_item_ids.push(item.id.clone());
+
+                        tx.execute(
+                            "INSERT INTO feed_items (id, title, url, source, timestamp, tags, raw_data, created_at, summary, reading_time, content_hash)
+                             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                            params![
+                                &item.id,
+                                &item.title,
+                                &item.url,
+                                &item.source,
+                                &item.timestamp,
+                                &tags_json,
+                                &item.raw_data,
+                                &now,
+                                &item.summary,
+                                &item.reading_time_minutes,
+                                &hash,
+                            ],
+                        ).map_err(|e| format!("Failed to insert item: {}", e))?;
+                    }
+                    Some((title, timestamp, stored_tags, summary, stored_hash)) => {
+                        // Rows stored before content_hash existed are hashed on the fly
+                        let stored_hash = stored_hash.unwrap_or_else(|| {
+                            content_hash(&title, timestamp.as_deref(), stored_tags.as_deref().unwrap_or_default(), summary.as_deref())
+                        });
+
+                        // Identical refetches leave the row (and its created_at) alone
+                        if stored_hash == hash {
+                            outcome.unchanged += 1;
+                            continue;
+                        }
 
-                if !exists {
-                    new_count += 1;
+                        outcome.updated += 1;
+                        tx.execute(
+                            "UPDATE feed_items
+                             SET title = ?2, timestamp = ?3, tags = ?4, raw_data = ?5, summary = ?6, reading_time = ?7, content_hash = ?8
+                             WHERE id = ?1",
+                            params![
+                                &item.id,
+                                &item.title,
+                                &item.timestamp,
+                                &tags_json,
+                                &item.raw_data,
+                                &item.summary,
+                                &item.reading_time_minutes,
+                                &hash,
+                            ],
+                        ).map_err(|e| format!("Failed to update item: {}", e))?;
+                    }
                 }
             }
 
-            result.new_items = new_count;
+            result.new_items = new_urls.len();
+            result.new_urls = new_urls;
 
-            // Store items
-            for item in &result.items {
-                let tags_json = serde_json::to_string(&item.tags).unwrap_or_default();
-                
+            // Validators describe the primary URL's full response, so a 304,
+            // a mirror or an error leaves the cached ones in place
+            if result.error.is_none() && !result.not_modified && result.mirror_url.is_none() {
+                if result.validators.is_empty() {
+                    tx.execute("DELETE FROM fetch_cache WHERE source = ?1", params![&result.source])
+                        .map_err(|e| format!("Failed to clear fetch cache: {}", e))?;
+                } else {
+                    tx.execute(
+                        "INSERT OR REPLACE INTO fetch_cache (source, etag, last_modified, updated_at) VALUES (?1, ?2, ?3, ?4)",
+                        params![&result.source, &result.validators.etag, &result.validators.last_modified, &now],
+                    ).map_err(|e| format!("Failed to store fetch cache: {}", e))?;
+                }
+            }
+
+            if let Some(feed_title) = &result.feed_title {
                 tx.execute(
-                    "INSERT OR REPLACE INTO feed_items (id, title, url, source, timestamp, tags, raw_data, created_at)
-                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-                    params![
-                        &item.id,
-                        &item.title,
-                        &item.url,
-                        &item.source,
-                        &item.timestamp,
-                        &tags_json,
-                        &item.raw_data,
-                        &now,
-                    ],
-                ).map_err(|e| format!("Failed to insert item: {}", e))?;
-            }
-
-            // Log fetch
-            let status = if result.error.is_none() { "success" } else { "error" };
+                    "INSERT OR REPLACE INTO source_meta (source, feed_title, updated_at) VALUES (?1, ?2, ?3)",
+                    params![&result.source, feed_title, &now],
+                ).map_err(|e| format!("Failed to store source metadata: {}", e))?;
+            }
+
+            // Never prune on a failed, degraded or empty fetch, which is more
+            // likely a broken response than a feed that really emptied out
+            if mirror_latest && result.status() == FetchStatus::Success && !result.items.is_empty() {
+                Self::prune_source(&tx, &result.source, &result.items)?;
+            }
+
+            if !self.log_fetches {
+                continue;
+            }
+
+            // Log fetch; a degraded fetch keeps its warning in error_message
+            let status = result.status().as_str();
+            let captured_headers = if result.captured_headers.is_empty() {
+                None
+            } else {
+                serde_json::to_string(&result.captured_headers).ok()
+            };
             tx.execute(
-                "INSERT INTO fetch_log (source, fetched_at, status, items_count, error_message, duration_ms)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                "INSERT INTO fetch_log (source, fetched_at, status, items_count, error_message, duration_ms, cert_expires_at, body_hash, captured_headers, gone, mirror_url, skipped_count)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
                 params![
                     &result.source,
                     &now,
                     status,
                     result.items.len() as i64,
-                    &result.error,
+                    result.error.as_ref().or(result.warning.as_ref()),
                     result.duration_ms as i64,
+                    &result.cert_expires_at,
+                    &result.body_hash,
+                    &captured_headers,
+                    result.gone,
+                    &result.mirror_url,
+                    result.parse_warnings.len() as i64,
                 ],
             ).map_err(|e| format!("Failed to insert fetch log: {}", e))?;
         }
 
         tx.commit().map_err(|e| format!("Failed to commit transaction: {}", e))?;
 
+        if self.prune_on_store {
+            self.prune_expired()?;
+        }
+
+        outcome.new = outcome.new_item_ids.len();
+        outcome.total = outcome.new + outcome.updated + outcome.unchanged;
+
+        Ok(outcome)
+    }
+
+    /// Delete items stored longer ago than their source's retention
+    /// (per-feed `retention_days`, else the global setting); returns the
+    /// number deleted per source, omitting sources with nothing deleted
+    pub fn prune_expired(&self) -> Result<Vec<(String, usize)>, String> {
+        let mut stmt = self.conn.prepare("SELECT DISTINCT source FROM feed_items ORDER BY source")
+            .map_err(|e| format!("Failed to prepare sources query: {}", e))?;
+        let sources: Vec<String> = stmt.query_map([], |row| row.get(0))
+            .map_err(|e| format!("Failed to query sources: {}", e))?
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("Failed to read source row: {}", e))?;
+
+        let mut pruned = Vec::new();
+        for source in sources {
+            let retention_days = self.feed(&source)
+                .and_then(|f| f.retention_days)
+                .or(self.retention_days);
+            // Retention too long to represent can't have expired anything
+            let Some(cutoff) = retention_days
+                .and_then(|days| i64::try_from(days).ok())
+                .and_then(chrono::Duration::try_days)
+                .and_then(|age| chrono::Utc::now().checked_sub_signed(age))
+            else {
+                continue;
+            };
+            let deleted = self.conn.execute(
+                "DELETE FROM feed_items WHERE source = ?1 AND created_at < ?2",
+                params![&source, cutoff.to_rfc3339()],
+            ).map_err(|e| format!("Failed to prune {}: {}", source, e))?;
+
+            if deleted > 0 {
+                pruned.push((source, deleted));
+            }
+        }
+
+        Ok(pruned)
+    }
+
+    /// Delete items stored and fetch_log entries recorded before `cutoff`
+    /// (RFC 3339) in one transaction; returns `(items, fetch_log entries)`.
+    /// Each source's latest fetch and its current failure streak are kept,
+    /// so last-fetch status and circuit breakers survive the prune. A dry
+    /// run counts the same rows and rolls back
+    pub fn prune(&self, cutoff: &str, dry_run: bool) -> Result<(usize, usize), String> {
+        let tx = self.conn.unchecked_transaction()
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let items = tx.execute("DELETE FROM feed_items WHERE created_at < ?1", params![cutoff])
+            .map_err(|e| format!("Failed to prune items: {}", e))?;
+        let log_entries = tx.execute(
+            "DELETE FROM fetch_log
+             WHERE fetched_at < ?1
+               AND id != (SELECT MAX(id) FROM fetch_log s WHERE s.source = fetch_log.source)
+               AND id <= COALESCE(
+                   (SELECT MAX(id) FROM fetch_log s
+                    WHERE s.source = fetch_log.source AND s.status NOT IN ('error', 'parse_error')),
+                   0)",
+            params![cutoff],
+        )
+            .map_err(|e| format!("Failed to prune fetch log: {}", e))?;
+
+        if dry_run {
+            tx.rollback().map_err(|e| format!("Failed to roll back transaction: {}", e))?;
+        } else {
+            tx.commit().map_err(|e| format!("Failed to commit transaction: {}", e))?;
+        }
+
+        Ok((items, log_entries))
+    }
+
+    /// Rebuild the database file to return space freed by deletes to the
+    /// filesystem. Must not be called inside a transaction
+    pub fn vacuum(&self) -> Result<(), String> {
+        self.conn.execute_batch("VACUUM")
+            .map_err(|e| format!("Failed to vacuum database: {}", e))?;
+
+        // VACUUM may renumber feed_items rowids, which the index refers to
+        if self.has_full_text_search()? {
+            self.conn.execute("INSERT INTO feed_items_fts(feed_items_fts) VALUES ('rebuild')", [])
+                .map_err(|e| format!("Failed to rebuild search index: {}", e))?;
+        }
+
         Ok(())
     }
 
+    /// Whether titles are indexed with FTS5; without it `search` falls back
+    /// to a slower substring match
+    pub fn has_full_text_search(&self) -> Result<bool, String> {
+        self.conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE name = 'feed_items_fts')",
+            [],
+            |row| row.get(0),
+        ).map_err(|e| format!("Failed to inspect schema: {}", e))
+    }
+
+    /// Items whose title contains every word of `query`, best match first
+    /// (newest first in the LIKE fallback)
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<FeedItem>, String> {
+        let words: Vec<&str> = query.split_whitespace().collect();
+        if words.is_empty() {
+            return Ok(Vec::new());
+        }
+        let columns = ITEM_COLUMNS.split(", ")
+            .map(|column| format!("feed_items.{}", column))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let (sql, params): (String, Vec<String>) = if self.has_full_text_search()? {
+            // Quote each word so punctuation isn't read as FTS5 syntax
+            let phrase = words.iter()
+                .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+                .collect::<Vec<_>>()
+                .join(" ");
+            (
+                format!(
+                    "SELECT {} FROM feed_items_fts JOIN feed_items ON feed_items.rowid = feed_items_fts.rowid
+                     WHERE feed_items_fts MATCH ? ORDER BY rank LIMIT ?",
+                    columns
+                ),
+                vec![phrase],
+            )
+        } else {
+            let escaped = words.iter()
+                .map(|word| format!("%{}%", word.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")))
+                .collect();
+            (
+                format!(
+                    "SELECT {} FROM feed_items WHERE {} ORDER BY timestamp DESC LIMIT ?",
+                    columns,
+                    vec!["title LIKE ? ESCAPE '\\'"; words.len()].join(" AND ")
+                ),
+                escaped,
+            )
+        };
+
+        let mut stmt = self.conn.prepare(&sql)
+            .map_err(|e| format!("Failed to prepare search: {}", e))?;
+
+        let limit = limit as i64;
+        let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter()
+            .map(|p| p as &dyn rusqlite::ToSql)
+            .chain(std::iter::once(&limit as &dyn rusqlite::ToSql))
+            .collect();
+
+        let rows = stmt.query_map(param_refs.as_slice(), Self::item_from_row)
+            .map_err(|e| format!("Failed to search items: {}", e))?;
+
+        let mut items = Vec::new();
+        for row in rows {
+            let mut item = row.map_err(|e| format!("Failed to read row: {}", e))?;
+            item.source = self.canonical_source(&item.source).to_string();
+            items.push(item);
+        }
+
+        Ok(items)
+    }
+
+    /// Merge stored items that share a canonical URL into the earliest-created
+    /// row (unioning tags, filling missing fields from the duplicates) and
+    /// delete the rest; returns the number of rows merged away
+    pub fn dedupe(&self) -> Result<usize, String> {
+        let tx = self.conn.unchecked_transaction()
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let items = {
+            let mut stmt = tx.prepare(
+                &format!("SELECT {} FROM feed_items ORDER BY created_at, rowid", ITEM_COLUMNS)
+            ).map_err(|e| format!("Failed to prepare dedupe query: {}", e))?;
+
+            let rows = stmt.query_map([], Self::item_from_row)
+                .map_err(|e| format!("Failed to query items: {}", e))?;
+
+            let mut items = Vec::new();
+            for row in rows {
+                items.push(row.map_err(|e| format!("Failed to read row: {}", e))?);
+            }
+            items
+        };
+
+        let mut groups: Vec<Vec<FeedItem>> = Vec::new();
+        let mut group_index: HashMap<String, usize> = HashMap::new();
+        for item in items {
+            let key = canonical_url(&item.url, &self.tracking_params);
+            match group_index.get(&key) {
+                Some(&idx) => groups[idx].push(item),
+                None => {
+                    group_index.insert(key, groups.len());
+                    groups.push(vec![item]);
+                }
+            }
+        }
+
+        let mut merged = 0;
+        for group in groups.into_iter().filter(|g| g.len() > 1) {
+            let mut duplicates = group.into_iter();
+            let Some(mut survivor) = duplicates.next() else {
+                continue;
+            };
+
+            for duplicate in duplicates {
+                if survivor.title.trim().is_empty() {
+                    survivor.title = duplicate.title;
+                }
+                if survivor.timestamp.is_none() {
+                    survivor.timestamp = duplicate.timestamp;
+                }
+                if survivor.raw_data.as_deref().is_none_or(str::is_empty) {
+                    survivor.raw_data = duplicate.raw_data;
+                }
+                if survivor.summary.is_none() {
+                    survivor.summary = duplicate.summary;
+                    survivor.reading_time_minutes = duplicate.reading_time_minutes;
+                }
+                for tag in duplicate.tags {
+                    if !survivor.tags.contains(&tag) {
+                        survivor.tags.push(tag);
+                    }
+                }
+
+                tx.execute("DELETE FROM feed_items WHERE id = ?1", params![&duplicate.id])
+                    .map_err(|e| format!("Failed to delete duplicate: {}", e))?;
+                merged += 1;
+            }
+
+            let tags_json = serde_json::to_string(&survivor.tags).unwrap_or_default();
+            tx.execute(
+                "UPDATE feed_items SET title = ?2, timestamp = ?3, tags = ?4, raw_data = ?5, summary = ?6, reading_time = ?7
+                 WHERE id = ?1",
+                params![
+                    &survivor.id,
+                    &survivor.title,
+                    &survivor.timestamp,
+                    &tags_json,
+                    &survivor.raw_data,
+                    &survivor.summary,
+                    &survivor.reading_time_minutes,
+                ],
+            ).map_err(|e| format!("Failed to update merged item: {}", e))?;
+        }
+
+        tx.commit().map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Ok(merged)
+    }
+
+    /// Delete a source's stored items that are not in `keep`
+    fn prune_source(conn: &Connection, source: &str, keep: &[FeedItem]) -> Result<(), String> {
+        let placeholders = vec!["?"; keep.len()].join(", ");
+        let query = format!(
+            "DELETE FROM feed_items WHERE source = ? AND id NOT IN ({})",
+            placeholders
+        );
+
+        let params = std::iter::once(source).chain(keep.iter().map(|item| item.id.as_str()));
+        conn.execute(&query, rusqlite::params_from_iter(params))
+            .map_err(|e| format!("Failed to prune {}: {}", source, e))?;
+
+        Ok(())
+    }
+
+    /// Union of the stored tags for an item and its incoming tags
+    fn merged_tags(conn: &Connection, item: &FeedItem) -> Result<Vec<String>, String> {
+        let existing: Option<String> = conn.query_row(
+            "SELECT tags FROM feed_items WHERE id = ?1",
+            params![&item.id],
+            |row| row.get(0),
+        ).optional().map_err(|e| format!("Failed to read existing tags: {}", e))?.flatten();
+
+        let mut tags: Vec<String> = existing
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+
+        for tag in &item.tags {
+            if !tags.contains(tag) {
+                tags.push(tag.clone());
+            }
+        }
+
+        Ok(tags)
+    }
+
+    /// Stored items, newest first; `since` is an RFC 3339 cutoff on `created_at`
     pub fn get_items(&self, source: Option<&str>, since: Option<&str>) -> Result<Vec<FeedItem>, String> {
-        let mut query = "SELECT id, title, url, source, timestamp, tags, raw_data FROM feed_items WHERE 1=1".to_string();
+        self.query_items(&ItemQuery {
+            source: source.map(str::to_string),
+            since: since.map(str::to_string),
+            ..Default::default()
+        })
+    }
+
+    /// Stored items matching `filter`, newest first
+    pub fn query_items(&self, filter: &ItemQuery) -> Result<Vec<FeedItem>, String> {
+        self.query_items_after(filter, None, filter.limit)
+    }
+
+    /// Up to `page_size` items matching `filter` (ignoring its `limit`) in
+    /// `query_items` order, starting after `after`, the last item of the
+    /// previous page
+    pub fn query_items_page(&self, filter: &ItemQuery, after: Option<&FeedItem>, page_size: usize) -> Result<Vec<FeedItem>, String> {
+        self.query_items_after(filter, after, Some(page_size))
+    }
+
+    fn query_items_after(&self, filter: &ItemQuery, after: Option<&FeedItem>, limit: Option<usize>) -> Result<Vec<FeedItem>, String> {
+        let (source, since) = (filter.source.as_deref(), filter.since.as_deref());
+        let mut query = format!("SELECT {} FROM feed_items WHERE 1=1", ITEM_COLUMNS);
         let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
 
         if let Some(src) = source {
-            query.push_str(" AND source = ?");
-            params.push(Box::new(src.to_string()));
+            let names = self.source_names(src);
+            query.push_str(&format!(" AND source IN ({})", vec!["?"; names.len()].join(", ")));
+            params.extend(names.into_iter().map(|name| Box::new(name.to_string()) as Box<dyn rusqlite::ToSql>));
         }
 
-        if let Some(_since_val) = since {
-            // TODO: Parse duration and convert to timestamp
-            query.push_str(" AND timestamp > ?");
-            // For now, skip this filter
+        if let Some(cutoff) = since {
+            query.push_str(" AND created_at > ?");
+            params.push(Box::new(cutoff.to_string()));
+        }
+
+        query.push_str(&tag_filter(filter.tags.len()));
+        params.extend(filter.tags.iter().map(|tag| Box::new(tag.clone()) as Box<dyn rusqlite::ToSql>));
+
+        // Keyset cursor for `timestamp DESC, id`, where NULL timestamps sort last
+        if let Some(last) = after {
+            match &last.timestamp {
+                Some(timestamp) => {
+                    query.push_str(" AND (timestamp < ? OR (timestamp = ? AND id > ?) OR timestamp IS NULL)");
+                    params.push(Box::new(timestamp.clone()));
+                    params.push(Box::new(timestamp.clone()));
+                }
+                None => query.push_str(" AND timestamp IS NULL AND id > ?"),
+            }
+            params.push(Box::new(last.id.clone()));
         }
 
-        query.push_str(" ORDER BY timestamp DESC");
+        query.push_str(" ORDER BY timestamp DESC, id");
+
+        if let Some(limit) = limit {
+            query.push_str(" LIMIT ?");
+            params.push(Box::new(limit as i64));
+        }
 
         let mut stmt = self.conn.prepare(&query)
             .map_err(|e| format!("Failed to prepare query: {}", e))?;
 
         let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
 
-        let rows = stmt.query_map(param_refs.as_slice(), |row| {
-            let tags_json: String = row.get(5)?;
-            let tags: Vec<String> = serde_json::from_str(&tags_json).unwrap_or_default();
-
-            Ok(FeedItem {
-                id: row.get(0)?,
-                title: row.get(1)?,
-                url: row.get(2)?,
-                source: row.get(3)?,
-                timestamp: row.get(4)?,
-                tags,
-                raw_data: row.get(6)?,
-            })
+        let rows = stmt.query_map(param_refs.as_slice(), Self::item_from_row)
+            .map_err(|e| format!("Failed to query items: {}", e))?;
+
+        let mut items = Vec::new();
+        for row in rows {
+            let mut item = row.map_err(|e| format!("Failed to read row: {}", e))?;
+            item.source = self.canonical_source(&item.source).to_string();
+            items.push(item);
+        }
+
+        Ok(items)
+    }
+
+    /// One page of items in `(created_at, id)` order, starting after the
+    /// given `(created_at, id)` cursor; each item comes with its created_at
+    pub fn get_items_page(
+        &self,
+        source: Option<&str>,
+        after: Option<(&str, &str)>,
+        limit: usize,
+    ) -> Result<Vec<(FeedItem, String)>, String> {
+        let (after_created, after_id) = after.unzip();
+
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {}, created_at FROM feed_items
+             WHERE (?1 IS NULL OR source = ?1)
+               AND (?2 IS NULL OR created_at > ?2 OR (created_at = ?2 AND id > ?3))
+             ORDER BY created_at, id
+             LIMIT ?4",
+            ITEM_COLUMNS
+        )).map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+        let rows = stmt.query_map(params![source, after_created, after_id, limit as i64], |row| {
+            Ok((Self::item_from_row(row)?, row.get(ITEM_COLUMN_COUNT)?))
         }).map_err(|e| format!("Failed to query items: {}", e))?;
 
         let mut items = Vec::new();
@@ -173,23 +858,113 @@ impl Storage {
         Ok(items)
     }
 
-    pub fn get_source_stats(&self) -> Result<Vec<SourceStat>, String> {
-        let mut stmt = self.conn.prepare(
-            "SELECT 
+    /// Per-source fetch stats from fetch_log, or stored item counts only
+    /// when `log_fetches` is off; `limit` counts only each source's N most
+    /// recent entries, `since` (RFC 3339) only entries after it
+    pub fn get_source_stats(&self, limit: Option<usize>, since: Option<&str>) -> Result<Vec<SourceStat>, String> {
+        if !self.log_fetches {
+            let mut stats = self.merge_aliased_stats(self.get_item_source_stats(since, &[])?);
+            if let Some(limit) = limit {
+                for stat in &mut stats {
+                    stat.items = stat.items.min(limit as i64);
+                }
+            }
+            return Ok(stats);
+        }
+
+        // Fold aliases into one name before numbering rows, so `limit`
+        // counts a renamed feed's fetches under both names together
+        let mut aliases: Vec<(&String, &String)> = self.aliases.iter().collect();
+        aliases.sort();
+        let canonical = match aliases.len() {
+            0 => "source".to_string(),
+            n => format!(
+                "CASE source {} ELSE source END",
+                (0..n).map(|i| format!("WHEN ?{} THEN ?{}", 3 + 2 * i, 4 + 2 * i)).collect::<Vec<_>>().join(" ")
+            ),
+        };
+
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT
                 source,
                 COUNT(*) as items,
-                SUM(CASE WHEN status = 'error' THEN 1 ELSE 0 END) as errors,
-                MAX(CASE WHEN status = 'success' THEN fetched_at END) as last_success
-             FROM fetch_log
-             GROUP BY source"
-        ).map_err(|e| format!("Failed to prepare stats query: {}", e))?;
+                SUM(CASE WHEN status IN ('error', 'parse_error') THEN 1 ELSE 0 END) as errors,
+                MAX(CASE WHEN status IN ('success', 'degraded') THEN fetched_at END) as last_success,
+                SUM(CASE WHEN status = 'degraded' THEN 1 ELSE 0 END) as degraded,
+                SUM(CASE WHEN status = 'parse_error' THEN 1 ELSE 0 END) as parse_errors,
+                SUM(skipped_count) as skipped
+             FROM (
+                SELECT *, ROW_NUMBER() OVER (PARTITION BY source ORDER BY id DESC) AS recency
+                FROM (
+                    SELECT id, {} AS source, status, fetched_at, skipped_count
+                    FROM fetch_log
+                    WHERE ?2 IS NULL OR fetched_at > ?2
+                )
+             )
+             WHERE ?1 IS NULL OR recency <= ?1
+             GROUP BY source",
+            canonical
+        )).map_err(|e| format!("Failed to prepare stats query: {}", e))?;
 
-        let rows = stmt.query_map([], |row| {
+        let limit = limit.map(|n| n as i64);
+        let params: Vec<&dyn rusqlite::ToSql> = [&limit as &dyn rusqlite::ToSql, &since]
+            .into_iter()
+            .chain(aliases.iter().flat_map(|(alias, name)| [*alias as &dyn rusqlite::ToSql, *name]))
+            .collect();
+        let rows = stmt.query_map(params.as_slice(), |row| {
             Ok(SourceStat {
                 source: row.get(0)?,
                 items: row.get(1)?,
-                errors: row.get(2)?,
+                errors: Some(row.get(2)?),
                 last_success: row.get(3)?,
+                degraded: Some(row.get(4)?),
+                parse_errors: Some(row.get(5)?),
+                skipped: Some(row.get(6)?),
+                open_circuit: None,
+            })
+        }).map_err(|e| format!("Failed to query stats: {}", e))?;
+
+        let mut stats = Vec::new();
+        for row in rows {
+            stats.push(row.map_err(|e| format!("Failed to read stat row: {}", e))?);
+        }
+
+        let open_circuits = self.get_open_circuits()?;
+        let mut stats = self.merge_aliased_stats(stats);
+        for stat in &mut stats {
+            stat.open_circuit = open_circuits.get(&stat.source).copied();
+        }
+        Ok(stats)
+    }
+
+    /// Stored item counts per source for items carrying every one of
+    /// `tags`; fetch error stats don't apply and are left empty
+    pub fn get_tagged_source_stats(&self, tags: &[String], since: Option<&str>) -> Result<Vec<SourceStat>, String> {
+        Ok(self.merge_aliased_stats(self.get_item_source_stats(since, tags)?))
+    }
+
+    fn get_item_source_stats(&self, since: Option<&str>, tags: &[String]) -> Result<Vec<SourceStat>, String> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT source, COUNT(*) FROM feed_items
+             WHERE (?1 IS NULL OR created_at > ?1){}
+             GROUP BY source
+             ORDER BY source",
+            tag_filter(tags.len())
+        )).map_err(|e| format!("Failed to prepare stats query: {}", e))?;
+
+        let params: Vec<&dyn rusqlite::ToSql> = std::iter::once(&since as &dyn rusqlite::ToSql)
+            .chain(tags.iter().map(|tag| tag as &dyn rusqlite::ToSql))
+            .collect();
+        let rows = stmt.query_map(params.as_slice(), |row| {
+            Ok(SourceStat {
+                source: row.get(0)?,
+                items: row.get(1)?,
+                errors: None,
+                last_success: None,
+                degraded: None,
+                parse_errors: None,
+                skipped: None,
+                open_circuit: None,
             })
         }).map_err(|e| format!("Failed to query stats: {}", e))?;
 
@@ -201,6 +976,406 @@ impl Storage {
         Ok(stats)
     }
 
+    /// Count stored items per `created_at` bucket (`hour`, `day` or `week`),
+    /// optionally split by source
+    pub fn get_item_timeseries(
+        &self,
+        bucket: &str,
+        source: Option<&str>,
+        by_source: bool,
+    ) -> Result<Vec<TimeseriesPoint>, String> {
+        let format = match bucket {
+            "hour" => "%Y-%m-%dT%H:00",
+            "day" => "%Y-%m-%d",
+            "week" => "%Y-W%W",
+            _ => return Err(format!("Unknown timeseries bucket: {} (expected hour, day or week)", bucket)),
+        };
+        let source_column = if by_source { "source" } else { "NULL" };
+
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT strftime('{format}', created_at) AS bucket, {source_column}, COUNT(*)
+             FROM feed_items
+             WHERE ?1 IS NULL OR source = ?1
+             GROUP BY 1, 2
+             ORDER BY 1, 2"
+        )).map_err(|e| format!("Failed to prepare timeseries query: {}", e))?;
+
+        let rows = stmt.query_map(params![source], |row| {
+            Ok(TimeseriesPoint {
+                bucket: row.get(0)?,
+                source: row.get(1)?,
+                count: row.get(2)?,
+            })
+        }).map_err(|e| format!("Failed to query timeseries: {}", e))?;
+
+        let mut points = Vec::new();
+        for row in rows {
+            points.push(row.map_err(|e| format!("Failed to read timeseries row: {}", e))?);
+        }
+
+        Ok(points)
+    }
+
+    /// Groups of stored items that share a canonical URL across two or more
+    /// sources (cross-posts), largest first. `source` keeps only clusters it
+    /// is part of; `since` (RFC 3339) counts only items stored after it
+    pub fn get_duplicate_clusters(&self, source: Option<&str>, since: Option<&str>) -> Result<Vec<DuplicateCluster>, String> {
+        let mut stmt = self.conn.prepare(
+            "SELECT url, source FROM feed_items
+             WHERE ?1 IS NULL OR created_at > ?1
+             ORDER BY created_at, rowid"
+        ).map_err(|e| format!("Failed to prepare duplicates query: {}", e))?;
+
+        let rows = stmt.query_map(params![since], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+            .map_err(|e| format!("Failed to query items: {}", e))?;
+
+        let mut clusters: BTreeMap<String, DuplicateCluster> = BTreeMap::new();
+        for row in rows {
+            let (url, source) = row.map_err(|e| format!("Failed to read row: {}", e))?;
+            let key = canonical_url(&url, &self.tracking_params);
+            let cluster = clusters.entry(key.clone()).or_insert_with(|| DuplicateCluster {
+                canonical_url: key,
+                sources: Vec::new(),
+                items: 0,
+            });
+            cluster.items += 1;
+            let source = self.canonical_source(&source).to_string();
+            if !cluster.sources.contains(&source) {
+                cluster.sources.push(source);
+            }
+        }
+
+        let only = source.map(|source| self.canonical_source(source));
+        let mut clusters: Vec<DuplicateCluster> = clusters.into_values()
+            .filter(|cluster| cluster.sources.len() > 1)
+            .filter(|cluster| only.is_none_or(|only| cluster.sources.iter().any(|s| s == only)))
+            .collect();
+        for cluster in &mut clusters {
+            cluster.sources.sort();
+        }
+        clusters.sort_by_key(|cluster| std::cmp::Reverse(cluster.sources.len()));
+
+        Ok(clusters)
+    }
+
+    /// Most recent fetch_log entries, newest first
+    pub fn get_fetch_history(&self, source: Option<&str>, limit: usize) -> Result<Vec<FetchLog>, String> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM fetch_log
+             WHERE ?1 IS NULL OR source = ?1
+             ORDER BY id DESC
+             LIMIT ?2",
+            FETCH_LOG_COLUMNS
+        )).map_err(|e| format!("Failed to prepare history query: {}", e))?;
+
+        let rows = stmt.query_map(params![source, limit as i64], Self::fetch_log_from_row)
+            .map_err(|e| format!("Failed to query history: {}", e))?;
+
+        let mut history = Vec::new();
+        for row in rows {
+            history.push(row.map_err(|e| format!("Failed to read history row: {}", e))?);
+        }
+
+        Ok(history)
+    }
+
+    /// A source's last `limit` failed fetches (aliases included) as
+    /// `(fetched_at, error_message)`, newest first
+    pub fn get_recent_errors(&self, source: &str, limit: usize) -> Result<Vec<(String, String)>, String> {
+        let names = self.source_names(source);
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT fetched_at, COALESCE(error_message, '') FROM fetch_log
+             WHERE status IN ('error', 'parse_error') AND source IN ({})
+             ORDER BY id DESC
+             LIMIT ?",
+            vec!["?"; names.len()].join(", ")
+        )).map_err(|e| format!("Failed to prepare error query: {}", e))?;
+
+        let limit = limit as i64;
+        let params: Vec<&dyn rusqlite::ToSql> = names.iter()
+            .map(|name| name as &dyn rusqlite::ToSql)
+            .chain(std::iter::once(&limit as &dyn rusqlite::ToSql))
+            .collect();
+        let rows = stmt.query_map(params.as_slice(), |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| format!("Failed to query errors: {}", e))?;
+
+        let mut errors = Vec::new();
+        for row in rows {
+            errors.push(row.map_err(|e| format!("Failed to read error row: {}", e))?);
+        }
+
+        Ok(errors)
+    }
+
+    /// Every fetch_log row, oldest first, optionally filtered by source and
+    /// a `fetched_at` cutoff (RFC 3339)
+    pub fn get_fetch_log(&self, source: Option<&str>, since: Option<&str>) -> Result<Vec<FetchLog>, String> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM fetch_log
+             WHERE (?1 IS NULL OR source = ?1)
+               AND (?2 IS NULL OR fetched_at > ?2)
+             ORDER BY id",
+            FETCH_LOG_COLUMNS
+        )).map_err(|e| format!("Failed to prepare fetch log query: {}", e))?;
+
+        let rows = stmt.query_map(params![source, since], Self::fetch_log_from_row)
+            .map_err(|e| format!("Failed to query fetch log: {}", e))?;
+
+        let mut log = Vec::new();
+        for row in rows {
+            log.push(row.map_err(|e| format!("Failed to read fetch log row: {}", e))?);
+        }
+
+        Ok(log)
+    }
+
+    /// Most recently recorded TLS certificate expiry per source
+    pub fn get_cert_expiries(&self) -> Result<Vec<(String, String)>, String> {
+        let mut stmt = self.conn.prepare(
+            "SELECT source, cert_expires_at FROM fetch_log f
+             WHERE cert_expires_at IS NOT NULL
+               AND fetched_at = (
+                   SELECT MAX(fetched_at) FROM fetch_log
+                   WHERE source = f.source AND cert_expires_at IS NOT NULL
+               )
+             GROUP BY source
+             ORDER BY source"
+        ).map_err(|e| format!("Failed to prepare cert query: {}", e))?;
+
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| format!("Failed to query cert expiries: {}", e))?;
+
+        let mut expiries = Vec::new();
+        for row in rows {
+            expiries.push(row.map_err(|e| format!("Failed to read cert row: {}", e))?);
+        }
+
+        Ok(expiries)
+    }
+
+    /// Number of consecutive most-recent successful fetches per source that
+    /// returned the same body hash as the latest one
+    pub fn get_repeated_body_counts(&self) -> Result<Vec<(String, usize)>, String> {
+        let mut stmt = self.conn.prepare(
+            "SELECT source, body_hash FROM fetch_log
+             WHERE status IN ('success', 'degraded')
+             ORDER BY source, id DESC"
+        ).map_err(|e| format!("Failed to prepare body hash query: {}", e))?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+        }).map_err(|e| format!("Failed to query body hashes: {}", e))?;
+
+        let mut counts: Vec<(String, usize)> = Vec::new();
+        let mut latest_hash: Option<String> = None;
+        let mut run_open = false;
+
+        for row in rows {
+            let (source, hash) = row.map_err(|e| format!("Failed to read body hash row: {}", e))?;
+
+            if counts.last().is_none_or(|(s, _)| *s != source) {
+                counts.push((source, 0));
+                latest_hash = hash.clone();
+                run_open = latest_hash.is_some();
+            }
+
+            if run_open && hash == latest_hash {
+                if let Some((_, count)) = counts.last_mut() {
+                    *count += 1;
+                }
+            } else {
+                run_open = false;
+            }
+        }
+
+        Ok(counts)
+    }
+
+    /// Per source, how many of its most recent fetches failed in a row
+    /// (sources whose latest fetch succeeded are left out)
+    pub fn get_consecutive_failures(&self) -> Result<HashMap<String, usize>, String> {
+        let mut stmt = self.conn.prepare(
+            "SELECT source, COUNT(*) FROM fetch_log f
+             WHERE status IN ('error', 'parse_error')
+               AND id > COALESCE(
+                   (SELECT MAX(id) FROM fetch_log s
+                    WHERE s.source = f.source AND s.status NOT IN ('error', 'parse_error')),
+                   0)
+             GROUP BY source"
+        ).map_err(|e| format!("Failed to prepare failure count query: {}", e))?;
+
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize)))
+            .map_err(|e| format!("Failed to query failure counts: {}", e))?;
+
+        let mut counts = HashMap::new();
+        for row in rows {
+            let (source, count) = row.map_err(|e| format!("Failed to read failure count row: {}", e))?;
+            counts.insert(source, count);
+        }
+        Ok(counts)
+    }
+
+    /// Sources whose circuit is open (at least `circuit_break_threshold`
+    /// consecutive failures) with their failure count; empty when the
+    /// breaker is off
+    pub fn get_open_circuits(&self) -> Result<HashMap<String, usize>, String> {
+        let Some(threshold) = self.circuit_break_threshold else {
+            return Ok(HashMap::new());
+        };
+        let mut counts = self.get_consecutive_failures()?;
+        counts.retain(|_, failures| *failures >= threshold);
+        Ok(counts)
+    }
+
+    /// Sources that have been fetched before: those with fetch_log entries,
+    /// stored items or a recorded feed title, so this still works with
+    /// `log_fetches: false`
+    pub fn get_fetched_sources(&self) -> Result<HashSet<String>, String> {
+        let mut stmt = self.conn.prepare(
+            "SELECT source FROM fetch_log
+             UNION
+             SELECT source FROM feed_items
+             UNION
+             SELECT source FROM source_meta"
+        )
+            .map_err(|e| format!("Failed to prepare fetched sources query: {}", e))?;
+
+        let rows = stmt.query_map([], |row| row.get(0))
+            .map_err(|e| format!("Failed to query fetched sources: {}", e))?;
+
+        let mut sources = HashSet::new();
+        for row in rows {
+            sources.insert(row.map_err(|e| format!("Failed to read source row: {}", e))?);
+        }
+
+        Ok(sources)
+    }
+
+    /// Sources whose most recent fetch answered 410 Gone
+    pub fn get_gone_sources(&self) -> Result<Vec<String>, String> {
+        let mut stmt = self.conn.prepare(
+            "SELECT source FROM fetch_log f
+             WHERE gone = 1
+               AND id = (SELECT MAX(id) FROM fetch_log WHERE source = f.source)
+             ORDER BY source"
+        ).map_err(|e| format!("Failed to prepare gone query: {}", e))?;
+
+        let rows = stmt.query_map([], |row| row.get(0))
+            .map_err(|e| format!("Failed to query gone sources: {}", e))?;
+
+        let mut sources = Vec::new();
+        for row in rows {
+            sources.push(row.map_err(|e| format!("Failed to read source row: {}", e))?);
+        }
+
+        Ok(sources)
+    }
+
+    /// Cached ETag / Last-Modified per source for conditional requests
+    pub fn get_fetch_cache(&self) -> Result<HashMap<String, CacheValidators>, String> {
+        let mut stmt = self.conn.prepare("SELECT source, etag, last_modified FROM fetch_cache")
+            .map_err(|e| format!("Failed to prepare fetch_cache query: {}", e))?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get(0)?, CacheValidators { etag: row.get(1)?, last_modified: row.get(2)? }))
+        }).map_err(|e| format!("Failed to query fetch_cache: {}", e))?;
+
+        let mut cache = HashMap::new();
+        for row in rows {
+            let (source, validators): (String, CacheValidators) =
+                row.map_err(|e| format!("Failed to read fetch_cache row: {}", e))?;
+            cache.insert(source, validators);
+        }
+
+        Ok(cache)
+    }
+
+    /// Latest title each source declared for itself, by source name
+    pub fn get_feed_titles(&self) -> Result<HashMap<String, String>, String> {
+        let mut stmt = self.conn.prepare("SELECT source, feed_title FROM source_meta WHERE feed_title IS NOT NULL")
+            .map_err(|e| format!("Failed to prepare source_meta query: {}", e))?;
+
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| format!("Failed to query source_meta: {}", e))?;
+
+        let mut titles = HashMap::new();
+        for row in rows {
+            let (source, title): (String, String) = row.map_err(|e| format!("Failed to read source_meta row: {}", e))?;
+            titles.insert(self.canonical_source(&source).to_string(), title);
+        }
+
+        Ok(titles)
+    }
+
+    /// Distinct source names that have stored items or fetch history
+    pub fn get_db_sources(&self) -> Result<Vec<String>, String> {
+        let mut stmt = self.conn.prepare(
+            "SELECT source FROM feed_items
+             UNION
+             SELECT source FROM fetch_log
+             ORDER BY source"
+        ).map_err(|e| format!("Failed to prepare sources query: {}", e))?;
+
+        let rows = stmt.query_map([], |row| row.get(0))
+            .map_err(|e| format!("Failed to query sources: {}", e))?;
+
+        let mut sources = Vec::new();
+        for row in rows {
+            sources.push(row.map_err(|e| format!("Failed to read source row: {}", e))?);
+        }
+
+        Ok(sources)
+    }
+
+    /// Record the latest link check result for an item
+    pub fn set_link_status(&self, id: &str, status: &str) -> Result<(), String> {
+        self.conn.execute(
+            "UPDATE feed_items SET link_status = ?2 WHERE id = ?1",
+            params![id, status],
+        ).map_err(|e| format!("Failed to update link status: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Build a FetchLog from a row selected with `FETCH_LOG_COLUMNS`
+    fn fetch_log_from_row(row: &rusqlite::Row) -> rusqlite::Result<FetchLog> {
+        let captured_headers: Option<String> = row.get(6)?;
+
+        Ok(FetchLog {
+            source: row.get(0)?,
+            fetched_at: row.get(1)?,
+            status: row.get(2)?,
+            items_count: row.get::<_, i64>(3)? as usize,
+            error_message: row.get(4)?,
+            duration_ms: row.get::<_, Option<i64>>(5)?.unwrap_or(0) as u64,
+            captured_headers: captured_headers
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default(),
+            cert_expires_at: row.get(7)?,
+            body_hash: row.get(8)?,
+            gone: row.get::<_, Option<bool>>(9)?.unwrap_or(false),
+            mirror_url: row.get(10)?,
+            skipped_count: row.get::<_, i64>(11)? as usize,
+        })
+    }
+
+    /// Build a FeedItem from a row whose leading columns are `ITEM_COLUMNS`
+    fn item_from_row(row: &rusqlite::Row) -> rusqlite::Result<FeedItem> {
+        let tags_json: Option<String> = row.get(5)?;
+
+        Ok(FeedItem {
+            id: row.get(0)?,
+            title: row.get(1)?,
+            url: row.get(2)?,
+            source: row.get(3)?,
+            timestamp: row.get(4)?,
+            tags: tags_json.and_then(|json| serde_json::from_str(&json).ok()).unwrap_or_default(),
+            raw_data: row.get(6)?,
+            summary: row.get(7)?,
+            reading_time_minutes: row.get(8)?,
+        })
+    }
+
     fn current_timestamp() -> String {
         chrono::Utc::now().to_rfc3339()
     }
@@ -209,10 +1384,11 @@ impl Storage {
     pub fn store_item(&self, item: &FeedItem) -> Result<(), String> {
         let now = Self::current_timestamp();
         let tags_json = serde_json::to_string(&item.tags).unwrap_or_default();
+        let hash = content_hash(&item.title, item.timestamp.as_deref(), &tags_json, item.summary.as_deref());
         
         self.conn.execute(
-            "INSERT OR REPLACE INTO feed_items (id, title, url, source, timestamp, tags, raw_data, created_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            "INSERT OR REPLACE INTO feed_items (id, title, url, source, timestamp, tags, raw_data, created_at, summary, reading_time, content_hash)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
             params![
                 &item.id,
                 &item.title,
@@ -222,6 +1398,9 @@ impl Storage {
                 &tags_json,
                 &item.raw_data,
                 &now,
+                &item.summary,
+                &item.reading_time_minutes,
+                &hash,
             ],
         ).map_err(|e| format!("Failed to insert item: {}", e))?;
 
@@ -229,10 +1408,104 @@ impl Storage {
     }
 }
 
+/// SQL conditions (each starting with ` AND`) requiring a feed_items row's
+/// tags JSON array to contain `count` bound tags
+fn tag_filter(count: usize) -> String {
+    " AND EXISTS (SELECT 1 FROM json_each(feed_items.tags) WHERE value = ?)".repeat(count)
+}
+
+/// Hash of the fields whose change makes a refetched item count as updated
+fn content_hash(title: &str, timestamp: Option<&str>, tags_json: &str, summary: Option<&str>) -> String {
+    let mut hasher = Sha256::new();
+    for field in [Some(title), timestamp, Some(tags_json), summary] {
+        // Tag each field so None and "" hash differently
+        match field {
+            Some(value) => {
+                hasher.update([1]);
+                hasher.update(value.len().to_le_bytes());
+                hasher.update(value.as_bytes());
+            }
+            None => hasher.update([0]),
+        }
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct TimeseriesPoint {
+    pub bucket: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    pub count: i64,
+}
+
+/// Items from different sources that point at the same canonical URL
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DuplicateCluster {
+    pub canonical_url: String,
+    /// Sources that carried the URL, sorted by name
+    pub sources: Vec<String>,
+    /// Stored items in the cluster
+    pub items: usize,
+}
+
+/// Filter for `Storage::query_items`; the default matches every item
+#[derive(Debug, Clone, Default)]
+pub struct ItemQuery {
+    /// Source name (aliases included)
+    pub source: Option<String>,
+    /// RFC 3339 cutoff on when the item was stored
+    pub since: Option<String>,
+    /// Return at most this many items
+    pub limit: Option<usize>,
+    /// Only items carrying every one of these tags
+    pub tags: Vec<String>,
+}
+
+/// Title, timestamp, tags JSON, summary and content hash of a stored item
+type StoredFields = (String, Option<String>, Option<String>, Option<String>, Option<String>);
+
+/// What `store_results` did with the items it was given
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StoreOutcome {
+    /// Items written: `new + updated + unchanged`
+    pub total: usize,
+    /// Items not stored before
+    pub new: usize,
+    /// Stored items whose title, timestamp, tags or summary changed (by
+    /// `content_hash`)
+    pub updated: usize,
+    /// Stored items that came back identical
+    pub unchanged: usize,
+    pub new_item_ids: Vec<String>,
+}
+
 #[derive(Debug)]
 pub struct SourceStat {
     pub source: String,
     pub items: i64,
-    pub errors: i64,
+    /// None when fetch history isn't logged (`log_fetches: false`)
+    pub errors: Option<i64>,
     pub last_success: Option<String>,
+    /// Fetches recorded as degraded; None without fetch history
+    pub degraded: Option<i64>,
+    /// The part of `errors` where a response arrived but couldn't be parsed
+    pub parse_errors: Option<i64>,
+    /// Items the parser skipped across these fetches; None without fetch history
+    pub skipped: Option<i64>,
+    /// Consecutive failures when the source's circuit is open
+    pub open_circuit: Option<usize>,
+}
+
+impl SourceStat {
+    /// Error percentage of logged fetches (`items` counts every fetch,
+    /// failed ones included), None without fetch history
+    pub fn error_rate(&self) -> Option<f64> {
+        let errors = self.errors?;
+        Some(if self.items > 0 {
+            (errors as f64 / self.items as f64) * 100.0
+        } else {
+            0.0
+        })
+    }
 }
@@ -0,0 +1,138 @@
+use crate::config::{Config, Feed};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+/// How long a config change is left to settle before it's reloaded, so an
+/// editor's write-then-rename save is read once, complete
+const RELOAD_SETTLE: Duration = Duration::from_millis(200);
+
+/// When each feed is next due in watch mode. Every feed is due immediately,
+/// then `refresh_interval_secs` after the start of its previous fetch, so
+/// feeds with different intervals fire independently. A feed is never due
+/// while its fetch is in flight
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    intervals: HashMap<String, Duration>,
+    next_due: HashMap<String, Instant>,
+    in_flight: HashSet<String>,
+}
+
+impl Schedule {
+    /// Schedule every enabled feed, all due at `now`
+    pub fn new(feeds: &[Feed], now: Instant) -> Self {
+        let intervals: HashMap<String, Duration> = feeds.iter()
+            .filter(|feed| feed.enabled)
+            .map(|feed| (feed.name.clone(), Duration::from_secs(feed.refresh_interval_secs)))
+            .collect();
+        let next_due = intervals.keys().map(|name| (name.clone(), now)).collect();
+        Self { intervals, next_due, in_flight: HashSet::new() }
+    }
+
+    /// Follow a reloaded config: newly enabled feeds are due at `now`,
+    /// removed or disabled ones are dropped (a fetch already running still
+    /// finishes) and changed intervals apply from each feed's next fetch
+    pub fn update(&mut self, feeds: &[Feed], now: Instant) {
+        self.intervals = feeds.iter()
+            .filter(|feed| feed.enabled)
+            .map(|feed| (feed.name.clone(), Duration::from_secs(feed.refresh_interval_secs)))
+            .collect();
+        self.next_due.retain(|name, _| self.intervals.contains_key(name));
+        for name in self.intervals.keys() {
+            if !self.in_flight.contains(name) && !self.next_due.contains_key(name) {
+                self.next_due.insert(name.clone(), now);
+            }
+        }
+    }
+
+    /// Feeds due at `now`, sorted by name
+    pub fn due(&self, now: Instant) -> Vec<String> {
+        let mut due: Vec<String> = self.next_due.iter()
+            .filter(|(_, &at)| at <= now)
+            .map(|(name, _)| name.clone())
+            .collect();
+        due.sort();
+        due
+    }
+
+    /// Record that a fetch of `name` started; it isn't due again until
+    /// `mark_fetched`
+    pub fn mark_started(&mut self, name: &str) {
+        self.next_due.remove(name);
+        self.in_flight.insert(name.to_string());
+    }
+
+    /// Record that `name` was fetched starting at `started`
+    pub fn mark_fetched(&mut self, name: &str, started: Instant) {
+        self.in_flight.remove(name);
+        if let Some(interval) = self.intervals.get(name) {
+            self.next_due.insert(name.to_string(), started + *interval);
+        }
+    }
+
+    /// When the next feed falls due, None when every feed is in flight or
+    /// none are scheduled
+    pub fn next_wakeup(&self) -> Option<Instant> {
+        self.next_due.values().min().copied()
+    }
+}
+
+/// Load and validate the config for watch mode, dropping (with a warning)
+/// feeds whose feed_type this build doesn't support
+pub fn load_config(path: &Path) -> Result<Config, String> {
+    let mut config = Config::load(path)?;
+    config.validate()?;
+
+    for feed in config.remove_unknown_feed_types() {
+        tracing::warn!(
+            "skipping feed '{}': unknown feed_type '{}'",
+            feed.name, feed.feed_type
+        );
+    }
+
+    Ok(config)
+}
+
+/// Notices changes to the config file. Its directory is watched rather than
+/// the file itself, so saves that replace the file are seen too
+pub struct ConfigWatcher {
+    _watcher: RecommendedWatcher,
+    changes: mpsc::UnboundedReceiver<()>,
+}
+
+impl ConfigWatcher {
+    pub fn new(path: &Path) -> Result<Self, String> {
+        let file_name: Option<OsString> = path.file_name().map(OsString::from);
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+
+        let (tx, changes) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let Ok(event) = event else { return };
+            let touches_config = event.paths.iter()
+                .any(|changed| changed.file_name() == file_name.as_deref());
+            if touches_config && matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                let _ = tx.send(());
+            }
+        })
+        .map_err(|e| format!("Failed to watch config file: {}", e))?;
+        watcher.watch(dir, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Failed to watch config file '{}': {}", path.display(), e))?;
+
+        Ok(Self { _watcher: watcher, changes })
+    }
+
+    /// Wait until the config file changes and has settled
+    pub async fn changed(&mut self) {
+        if self.changes.recv().await.is_none() {
+            return std::future::pending().await;
+        }
+        tokio::time::sleep(RELOAD_SETTLE).await;
+        while self.changes.try_recv().is_ok() {}
+    }
+}
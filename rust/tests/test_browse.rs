@@ -0,0 +1,30 @@
+/// Tests for the interactive browser's navigation logic
+use feedpulse::browse::Pager;
+
+#[test]
+fn test_pager_stays_within_bounds() {
+    let mut pager = Pager::new(3);
+    assert_eq!(pager.current(), Some(0));
+
+    assert!(!pager.back(), "Cannot move before the first item");
+    assert_eq!(pager.current(), Some(0));
+
+    assert!(pager.forward());
+    assert!(pager.forward());
+    assert_eq!(pager.current(), Some(2));
+
+    assert!(!pager.forward(), "Cannot move past the last item");
+    assert_eq!(pager.current(), Some(2));
+
+    assert!(pager.back());
+    assert_eq!(pager.current(), Some(1));
+}
+
+#[test]
+fn test_pager_empty() {
+    let mut pager = Pager::new(0);
+    assert_eq!(pager.current(), None);
+    assert!(!pager.forward());
+    assert!(!pager.back());
+    assert_eq!(pager.current(), None);
+}
@@ -0,0 +1,124 @@
+/// Tests for command-line behaviour of the feedpulse binary
+use std::process::Command;
+
+#[test]
+fn test_json_errors_prints_json_to_stderr() {
+    let output = Command::new(env!("CARGO_BIN_EXE_feedpulse"))
+        .args(["--json-errors", "fetch", "--config", "/nonexistent/feedpulse.yaml"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let error: serde_json::Value = serde_json::from_str(stderr.trim())
+        .unwrap_or_else(|e| panic!("stderr is not JSON ({}): {}", e, stderr));
+
+    let message = error["error"].as_str().unwrap();
+    assert!(message.contains("Failed to read config file"), "Unexpected error: {}", message);
+    assert_eq!(error.as_object().unwrap().len(), 1, "Unexpected fields: {}", error);
+}
+
+#[test]
+fn test_plain_errors_by_default() {
+    let output = Command::new(env!("CARGO_BIN_EXE_feedpulse"))
+        .args(["fetch", "--config", "/nonexistent/feedpulse.yaml"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("Error: Failed to read config file"), "Unexpected stderr: {}", stderr);
+}
+
+#[test]
+fn test_report_rejects_limit_it_would_ignore() {
+    for flags in [&["--duplicates"][..], &["--tag", "rust"]] {
+        let output = Command::new(env!("CARGO_BIN_EXE_feedpulse"))
+            .args(["report", "--limit", "5"])
+            .args(flags)
+            .output()
+            .unwrap();
+
+        assert_eq!(output.status.code(), Some(2));
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("cannot be used with"), "Unexpected stderr: {}", stderr);
+    }
+}
+
+#[test]
+fn test_fetch_dry_run_leaves_database_alone() {
+    let dir = tempfile::tempdir().unwrap();
+    let db_path = dir.path().join("feedpulse.db");
+    let config_path = dir.path().join("config.yaml");
+    std::fs::write(&config_path, format!("settings:\n  database_path: {:?}\nfeeds: []\n", db_path)).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_feedpulse"))
+        .args(["fetch", "--dry-run", "--config"])
+        .arg(&config_path)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    // Progress is logged to stderr, leaving stdout empty
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("(dry run — nothing stored)"), "Unexpected stderr: {}", stderr);
+    assert!(!db_path.exists());
+}
+
+#[test]
+fn test_fail_fast_counts_only_enabled_feeds_as_cancelled() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("config.yaml");
+    std::fs::write(&config_path, r#"
+feeds:
+  - name: "Down"
+    url: "http://127.0.0.1:1/feed"
+    feed_type: json
+  - name: "Off"
+    url: "http://127.0.0.1:1/off"
+    feed_type: json
+    enabled: false
+"#).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_feedpulse"))
+        .args(["fetch", "--dry-run", "--fail-fast", "--retries", "0", "--config"])
+        .arg(&config_path)
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("fail-fast: feed 'Down' failed"), "Unexpected stderr: {}", stderr);
+    assert!(stderr.contains("0 feeds cancelled"), "Unexpected stderr: {}", stderr);
+}
+
+#[test]
+fn test_log_format_json_and_level() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("config.yaml");
+    std::fs::write(&config_path, "feeds: []\n").unwrap();
+
+    let run = |args: &[&str]| Command::new(env!("CARGO_BIN_EXE_feedpulse"))
+        .args(["fetch", "--dry-run", "--config"])
+        .arg(&config_path)
+        .args(args)
+        .output()
+        .unwrap();
+
+    let output = run(&["--log-format", "json"]);
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(!stderr.is_empty());
+    for line in stderr.lines() {
+        let record: serde_json::Value = serde_json::from_str(line)
+            .unwrap_or_else(|e| panic!("not JSON ({}): {}", e, line));
+        assert_eq!(record["level"], "INFO");
+    }
+
+    let output = run(&["--log-level", "warn"]);
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty(), "{}", String::from_utf8_lossy(&output.stderr));
+}
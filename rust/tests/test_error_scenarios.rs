@@ -1,10 +1,15 @@
 /// Tests for error handling scenarios from the spec
 /// Tests all 16 error scenarios from SPEC.md section 6
 use feedpulse::config::Config;
+use feedpulse::fetcher::{FetchResult, Fetcher};
+use feedpulse::http::{HttpClient, HttpFuture, HttpRequest, HttpResponse, TransportError};
 use feedpulse::parser::Parser;
 use feedpulse::storage::Storage;
 use feedpulse::models::FeedItem;
+use reqwest::StatusCode;
+use std::collections::HashMap;
 use std::fs;
+use std::sync::{Arc, Mutex};
 use tempfile::{NamedTempFile, TempDir};
 
 // ============================================================================
@@ -26,7 +31,7 @@ fn test_scenario_1_config_missing_file() {
 fn test_scenario_2_config_invalid_yaml() {
     // Scenario: Config file invalid YAML
     // Expected: Print "Error: invalid config: {details}" + exit 1
-    let mut temp_file = NamedTempFile::new().unwrap();
+    let temp_file = NamedTempFile::new().unwrap();
     fs::write(&temp_file, "invalid: yaml: content: [unclosed").unwrap();
     
     let result = Config::load(temp_file.path());
@@ -39,7 +44,7 @@ fn test_scenario_2_config_invalid_yaml() {
 fn test_scenario_3_config_missing_required_field() {
     // Scenario: Config missing required field
     // Expected: Print "Error: feed '{name}': missing field '{field}'" + exit 1
-    let mut temp_file = NamedTempFile::new().unwrap();
+    let temp_file = NamedTempFile::new().unwrap();
     fs::write(&temp_file, r#"
 feeds:
   - name: "Test Feed"
@@ -60,7 +65,7 @@ feeds:
 fn test_scenario_4_config_invalid_url() {
     // Scenario: Config invalid URL
     // Expected: Print "Error: feed '{name}': invalid URL '{url}'" + exit 1
-    let mut temp_file = NamedTempFile::new().unwrap();
+    let temp_file = NamedTempFile::new().unwrap();
     fs::write(&temp_file, r#"
 feeds:
   - name: "Bad Feed"
@@ -79,101 +84,148 @@ feeds:
 // ============================================================================
 // Network/HTTP Error Scenarios (5-9)
 // ============================================================================
-// Note: These scenarios require actual HTTP calls and are tested in integration
-// tests or during runtime. Here we test the logic that would handle them.
+// These scenarios run against a fake HTTP client, so no network is needed.
+// Each feed URL maps to one canned response or transport error that is
+// returned on every attempt.
 
-#[test]
-fn test_scenario_5_dns_resolution_failure() {
-    // Scenario: DNS resolution failure
-    // Expected: Retry, then log error, continue other feeds
-    // This would be tested with actual HTTP client - here we verify the
-    // configuration allows for retries
-    let mut temp_file = NamedTempFile::new().unwrap();
-    fs::write(&temp_file, r#"
+struct FakeClient {
+    routes: HashMap<String, Result<HttpResponse, TransportError>>,
+    calls: Mutex<HashMap<String, usize>>,
+}
+
+impl FakeClient {
+    fn new(routes: Vec<(&str, Result<HttpResponse, TransportError>)>) -> Arc<Self> {
+        Arc::new(Self {
+            routes: routes.into_iter().map(|(url, r)| (url.to_string(), r)).collect(),
+            calls: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn calls(&self, url: &str) -> usize {
+        self.calls.lock().unwrap().get(url).copied().unwrap_or(0)
+    }
+}
+
+impl HttpClient for FakeClient {
+    fn get(&self, request: HttpRequest) -> HttpFuture<'_> {
+        *self.calls.lock().unwrap().entry(request.url.clone()).or_insert(0) += 1;
+        let response = self.routes.get(&request.url).cloned()
+            .unwrap_or_else(|| Err(TransportError::Other(format!("no route for {}", request.url))));
+        Box::pin(async move { response })
+    }
+}
+
+const OK_URL: &str = "https://example.com/ok.json";
+const OK_BODY: &str = r#"[{"title": "Still here", "url": "https://example.com/still-here"}]"#;
+
+/// Fetch a failing feed next to a healthy one through `client`
+async fn fetch_with(client: Arc<FakeClient>, failing_url: &str) -> Vec<FetchResult> {
+    let config: Config = serde_yaml::from_str(&format!(r#"
 settings:
   retry_max: 3
+  retry_base_delay_ms: 1
 feeds:
-  - name: "DNS Test"
-    url: "https://nonexistent-domain-12345.example"
+  - name: "Failing"
+    url: "{}"
     feed_type: json
-"#).unwrap();
-    
-    let config = Config::load(temp_file.path()).unwrap();
-    assert!(config.validate().is_ok());
-    assert_eq!(config.settings.retry_max, 3);
+  - name: "Healthy"
+    url: "{}"
+    feed_type: json
+"#, failing_url, OK_URL)).unwrap();
+    let mut results = Fetcher::new(config).with_http_client(client).fetch_all().await;
+    results.sort_by(|a, b| a.source.cmp(&b.source));
+    results
 }
 
-#[test]
-fn test_scenario_6_http_timeout() {
+fn assert_healthy_continued(results: &[FetchResult]) {
+    let healthy = results.iter().find(|r| r.source == "Healthy").unwrap();
+    assert!(healthy.error.is_none(), "{:?}", healthy.error);
+    assert_eq!(healthy.items.len(), 1);
+}
+
+#[tokio::test]
+async fn test_scenario_5_dns_resolution_failure() {
+    // Scenario: DNS resolution failure
+    // Expected: Retry, then log error, continue other feeds
+    let url = "https://nonexistent-domain-12345.example/feed.json";
+    let client = FakeClient::new(vec![
+        (url, Err(TransportError::Connect)),
+        (OK_URL, Ok(HttpResponse::ok(OK_URL, OK_BODY))),
+    ]);
+    let results = fetch_with(client.clone(), url).await;
+
+    let failing = results.iter().find(|r| r.source == "Failing").unwrap();
+    assert!(failing.error.as_deref().unwrap().contains("DNS resolution failure"));
+    assert_eq!(client.calls(url), 4);
+    assert_healthy_continued(&results);
+}
+
+#[tokio::test]
+async fn test_scenario_6_http_timeout() {
     // Scenario: HTTP timeout
     // Expected: Retry, then log error, continue other feeds
-    let mut temp_file = NamedTempFile::new().unwrap();
-    fs::write(&temp_file, r#"
-settings:
-  default_timeout_secs: 10
-  retry_max: 3
-feeds:
-  - name: "Timeout Test"
-    url: "https://httpbin.org/delay/20"
-    feed_type: json
-"#).unwrap();
-    
-    let config = Config::load(temp_file.path()).unwrap();
-    assert!(config.validate().is_ok());
-    assert_eq!(config.settings.default_timeout_secs, 10);
+    let url = "https://slow.example/feed.json";
+    let client = FakeClient::new(vec![
+        (url, Err(TransportError::Timeout)),
+        (OK_URL, Ok(HttpResponse::ok(OK_URL, OK_BODY))),
+    ]);
+    let results = fetch_with(client.clone(), url).await;
+
+    let failing = results.iter().find(|r| r.source == "Failing").unwrap();
+    assert!(failing.error.as_deref().unwrap().contains("timed out"));
+    assert_eq!(client.calls(url), 4);
+    assert_healthy_continued(&results);
 }
 
-#[test]
-fn test_scenario_7_http_429_rate_limit() {
+#[tokio::test]
+async fn test_scenario_7_http_429_rate_limit() {
     // Scenario: HTTP 429 (rate limit)
     // Expected: Retry with backoff, then log error, continue
-    // Configuration should allow for exponential backoff
-    let mut temp_file = NamedTempFile::new().unwrap();
-    fs::write(&temp_file, r#"
-settings:
-  retry_max: 3
-  retry_base_delay_ms: 500
-feeds:
-  - name: "Rate Limited"
-    url: "https://httpbin.org/status/429"
-    feed_type: json
-"#).unwrap();
-    
-    let config = Config::load(temp_file.path()).unwrap();
-    assert!(config.validate().is_ok());
-    assert_eq!(config.settings.retry_base_delay_ms, 500);
+    let url = "https://limited.example/feed.json";
+    let client = FakeClient::new(vec![
+        (url, Ok(HttpResponse::with_status(url, StatusCode::TOO_MANY_REQUESTS, ""))),
+        (OK_URL, Ok(HttpResponse::ok(OK_URL, OK_BODY))),
+    ]);
+    let results = fetch_with(client.clone(), url).await;
+
+    let failing = results.iter().find(|r| r.source == "Failing").unwrap();
+    assert!(failing.error.as_deref().unwrap().contains("429"));
+    assert_eq!(client.calls(url), 4);
+    assert_healthy_continued(&results);
 }
 
-#[test]
-fn test_scenario_8_http_5xx() {
+#[tokio::test]
+async fn test_scenario_8_http_5xx() {
     // Scenario: HTTP 5xx
     // Expected: Retry with backoff, then log error, continue
-    let mut temp_file = NamedTempFile::new().unwrap();
-    fs::write(&temp_file, r#"
-feeds:
-  - name: "Server Error"
-    url: "https://httpbin.org/status/500"
-    feed_type: json
-"#).unwrap();
-    
-    let config = Config::load(temp_file.path()).unwrap();
-    assert!(config.validate().is_ok());
+    let url = "https://broken.example/feed.json";
+    let client = FakeClient::new(vec![
+        (url, Ok(HttpResponse::with_status(url, StatusCode::INTERNAL_SERVER_ERROR, ""))),
+        (OK_URL, Ok(HttpResponse::ok(OK_URL, OK_BODY))),
+    ]);
+    let results = fetch_with(client.clone(), url).await;
+
+    let failing = results.iter().find(|r| r.source == "Failing").unwrap();
+    assert!(failing.error.as_deref().unwrap().contains("500"));
+    assert_eq!(client.calls(url), 4);
+    assert_healthy_continued(&results);
 }
 
-#[test]
-fn test_scenario_9_http_404() {
+#[tokio::test]
+async fn test_scenario_9_http_404() {
     // Scenario: HTTP 404
     // Expected: No retry, log error, continue other feeds
-    let mut temp_file = NamedTempFile::new().unwrap();
-    fs::write(&temp_file, r#"
-feeds:
-  - name: "Not Found"
-    url: "https://httpbin.org/status/404"
-    feed_type: json
-"#).unwrap();
-    
-    let config = Config::load(temp_file.path()).unwrap();
-    assert!(config.validate().is_ok());
+    let url = "https://missing.example/feed.json";
+    let client = FakeClient::new(vec![
+        (url, Ok(HttpResponse::with_status(url, StatusCode::NOT_FOUND, ""))),
+        (OK_URL, Ok(HttpResponse::ok(OK_URL, OK_BODY))),
+    ]);
+    let results = fetch_with(client.clone(), url).await;
+
+    let failing = results.iter().find(|r| r.source == "Failing").unwrap();
+    assert!(failing.error.as_deref().unwrap().contains("404"));
+    assert_eq!(client.calls(url), 1);
+    assert_healthy_continued(&results);
 }
 
 // ============================================================================
@@ -288,13 +340,50 @@ fn test_scenario_14_database_corrupted() {
 // System Error Scenarios (15-17)
 // ============================================================================
 
-#[test]
-fn test_scenario_15_ctrl_c_handling() {
+#[tokio::test]
+async fn test_scenario_15_ctrl_c_handling() {
     // Scenario: Ctrl+C during fetch
     // Expected: Cancel pending fetches, save completed results, exit
-    // This would require signal handling and is tested at integration level
-    // Here we just verify graceful shutdown is possible
-    assert!(true, "Ctrl+C handling requires integration test");
+    // The CLI's signal handler triggers the fetcher's cancellation token;
+    // here the token is triggered directly while one feed hangs
+    let mut server = mockito::Server::new_async().await;
+    let _fast = server.mock("GET", "/fast")
+        .with_status(200)
+        .with_body(r#"[{"title": "Done", "url": "https://example.com/done"}]"#)
+        .create_async()
+        .await;
+    // Accepts connections but never answers
+    let hung = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+
+    let config: Config = serde_yaml::from_str(&format!(r#"
+settings:
+  default_timeout_secs: 30
+feeds:
+  - name: "Fast"
+    url: "{}/fast"
+    feed_type: json
+  - name: "Hung"
+    url: "http://{}/feed"
+    feed_type: json
+"#, server.url(), hung.local_addr().unwrap())).unwrap();
+
+    let fetcher = Fetcher::new(config);
+    let cancel = fetcher.cancellation_token();
+    tokio::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        cancel.cancel();
+    });
+
+    let started = std::time::Instant::now();
+    let mut results = fetcher.fetch_all().await;
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].source, "Fast");
+
+    let temp_dir = TempDir::new().unwrap();
+    let storage = Storage::new(temp_dir.path().join("interrupted.db")).unwrap();
+    storage.store_results(&mut results).unwrap();
+    assert_eq!(storage.get_items(Some("Fast"), None).unwrap().len(), 1);
 }
 
 #[test]
@@ -303,7 +392,6 @@ fn test_scenario_16_disk_full() {
     // Expected: Print error, exit 1
     // This is difficult to test without actually filling disk
     // We verify that file write errors are propagated
-    assert!(true, "Disk full requires system-level testing");
 }
 
 #[test]
@@ -311,7 +399,7 @@ fn test_scenario_17_no_internet() {
     // Scenario: No internet connection
     // Expected: All feeds fail gracefully, report shows all errors
     // This requires network mocking or actual network disconnection
-    let mut temp_file = NamedTempFile::new().unwrap();
+    let temp_file = NamedTempFile::new().unwrap();
     fs::write(&temp_file, r#"
 feeds:
   - name: "Feed 1"
@@ -333,7 +421,7 @@ feeds:
 
 #[test]
 fn test_empty_feed_list() {
-    let mut temp_file = NamedTempFile::new().unwrap();
+    let temp_file = NamedTempFile::new().unwrap();
     fs::write(&temp_file, r#"
 settings:
   max_concurrency: 5
@@ -347,7 +435,7 @@ feeds: []
 
 #[test]
 fn test_very_large_concurrency() {
-    let mut temp_file = NamedTempFile::new().unwrap();
+    let temp_file = NamedTempFile::new().unwrap();
     fs::write(&temp_file, r#"
 settings:
   max_concurrency: 100
@@ -443,7 +531,7 @@ fn test_timestamp_formats() {
         ("invalid-timestamp", true),  // Should not crash
     ];
     
-    for (ts, should_parse) in timestamps {
+    for (ts, _should_parse) in timestamps {
         let data = format!(r#"[
             {{
                 "title": "Test",
@@ -460,7 +548,7 @@ fn test_timestamp_formats() {
 
 #[test]
 fn test_config_with_all_feed_types() {
-    let mut temp_file = NamedTempFile::new().unwrap();
+    let temp_file = NamedTempFile::new().unwrap();
     fs::write(&temp_file, r#"
 feeds:
   - name: "JSON Feed"
@@ -0,0 +1,59 @@
+/// Tests for post-fetch hook execution
+use feedpulse::fetcher::FetchResult;
+use feedpulse::hooks::run_post_fetch_hook;
+use std::fs;
+use tempfile::TempDir;
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_post_fetch_hook_receives_summary_and_new_urls() {
+    let temp_dir = TempDir::new().unwrap();
+    let env_out = temp_dir.path().join("env.txt");
+    let stdin_out = temp_dir.path().join("stdin.txt");
+
+    let results = vec![
+        FetchResult {
+            source: "Lobsters".to_string(),
+            new_items: 2,
+            new_urls: vec!["https://example.com/a".to_string(), "https://example.com/b".to_string()],
+            ..Default::default()
+        },
+        FetchResult {
+            source: "Broken".to_string(),
+            error: Some("HTTP 500".to_string()),
+            ..Default::default()
+        },
+    ];
+
+    let hook = format!(
+        "echo \"$FEEDPULSE_FEEDS $FEEDPULSE_NEW_ITEMS $FEEDPULSE_ERRORS\" > '{}'; cat > '{}'; exit 3",
+        env_out.display(),
+        stdin_out.display()
+    );
+    let status = run_post_fetch_hook(&hook, &results).await.unwrap();
+
+    assert_eq!(status.code(), Some(3), "Exit status is reported, not turned into an error");
+    assert_eq!(fs::read_to_string(&env_out).unwrap(), "2 2 1\n");
+    assert_eq!(
+        fs::read_to_string(&stdin_out).unwrap(),
+        "https://example.com/a\nhttps://example.com/b\n"
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_post_fetch_hook_that_ignores_stdin_does_not_block() {
+    // More URLs than fit in a pipe buffer
+    let results = vec![FetchResult {
+        source: "Firehose".to_string(),
+        new_items: 5000,
+        new_urls: (0..5000).map(|n| format!("https://example.com/{}", n)).collect(),
+        ..Default::default()
+    }];
+
+    let status = tokio::time::timeout(
+        std::time::Duration::from_secs(10),
+        run_post_fetch_hook("exit 0", &results),
+    ).await.expect("hook should not hang").unwrap();
+    assert!(status.success());
+}
@@ -0,0 +1,42 @@
+/// Tests for driving fetches through the library API
+use feedpulse::{run_fetch, Config, Feed, FetchStatus, Settings, Storage};
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn test_run_fetch_with_config_built_in_code() {
+    let mut server = mockito::Server::new_async().await;
+    let _ok = server.mock("GET", "/items.json")
+        .with_status(200)
+        .with_body(r#"[{"title": "Hello", "url": "https://example.com/hello"}]"#)
+        .create_async()
+        .await;
+    let _broken = server.mock("GET", "/broken").with_status(404).create_async().await;
+
+    let mut broken = Feed::new("Broken", format!("{}/broken", server.url()), "json");
+    broken.retry_max = Some(0);
+    let mut disabled = Feed::new("Disabled", format!("{}/never", server.url()), "json");
+    disabled.enabled = false;
+
+    let config = Config {
+        settings: Settings { max_concurrency: 2, ..Settings::default() },
+        feeds: vec![Feed::new("Api", format!("{}/items.json", server.url()), "json"), broken, disabled],
+    };
+    config.validate().unwrap();
+
+    let mut results = run_fetch(&config).await;
+    results.sort_by(|a, b| a.source.cmp(&b.source));
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].source, "Api");
+    assert!(results[0].error.is_none());
+    assert_eq!(results[0].status(), FetchStatus::Success);
+    assert_eq!(results[0].items[0].title, "Hello");
+    assert_eq!(results[1].source, "Broken");
+    assert_eq!(results[1].status(), FetchStatus::Error);
+    assert!(results[1].error.as_deref().unwrap().contains("404"));
+
+    let temp_dir = TempDir::new().unwrap();
+    let storage = Storage::new(temp_dir.path().join("library.db")).unwrap();
+    let outcome = storage.store_results(&mut results).unwrap();
+    assert_eq!(outcome.new, 1);
+}
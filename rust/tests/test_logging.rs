@@ -0,0 +1,70 @@
+/// Tests for log event formatting
+use feedpulse::logging::{subscriber, LogFormat};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use tracing_subscriber::filter::LevelFilter;
+
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Run `emit` under a subscriber writing to a buffer and return what it wrote
+fn capture(level: LevelFilter, format: LogFormat, emit: impl FnOnce()) -> String {
+    let buffer = Buffer::default();
+    let writer = buffer.clone();
+    tracing::subscriber::with_default(subscriber(level, format, move || writer.clone()), emit);
+    let bytes = buffer.0.lock().unwrap().clone();
+    String::from_utf8(bytes).unwrap()
+}
+
+#[test]
+fn test_text_format_prefixes_warnings_and_errors() {
+    let output = capture(LevelFilter::DEBUG, LogFormat::Text, || {
+        tracing::info!(target: "feedpulse::fetcher", "feed 'A' is slow");
+        tracing::warn!(target: "feedpulse::fetcher", "feed 'A' is slow");
+        tracing::error!(target: "feedpulse::fetcher", "feed 'A' is slow");
+        tracing::debug!(target: "feedpulse::fetcher", "feed 'A' is slow");
+    });
+
+    let lines: Vec<_> = output.lines().collect();
+    assert_eq!(lines, vec![
+        "feed 'A' is slow",
+        "Warning: feed 'A' is slow",
+        "Error: feed 'A' is slow",
+        "debug: feed 'A' is slow",
+    ]);
+}
+
+#[test]
+fn test_level_filter_drops_less_severe_events() {
+    let output = capture(LevelFilter::WARN, LogFormat::Text, || {
+        tracing::info!("Fetching 3 feeds");
+        tracing::warn!("feed 'A' is slow");
+    });
+
+    assert_eq!(output, "Warning: feed 'A' is slow\n");
+}
+
+#[test]
+fn test_json_format_is_one_object() {
+    let output = capture(LevelFilter::INFO, LogFormat::Json, || {
+        tracing::info!(target: "feedpulse::fetcher", "Fetching 3 feeds");
+    });
+
+    let line = output.strip_suffix('\n').unwrap();
+    assert!(!line.contains('\n'));
+    let value: serde_json::Value = serde_json::from_str(line).unwrap();
+    assert_eq!(value["level"], "INFO");
+    assert_eq!(value["target"], "feedpulse::fetcher");
+    assert_eq!(value["message"], "Fetching 3 feeds");
+    assert!(value["timestamp"].as_str().is_some_and(|ts| chrono::DateTime::parse_from_rfc3339(ts).is_ok()));
+}
@@ -1,5 +1,6 @@
 /// Tests for feed parsing and normalization
-use feedpulse::parser::Parser;
+use feedpulse::config::JsonParser;
+use feedpulse::parser::{ParseOptions, Parser};
 use feedpulse::models::FeedItem;
 
 #[test]
@@ -95,6 +96,29 @@ fn test_parse_github_missing_url() {
     assert_eq!(items.len(), 0);
 }
 
+#[test]
+fn test_parse_outcome_collects_skipped_github_items() {
+    let data = r#"{
+        "items": [
+            {"full_name": "user/first", "html_url": "https://github.com/user/first"},
+            {"full_name": "user/no-url"},
+            {"full_name": "user/second", "html_url": "https://github.com/user/second"},
+            {"html_url": "https://github.com/user/no-name"}
+        ]
+    }"#;
+
+    let outcome = Parser::parse_outcome("GitHub Trending", "json", data, &ParseOptions::default()).unwrap();
+    assert_eq!(outcome.items.len(), 2);
+    assert_eq!(outcome.warnings, vec![
+        "GitHub Trending item 1: missing html_url".to_string(),
+        "GitHub Trending item 3: missing full_name".to_string(),
+    ]);
+
+    let clean = r#"{"items": [{"full_name": "user/repo", "html_url": "https://github.com/user/repo"}]}"#;
+    let outcome = Parser::parse_outcome("GitHub Trending", "json", clean, &ParseOptions::default()).unwrap();
+    assert!(outcome.warnings.is_empty());
+}
+
 #[test]
 fn test_parse_reddit_valid() {
     let data = r#"{
@@ -337,23 +361,21 @@ fn test_parse_null_values() {
 }
 
 #[test]
-fn test_parse_rss_not_implemented() {
+fn test_parse_rss_empty_channel() {
     let data = r#"<?xml version="1.0"?><rss><channel></channel></rss>"#;
     let result = Parser::parse("RSS Feed", "rss", data);
     
-    assert!(result.is_err());
-    let err = result.unwrap_err();
-    assert!(err.contains("not yet implemented") || err.contains("RSS"));
+    assert!(result.is_ok());
+    assert_eq!(result.unwrap().len(), 0);
 }
 
 #[test]
-fn test_parse_atom_not_implemented() {
+fn test_parse_atom_empty_feed() {
     let data = r#"<?xml version="1.0"?><feed></feed>"#;
     let result = Parser::parse("Atom Feed", "atom", data);
     
-    assert!(result.is_err());
-    let err = result.unwrap_err();
-    assert!(err.contains("not yet implemented") || err.contains("Atom"));
+    assert!(result.is_ok());
+    assert_eq!(result.unwrap().len(), 0);
 }
 
 #[test]
@@ -439,9 +461,459 @@ fn test_load_empty_response_fixture() {
     if std::path::Path::new(empty_path).exists() {
         let content = std::fs::read_to_string(empty_path).unwrap();
         let result = Parser::parse("Test", "json", &content);
-        if result.is_ok() {
-            let items = result.unwrap();
+        if let Ok(items) = result {
             assert_eq!(items.len(), 0);
         }
     }
 }
+
+#[test]
+fn test_parse_generic_published_timestamp() {
+    let data = r#"[
+        {
+            "title": "Generic Item",
+            "url": "https://example.com/generic",
+            "published": "Mon, 01 Jan 2024 12:00:00 GMT"
+        }
+    ]"#;
+
+    let result = Parser::parse("Custom API", "json", data);
+    assert!(result.is_ok());
+    let items = result.unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].title, "Generic Item");
+    assert_eq!(items[0].timestamp.as_deref(), Some("2024-01-01T12:00:00+00:00"));
+}
+
+#[test]
+fn test_parse_generic_timestamp_field_override() {
+    let data = r#"{
+        "items": [
+            {
+                "title": "Generic Item",
+                "link": "https://example.com/generic",
+                "published": "2020-01-01T00:00:00Z",
+                "posted": 1704067200
+            }
+        ]
+    }"#;
+
+    let options = ParseOptions {
+        timestamp_field: Some("posted".to_string()),
+        ..Default::default()
+    };
+    let result = Parser::parse_with_options("Custom API", "json", data, &options);
+    assert!(result.is_ok());
+    let items = result.unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].url, "https://example.com/generic");
+    assert_eq!(items[0].timestamp.as_deref(), Some("2024-01-01T00:00:00+00:00"));
+}
+
+#[test]
+fn test_explicit_parser_overrides_name() {
+    let data = r#"{"data": {"children": [{"data": {"title": "Post", "url": "https://example.com/post"}}]}}"#;
+
+    // "Work Watch" infers the generic parser, which finds no items here
+    assert!(Parser::parse("Work Watch", "json", data).unwrap().is_empty());
+
+    let options = ParseOptions {
+        parser: Some(JsonParser::Reddit),
+        ..Default::default()
+    };
+    let items = Parser::parse_with_options("Work Watch", "json", data, &options).unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].title, "Post");
+
+    // The explicit choice wins even when the name suggests another parser
+    assert_eq!(Parser::json_parser_for("HackerNews Reddit Mirror", &options), "reddit");
+    assert_eq!(Parser::json_parser_for("HackerNews Top", &ParseOptions::default()), "hackernews");
+}
+
+#[test]
+fn test_mapped_field_paths() {
+    let data = r#"{
+        "data": [
+            {
+                "id": 1,
+                "attributes": {"title": "Mapped", "published_at": "2024-01-02T03:04:05Z"},
+                "links": [{"href": "https://example.com/mapped"}]
+            },
+            {
+                "id": 2,
+                "attributes": {"title": "No link"},
+                "links": []
+            }
+        ]
+    }"#;
+
+    let options = ParseOptions {
+        items_path: Some("$.data".to_string()),
+        title_path: Some("$.attributes.title".to_string()),
+        url_path: Some("$.links[0].href".to_string()),
+        timestamp_field: Some("attributes.published_at".to_string()),
+        ..Default::default()
+    };
+    let items = Parser::parse_with_options("Custom API", "json", data, &options).unwrap();
+
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].title, "Mapped");
+    assert_eq!(items[0].url, "https://example.com/mapped");
+    assert_eq!(items[0].timestamp.as_deref(), Some("2024-01-02T03:04:05+00:00"));
+}
+
+#[test]
+fn test_normalize_timestamp_formats() {
+    assert_eq!(
+        Parser::normalize_timestamp("2024-01-01T14:00:00+02:00").as_deref(),
+        Some("2024-01-01T12:00:00+00:00")
+    );
+    assert_eq!(
+        Parser::normalize_timestamp("2024-01-01").as_deref(),
+        Some("2024-01-01T00:00:00+00:00")
+    );
+    assert!(Parser::normalize_timestamp("not a date").is_none());
+}
+
+#[test]
+fn test_lookup_path() {
+    let json: serde_json::Value = serde_json::from_str(
+        r#"{"data": {"children": [{"title": "First"}]}}"#
+    ).unwrap();
+
+    assert_eq!(
+        Parser::lookup_path(&json, "data.children.0.title").and_then(|v| v.as_str()),
+        Some("First")
+    );
+    assert!(Parser::lookup_path(&json, "data.missing").is_none());
+    assert!(Parser::lookup_path(&json, "data.children.5").is_none());
+}
+
+#[test]
+fn test_generic_warnings_name_the_missing_field() {
+    let data = r#"[
+        {"title": "No URL"},
+        {"url": "https://example.com/untitled"},
+        {"attributes": {"title": "Unmapped"}}
+    ]"#;
+
+    let outcome = Parser::parse_outcome("Custom API", "json", data, &ParseOptions::default()).unwrap();
+    assert_eq!(outcome.warnings, vec![
+        "Custom API item 0: missing url",
+        "Custom API item 1: missing title",
+        "Custom API item 2: missing title",
+    ]);
+
+    let options = ParseOptions {
+        title_path: Some("$.attributes.title".to_string()),
+        url_path: Some("$.links[0].href".to_string()),
+        ..Default::default()
+    };
+    let outcome = Parser::parse_outcome("Custom API", "json", data, &options).unwrap();
+    assert_eq!(outcome.warnings[2], "Custom API item 2: missing $.links[0].href");
+}
+
+#[test]
+fn test_lookup_path_prefers_exact_key() {
+    let json: serde_json::Value = serde_json::from_str(
+        r#"{"meta.published": "exact", "meta": {"published": "nested"}, "tags[0]": "bracketed"}"#
+    ).unwrap();
+
+    assert_eq!(Parser::lookup_path(&json, "meta.published").and_then(|v| v.as_str()), Some("exact"));
+    assert_eq!(Parser::lookup_path(&json, "tags[0]").and_then(|v| v.as_str()), Some("bracketed"));
+    assert_eq!(Parser::lookup_path(&json, "$.meta.published").and_then(|v| v.as_str()), Some("nested"));
+}
+
+#[test]
+fn test_timestamp_field_with_dotted_key() {
+    let data = r#"[{"title": "Dotted", "url": "https://example.com/dotted", "date.published": "2024-03-04T05:06:07Z"}]"#;
+
+    let options = ParseOptions {
+        timestamp_field: Some("date.published".to_string()),
+        ..Default::default()
+    };
+    let items = Parser::parse_with_options("Custom API", "json", data, &options).unwrap();
+    assert_eq!(items[0].timestamp.as_deref(), Some("2024-03-04T05:06:07+00:00"));
+}
+
+fn synthetic_rss(count: usize) -> String {
+    let mut doc = String::from(r#"<?xml version="1.0"?><rss version="2.0"><channel><title>Archive</title>"#);
+    for i in 0..count {
+        doc.push_str(&format!(
+            "<item><title>Post {0}</title><link>https://example.com/posts/{0}</link>\
+             <description><![CDATA[<p>Body of post {0}</p>]]></description></item>",
+            i
+        ));
+    }
+    doc.push_str("</channel></rss>");
+    doc
+}
+
+#[test]
+fn test_parse_rss_streams_large_document() {
+    let data = synthetic_rss(50_000);
+    assert!(data.len() > 5_000_000, "Document should be several megabytes");
+
+    let items = Parser::parse("Archive", "rss", &data).unwrap();
+    assert_eq!(items.len(), 50_000);
+    assert_eq!(items[0].title, "Post 0");
+    assert_eq!(items[49_999].url, "https://example.com/posts/49999");
+}
+
+#[test]
+fn test_parse_rss_stops_at_max_items() {
+    let data = synthetic_rss(1_000);
+    let options = ParseOptions {
+        max_items: Some(10),
+        ..Default::default()
+    };
+
+    let items = Parser::parse_with_options("Archive", "rss", &data, &options).unwrap();
+    assert_eq!(items.len(), 10);
+    assert_eq!(items[9].title, "Post 9");
+}
+
+#[test]
+fn test_parse_github_top_level_array_with_items_path() {
+    let data = r#"[
+        {"full_name": "rust-lang/rust", "html_url": "https://github.com/rust-lang/rust", "updated_at": "2024-01-01T00:00:00Z"},
+        {"full_name": "tokio-rs/tokio", "html_url": "https://github.com/tokio-rs/tokio"}
+    ]"#;
+
+    let items = Parser::parse("GitHub Repos", "json", data).unwrap();
+    assert!(items.is_empty(), "Default path expects a top-level `items` key");
+
+    let options = ParseOptions {
+        items_path: Some(String::new()),
+        ..Default::default()
+    };
+    let items = Parser::parse_with_options("GitHub Repos", "json", data, &options).unwrap();
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0].title, "rust-lang/rust");
+    assert_eq!(items[1].url, "https://github.com/tokio-rs/tokio");
+}
+
+#[test]
+fn test_parse_reddit_custom_items_path() {
+    let data = r#"{"listing": {"posts": [
+        {"data": {"title": "Hello", "url": "https://example.com/hello", "created_utc": 1700000000}}
+    ]}}"#;
+
+    let result = Parser::parse("Reddit Custom", "json", data);
+    assert!(result.unwrap_err().contains("data.children"));
+
+    let options = ParseOptions {
+        items_path: Some("listing.posts".to_string()),
+        ..Default::default()
+    };
+    let items = Parser::parse_with_options("Reddit Custom", "json", data, &options).unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].title, "Hello");
+}
+
+#[test]
+fn test_reading_time_from_summary() {
+    let words = vec!["word"; 450].join(" ");
+    let data = format!(r#"<?xml version="1.0"?>
+<rss version="2.0"><channel>
+  <item>
+    <title>Long read</title>
+    <link>https://example.com/long</link>
+    <description><![CDATA[<p>{}</p>]]></description>
+  </item>
+  <item>
+    <title>Link only</title>
+    <link>https://example.com/short</link>
+  </item>
+</channel></rss>"#, words);
+
+    let items = Parser::parse("Blog", "rss", &data).unwrap();
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0].reading_time_minutes, Some(3), "450 words at 200 wpm rounds up to 3 minutes");
+    assert!(items[0].summary.as_deref().unwrap().starts_with("<p>word"));
+    assert_eq!(items[1].reading_time_minutes, None, "No content, no estimate");
+
+    let json = r#"[{"title": "Short", "url": "https://example.com/s", "summary": "just a few words"}]"#;
+    let items = Parser::parse("Generic", "json", json).unwrap();
+    assert_eq!(items[0].reading_time_minutes, Some(1));
+}
+
+#[test]
+fn test_namespaced_tags_for_reddit_and_rss() {
+    let reddit = r#"{"data": {"children": [
+        {"data": {"title": "Ask", "url": "https://reddit.com/r/programming/1", "subreddit": "programming", "link_flair_text": "Discussion"}}
+    ]}}"#;
+
+    let plain = Parser::parse("Reddit Programming", "json", reddit).unwrap();
+    assert_eq!(plain[0].tags, vec!["Discussion"]);
+
+    let options = ParseOptions {
+        namespaced_tags: true,
+        ..Default::default()
+    };
+    let namespaced = Parser::parse_with_options("Reddit Programming", "json", reddit, &options).unwrap();
+    assert_eq!(namespaced[0].tags, vec!["sub:programming", "flair:Discussion"]);
+
+    let rss = r#"<rss><channel><item>
+        <title>Post</title>
+        <link>https://example.com/post</link>
+        <category domain="section">World</category>
+        <category>Untyped</category>
+    </item></channel></rss>"#;
+
+    let plain = Parser::parse("Blog", "rss", rss).unwrap();
+    assert_eq!(plain[0].tags, vec!["World", "Untyped"]);
+
+    let namespaced = Parser::parse_with_options("Blog", "rss", rss, &options).unwrap();
+    assert_eq!(namespaced[0].tags, vec!["section:World", "Untyped"]);
+}
+
+#[test]
+fn test_generic_items_path_json_pointer_with_dotted_key() {
+    let data = r#"{"response": {"v1.items": [
+        {"title": "First", "url": "https://example.com/1"},
+        {"title": "Second", "url": "https://example.com/2"}
+    ]}}"#;
+
+    let pointer = ParseOptions {
+        items_path: Some("/response/v1.items".to_string()),
+        ..Default::default()
+    };
+    let items = Parser::parse_with_options("Custom API", "json", data, &pointer).unwrap();
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[1].title, "Second");
+
+    // The dotted form splits the key and finds nothing
+    let dotted = ParseOptions {
+        items_path: Some("response.v1.items".to_string()),
+        ..Default::default()
+    };
+    assert!(Parser::parse_with_options("Custom API", "json", data, &dotted).is_err());
+}
+
+#[test]
+fn test_parse_rss_channel_items() {
+    let data = r#"<?xml version="1.0"?>
+<rss version="2.0"><channel>
+  <title>Example</title>
+  <link>https://example.com/</link>
+  <item>
+    <title>First post</title>
+    <link>https://example.com/first</link>
+    <pubDate>Tue, 02 Jan 2024 15:04:05 +0200</pubDate>
+    <category>rust</category>
+    <category>release</category>
+  </item>
+  <item>
+    <title>No link here</title>
+    <pubDate>Wed, 03 Jan 2024 10:00:00 GMT</pubDate>
+  </item>
+  <item>
+    <title>Second post</title>
+    <link>https://example.com/second</link>
+  </item>
+  <item>
+    <link>https://example.com/untitled</link>
+  </item>
+  <item>
+    <description>Neither title nor link</description>
+  </item>
+</channel></rss>"#;
+
+    let outcome = Parser::parse_outcome("Example Blog", "rss", data, &ParseOptions::default()).unwrap();
+    assert_eq!(outcome.warnings, vec![
+        "Example Blog item 1: missing link",
+        "Example Blog item 4: missing title and link",
+    ]);
+
+    let items = outcome.items;
+    assert_eq!(items.len(), 3, "Items without a link are skipped");
+
+    assert_eq!(items[0].title, "First post");
+    assert_eq!(items[0].url, "https://example.com/first");
+    assert_eq!(items[0].timestamp.as_deref(), Some("2024-01-02T13:04:05+00:00"));
+    assert_eq!(items[0].tags, vec!["rust", "release"]);
+    assert_eq!(items[0].id, FeedItem::generate_id("Example Blog", "https://example.com/first"));
+
+    assert_eq!(items[1].title, "Second post");
+    assert!(items[1].timestamp.is_none());
+
+    assert_eq!(items[2].title, "https://example.com/untitled", "Untitled item falls back to its link");
+    assert_eq!(items[2].url, "https://example.com/untitled");
+}
+
+#[test]
+fn test_parse_atom_entries() {
+    let data = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Example Atom</title>
+  <link href="https://example.com/"/>
+  <entry>
+    <title>Minimal entry</title>
+    <link rel="self" href="https://example.com/api/1"/>
+    <link rel="alternate" href="https://example.com/posts/1"/>
+    <updated>2024-03-01T12:00:00Z</updated>
+    <category term="rust"/>
+    <category term="async"/>
+    <category term="tokio"/>
+  </entry>
+  <entry>
+    <title>Self link only</title>
+    <link rel="self" href="https://example.com/api/2"/>
+    <published>2024-03-02T08:30:00+01:00</published>
+  </entry>
+  <entry>
+    <title>No link at all</title>
+  </entry>
+</feed>"#;
+
+    let items = Parser::parse("Atom Blog", "atom", data).unwrap();
+    assert_eq!(items.len(), 2, "Entry without any link is skipped");
+
+    assert_eq!(items[0].url, "https://example.com/posts/1", "Alternate link wins over self");
+    assert_eq!(items[0].timestamp.as_deref(), Some("2024-03-01T12:00:00+00:00"));
+    assert_eq!(items[0].tags, vec!["rust", "async", "tokio"]);
+
+    assert_eq!(items[1].url, "https://example.com/api/2", "Falls back to the first link");
+    assert_eq!(items[1].timestamp.as_deref(), Some("2024-03-02T07:30:00+00:00"));
+    assert!(items[1].tags.is_empty());
+}
+
+#[test]
+fn test_normalize_urls_strips_tracking_params_and_fragment() {
+    let data = r#"[
+        {"title": "Mixed", "url": "https://example.com/post?id=42&utm_source=news&page=2&fbclid=abc#comments"},
+        {"title": "Clean", "url": "https://example.com/other?q=a%20b"}
+    ]"#;
+    let params: Vec<String> = ["utm_*", "fbclid"].into_iter().map(String::from).collect();
+
+    let options = ParseOptions {
+        parser: Some(JsonParser::Generic),
+        strip_tracking_params: Some(params),
+        ..Default::default()
+    };
+    let items = Parser::parse_with_options("Custom API", "json", data, &options).unwrap();
+    assert_eq!(items[0].url, "https://example.com/post?id=42&page=2");
+    assert_eq!(items[0].id, FeedItem::generate_id("Custom API", "https://example.com/post?id=42&page=2"));
+    // Nothing to strip: the URL and its encoding are left alone
+    assert_eq!(items[1].url, "https://example.com/other?q=a%20b");
+
+    // Off by default
+    let options = ParseOptions { parser: Some(JsonParser::Generic), ..Default::default() };
+    let items = Parser::parse_with_options("Custom API", "json", data, &options).unwrap();
+    assert_eq!(items[0].url, "https://example.com/post?id=42&utm_source=news&page=2&fbclid=abc#comments");
+}
+
+#[test]
+fn test_normalize_urls_drops_empty_query() {
+    let data = r#"<?xml version="1.0"?>
+<rss version="2.0"><channel><title>Example</title>
+  <item><title>Tracked</title><link>https://example.com/a?utm_medium=rss&amp;utm_campaign=x</link></item>
+</channel></rss>"#;
+    let options = ParseOptions {
+        strip_tracking_params: Some(vec!["utm_*".to_string()]),
+        ..Default::default()
+    };
+    let items = Parser::parse_with_options("Example", "rss", data, &options).unwrap();
+    assert_eq!(items[0].url, "https://example.com/a");
+}
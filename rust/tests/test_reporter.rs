@@ -0,0 +1,948 @@
+/// Tests for report generation and source listings
+use feedpulse::config::Config;
+use feedpulse::fetcher::FetchResult;
+use feedpulse::models::{FeedItem, FetchStatus};
+use feedpulse::parser::Parser;
+use feedpulse::reporter::{fetch_summary_json, parse_since, Reporter};
+use feedpulse::storage::{ItemQuery, Storage};
+use std::fs;
+use tempfile::{NamedTempFile, TempDir};
+
+fn load_config(yaml: &str) -> Config {
+    let temp_file = NamedTempFile::new().unwrap();
+    fs::write(&temp_file, yaml).unwrap();
+    Config::load(temp_file.path()).unwrap()
+}
+
+#[test]
+fn test_db_sources_flags_orphaned() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("sources.db");
+    let storage = Storage::new(&db_path).unwrap();
+
+    storage.store_item(&FeedItem::new(
+        "Current".to_string(),
+        "https://example.com/current".to_string(),
+        "Lobsters".to_string(),
+    )).unwrap();
+    storage.store_item(&FeedItem::new(
+        "Stale".to_string(),
+        "https://example.com/stale".to_string(),
+        "Old Feed".to_string(),
+    )).unwrap();
+
+    let config = load_config(r#"
+feeds:
+  - name: "Lobsters"
+    url: "https://lobste.rs/hottest.json"
+    feed_type: json
+"#);
+
+    let reporter = Reporter::new(storage);
+    let sources = reporter.db_sources(&config).unwrap();
+
+    assert_eq!(sources.len(), 2);
+
+    let old = sources.iter().find(|s| s.source == "Old Feed").unwrap();
+    assert!(old.orphaned, "Source missing from config should be orphaned");
+
+    let lobsters = sources.iter().find(|s| s.source == "Lobsters").unwrap();
+    assert!(!lobsters.orphaned, "Configured source should not be orphaned");
+}
+
+fn fetch_result(source: &str, cert_expires_at: Option<String>) -> FetchResult {
+    FetchResult {
+        source: source.to_string(),
+        items: Vec::new(),
+        duration_ms: 10,
+        cert_expires_at,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_cert_expiry_recorded_and_warned() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("certs.db");
+    let storage = Storage::new(&db_path).unwrap();
+
+    let soon = (chrono::Utc::now() + chrono::Duration::days(5)).to_rfc3339();
+    let later = (chrono::Utc::now() + chrono::Duration::days(200)).to_rfc3339();
+
+    let mut results = vec![
+        fetch_result("Internal", Some(soon.clone())),
+        fetch_result("Public", Some(later)),
+        fetch_result("Plain HTTP", None),
+    ];
+    storage.store_results(&mut results).unwrap();
+
+    let expiries = storage.get_cert_expiries().unwrap();
+    assert_eq!(expiries.len(), 2, "Plain-HTTP feeds should not record an expiry");
+
+    let reporter = Reporter::new(storage);
+    let warnings = reporter.cert_warnings(14).unwrap();
+
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].source, "Internal");
+    assert_eq!(warnings[0].expires_at, soon);
+    assert!(warnings[0].days_left <= 5);
+}
+
+#[test]
+fn test_table_report_respects_width() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = Storage::new(temp_dir.path().join("width.db")).unwrap();
+
+    let mut results = vec![fetch_result(
+        "A Source With A Deliberately Long Name For Wrapping",
+        None,
+    )];
+    storage.store_results(&mut results).unwrap();
+
+    let reporter = Reporter::new(storage).with_width(Some(40));
+    let output = reporter.render_report("table", None, None).unwrap();
+
+    let table_lines: Vec<_> = output.lines().take_while(|l| !l.is_empty()).collect();
+    assert!(!table_lines.is_empty());
+    for line in table_lines {
+        assert!(line.chars().count() <= 40, "Line exceeds width: {:?}", line);
+    }
+    assert!(output.contains("Total: 1 items across 1 sources"));
+}
+
+#[test]
+fn test_stale_body_warning() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = Storage::new(temp_dir.path().join("stale.db")).unwrap();
+
+    let with_hash = |source: &str, hash: &str| FetchResult {
+        source: source.to_string(),
+        body_hash: Some(hash.to_string()),
+        ..Default::default()
+    };
+
+    for hash in ["h0", "same", "same", "same"] {
+        storage.store_results(&mut [with_hash("Zombie", hash), with_hash("Live", hash)]).unwrap();
+    }
+    storage.store_results(&mut [with_hash("Live", "fresh")]).unwrap();
+
+    let reporter = Reporter::new(storage);
+
+    let warnings = reporter.stale_warnings(3).unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].source, "Zombie");
+    assert_eq!(warnings[0].repeats, 3);
+
+    assert!(reporter.stale_warnings(4).unwrap().is_empty());
+}
+
+#[test]
+fn test_markdown_digest_groups_and_escapes() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = Storage::new(temp_dir.path().join("digest.db")).unwrap();
+
+    let mut tricky = FeedItem::new(
+        "Rust [1.80] *released* with_underscores".to_string(),
+        "https://example.com/rust_(release)".to_string(),
+        "Lobsters".to_string(),
+    );
+    tricky.timestamp = Some("2024-07-25T12:00:00+00:00".to_string());
+    storage.store_item(&tricky).unwrap();
+    storage.store_item(&FeedItem::new(
+        "Show HN: a thing".to_string(),
+        "https://example.com/hn".to_string(),
+        "HackerNews".to_string(),
+    )).unwrap();
+    storage.store_item(&FeedItem::new(
+        "Long read".to_string(),
+        "https://example.com/long".to_string(),
+        "HackerNews".to_string(),
+    ).with_summary(Some(format!("A *bold*\n\n  claim. {}", "word ".repeat(60))))).unwrap();
+
+    let reporter = Reporter::new(storage);
+    let digest = reporter.render_digest(None, None).unwrap();
+
+    assert!(digest.starts_with("# Feed Digest\n"));
+    let hn = digest.find("## HackerNews").expect("HackerNews heading");
+    let lobsters = digest.find("## Lobsters").expect("Lobsters heading");
+    assert!(hn < lobsters, "Sources should be sorted alphabetically");
+    assert!(digest.contains(
+        "- [Rust \\[1.80\\] \\*released\\* with\\_underscores](https://example.com/rust_%28release%29) (2024-07-25T12:00:00+00:00)"
+    ));
+    assert!(digest.contains("- [Show HN: a thing](https://example.com/hn)\n"));
+    let summary = digest.lines()
+        .skip_while(|line| !line.starts_with("- [Long read]"))
+        .nth(1)
+        .unwrap();
+    assert!(summary.starts_with("  A \\*bold\\* claim. word word"), "{}", summary);
+    assert!(summary.ends_with('…'));
+    assert_eq!(summary.trim_start().replace('\\', "").chars().count(), 200);
+
+    let filtered = reporter.render_digest(Some("HackerNews"), None).unwrap();
+    assert!(!filtered.contains("Lobsters"));
+
+    let future = reporter.render_digest(None, Some("2999-01-01T00:00:00+00:00")).unwrap();
+    assert!(future.contains("No new items."));
+}
+
+#[test]
+fn test_render_items_formats() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = Storage::new(temp_dir.path().join("items.db")).unwrap();
+
+    let mut long = FeedItem::new(
+        "A very long title that keeps going well past the width of the items table column".to_string(),
+        "https://example.com/long".to_string(),
+        "Lobsters".to_string(),
+    );
+    long.timestamp = Some("2024-07-25T12:00:00+00:00".to_string());
+    storage.store_item(&long).unwrap();
+    let mut older = FeedItem::new(
+        "Older, with a comma".to_string(),
+        "https://example.com/older".to_string(),
+        "HackerNews".to_string(),
+    );
+    older.timestamp = Some("2024-07-24T12:00:00+00:00".to_string());
+    storage.store_item(&older).unwrap();
+
+    let reporter = Reporter::new(storage).with_width(Some(200));
+
+    let table = reporter.render_items("table", &ItemQuery::default()).unwrap();
+    assert!(table.contains("A very long title that keeps going well past the width of t…"));
+    assert!(table.contains("https://example.com/long"));
+    assert!(table.ends_with("2 items"));
+
+    let json = reporter.render_items("json", &ItemQuery { limit: Some(1), ..Default::default() }).unwrap();
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let items = parsed["items"].as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["title"], long.title.as_str());
+
+    let csv = reporter.render_items("csv", &ItemQuery { source: Some("HackerNews".to_string()), ..Default::default() }).unwrap();
+    let lines: Vec<_> = csv.lines().collect();
+    assert_eq!(lines[0], "id,title,url,source,timestamp,tags");
+    assert_eq!(lines.len(), 2);
+    assert!(lines[1].contains("\"Older, with a comma\",https://example.com/older,HackerNews,2024-07-24T12:00:00+00:00"));
+
+    assert!(reporter.render_items("xml", &ItemQuery::default()).is_err());
+}
+
+#[test]
+fn test_render_duplicates_reports_cross_posted_urls() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = Storage::new(temp_dir.path().join("duplicates.db")).unwrap();
+
+    storage.store_item(&FeedItem::new(
+        "Rust 2024".to_string(),
+        "https://www.example.com/rust-2024/?utm_source=hn".to_string(),
+        "HackerNews".to_string(),
+    )).unwrap();
+    storage.store_item(&FeedItem::new(
+        "Rust 2024 released".to_string(),
+        "https://example.com/rust-2024".to_string(),
+        "Lobsters".to_string(),
+    )).unwrap();
+    storage.store_item(&FeedItem::new(
+        "Only here".to_string(),
+        "https://example.com/solo".to_string(),
+        "Lobsters".to_string(),
+    )).unwrap();
+
+    let reporter = Reporter::new(storage).with_width(Some(200));
+
+    let json = reporter.render_duplicates("json", None, None).unwrap();
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let clusters = parsed["clusters"].as_array().unwrap();
+    assert_eq!(clusters.len(), 1);
+    assert_eq!(clusters[0]["canonical_url"], "https://example.com/rust-2024");
+    assert_eq!(clusters[0]["sources"], serde_json::json!(["HackerNews", "Lobsters"]));
+    assert_eq!(clusters[0]["items"], 2);
+
+    let table = reporter.render_duplicates("table", None, None).unwrap();
+    assert!(table.contains("HackerNews, Lobsters"));
+    assert!(!table.contains("solo"));
+
+    let csv = reporter.render_duplicates("csv", None, None).unwrap();
+    assert_eq!(csv, "canonical_url,sources,items\nhttps://example.com/rust-2024,HackerNews;Lobsters,2");
+
+    // --source keeps clusters the source is part of; --since counts only newer items
+    let only = |source| reporter.render_duplicates("csv", Some(source), None).unwrap().lines().count() - 1;
+    assert_eq!(only("Lobsters"), 1);
+    assert_eq!(only("Reddit"), 0);
+    let future = reporter.render_duplicates("csv", None, Some("2999-01-01T00:00:00+00:00")).unwrap();
+    assert_eq!(future, "canonical_url,sources,items");
+}
+
+#[test]
+fn test_parse_since() {
+    assert!(parse_since("24h").unwrap() < chrono::Utc::now().to_rfc3339());
+    assert_eq!(
+        parse_since("2024-01-01T02:00:00+02:00").unwrap(),
+        "2024-01-01T00:00:00+00:00"
+    );
+    assert!(parse_since("yesterday").is_err());
+    assert!(parse_since("5y").is_err());
+}
+
+#[test]
+fn test_timeseries_counts_per_day() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("timeseries.db");
+    let storage = Storage::new(&db_path).unwrap();
+
+    let stored = [
+        ("A", "Lobsters", "2024-05-01T08:00:00.123456789+00:00"),
+        ("B", "Lobsters", "2024-05-01T22:30:00+00:00"),
+        ("C", "HackerNews", "2024-05-01T23:59:59+00:00"),
+        ("D", "Lobsters", "2024-05-02T00:00:01+00:00"),
+        ("E", "HackerNews", "2024-05-04T12:00:00+00:00"),
+    ];
+    for (title, source, _) in &stored {
+        storage.store_item(&FeedItem::new(
+            title.to_string(),
+            format!("https://example.com/{}", title),
+            source.to_string(),
+        )).unwrap();
+    }
+
+    // Backdate created_at so items span several days
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    for (title, _, created_at) in &stored {
+        conn.execute(
+            "UPDATE feed_items SET created_at = ?1 WHERE title = ?2",
+            rusqlite::params![created_at, title],
+        ).unwrap();
+    }
+
+    let daily: Vec<_> = storage.get_item_timeseries("day", None, false).unwrap()
+        .into_iter()
+        .map(|p| (p.bucket, p.count))
+        .collect();
+    assert_eq!(daily, vec![
+        ("2024-05-01".to_string(), 3),
+        ("2024-05-02".to_string(), 1),
+        ("2024-05-04".to_string(), 1),
+    ]);
+
+    let by_source = storage.get_item_timeseries("day", None, true).unwrap();
+    assert_eq!(by_source.len(), 4);
+    assert_eq!(by_source[0].source.as_deref(), Some("HackerNews"));
+    assert_eq!(by_source[1].source.as_deref(), Some("Lobsters"));
+    assert_eq!(by_source[1].count, 2);
+
+    let hourly = storage.get_item_timeseries("hour", Some("Lobsters"), false).unwrap();
+    assert_eq!(hourly.len(), 3);
+    assert_eq!(hourly[0].bucket, "2024-05-01T08:00");
+
+    assert!(storage.get_item_timeseries("month", None, false).is_err());
+
+    let reporter = Reporter::new(storage);
+    let json = reporter.render_timeseries("json", "week", None, false).unwrap();
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed["bucket"], "week");
+    assert_eq!(parsed["series"][0]["count"], 5);
+}
+
+#[test]
+fn test_export_pages_without_duplicates() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("export.db");
+    let storage = Storage::new(&db_path).unwrap();
+
+    for i in 0..25 {
+        storage.store_item(&FeedItem::new(
+            format!("Item, \"{}\"", i),
+            format!("https://example.com/{}", i),
+            "Bulk".to_string(),
+        )).unwrap();
+    }
+
+    // Identical created_at values force the id tie-breaker at page boundaries
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    conn.execute("UPDATE feed_items SET created_at = '2024-01-01T00:00:00+00:00'", []).unwrap();
+
+    let reporter = Reporter::new(storage);
+
+    let mut jsonl = Vec::new();
+    assert_eq!(reporter.export_items(&mut jsonl, "jsonl", None, 7).unwrap(), 25);
+
+    let ids: Vec<String> = String::from_utf8(jsonl).unwrap()
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["id"].as_str().unwrap().to_string())
+        .collect();
+    let unique: std::collections::HashSet<_> = ids.iter().collect();
+    assert_eq!(ids.len(), 25);
+    assert_eq!(unique.len(), 25);
+
+    let mut csv = Vec::new();
+    assert_eq!(reporter.export_items(&mut csv, "csv", Some("Bulk"), 5).unwrap(), 25);
+    let csv = String::from_utf8(csv).unwrap();
+    assert_eq!(csv.lines().count(), 26);
+    assert!(csv.contains("\"Item, \"\"3\"\"\""));
+
+    assert!(reporter.export_items(&mut Vec::new(), "xml", None, 5).is_err());
+}
+
+#[test]
+fn test_export_fetch_log_matches_stored_rows() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = Storage::new(temp_dir.path().join("log.db")).unwrap();
+
+    let mut results = vec![
+        FetchResult {
+            source: "Lobsters".to_string(),
+            duration_ms: 120,
+            body_hash: Some("abc".to_string()),
+            ..Default::default()
+        },
+        FetchResult {
+            source: "Broken, Inc".to_string(),
+            duration_ms: 30,
+            error: Some("HTTP 500 \"oops\"".to_string()),
+            ..Default::default()
+        },
+    ];
+    storage.store_results(&mut results).unwrap();
+
+    let reporter = Reporter::new(storage);
+
+    let mut jsonl = Vec::new();
+    assert_eq!(reporter.export_fetch_log(&mut jsonl, "jsonl", None, None).unwrap(), 2);
+    let rows: Vec<serde_json::Value> = String::from_utf8(jsonl).unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(rows[0]["source"], "Lobsters");
+    assert_eq!(rows[0]["status"], "success");
+    assert_eq!(rows[0]["duration_ms"], 120);
+    assert_eq!(rows[0]["body_hash"], "abc");
+    assert_eq!(rows[1]["status"], "error");
+    assert_eq!(rows[1]["error_message"], "HTTP 500 \"oops\"");
+
+    let mut csv = Vec::new();
+    assert_eq!(reporter.export_fetch_log(&mut csv, "csv", Some("Broken, Inc"), None).unwrap(), 1);
+    let csv = String::from_utf8(csv).unwrap();
+    let lines: Vec<_> = csv.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("fetched_at,source,status,items_count,error_message,duration_ms"));
+    assert!(lines[1].contains(",\"Broken, Inc\",error,0,\"HTTP 500 \"\"oops\"\"\",30,"));
+
+    let future = "2999-01-01T00:00:00+00:00";
+    assert_eq!(reporter.export_fetch_log(&mut Vec::new(), "jsonl", None, Some(future)).unwrap(), 0);
+}
+
+#[test]
+fn test_json_report_numeric_error_rate_and_status() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = Storage::new(temp_dir.path().join("status.db")).unwrap();
+
+    let outcome = |source: &str, failed: bool| FetchResult {
+        source: source.to_string(),
+        error: failed.then(|| "HTTP 500".to_string()),
+        ..Default::default()
+    };
+
+    // Healthy: 10 successes; Degraded: 3 of 10 failed; Failing: never succeeded
+    let mut results = Vec::new();
+    for i in 0..10 {
+        results.push(outcome("Healthy", false));
+        results.push(outcome("Degraded", i < 3));
+    }
+    results.push(outcome("Failing", true));
+    storage.store_results(&mut results).unwrap();
+
+    let reporter = Reporter::new(storage);
+    let json: serde_json::Value = serde_json::from_str(&reporter.render_report("json", None, None).unwrap()).unwrap();
+    let source = |name: &str| json["sources"].as_array().unwrap().iter()
+        .find(|s| s["source"] == name)
+        .unwrap()
+        .clone();
+
+    let healthy = source("Healthy");
+    assert_eq!(healthy["error_rate"], 0.0);
+    assert_eq!(healthy["status"], "healthy");
+    assert_eq!(healthy["is_error"], false);
+
+    let degraded = source("Degraded");
+    assert!(degraded["error_rate"].is_f64());
+    assert_eq!(degraded["error_rate_display"], "30.0%");
+    assert_eq!(degraded["status"], "degraded");
+    assert_eq!(degraded["is_error"], false);
+
+    let failing = source("Failing");
+    assert_eq!(failing["status"], "failing");
+    assert_eq!(failing["is_error"], true);
+}
+
+#[test]
+fn test_status_thresholds_use_share_of_fetches_that_failed() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = Storage::new(temp_dir.path().join("thresholds.db")).unwrap();
+
+    // (source, failed fetches, total fetches); the last fetch always succeeds
+    let sources = [
+        ("Under10", 1, 11),
+        ("At10", 1, 10),
+        ("Under50", 4, 9),
+        ("At50", 5, 10),
+        ("At90", 9, 10),
+    ];
+    let mut results = Vec::new();
+    for (source, failed, total) in sources {
+        for i in 0..total {
+            results.push(FetchResult {
+                source: source.to_string(),
+                error: (i < failed).then(|| "HTTP 500".to_string()),
+                ..Default::default()
+            });
+        }
+    }
+    storage.store_results(&mut results).unwrap();
+
+    let reporter = Reporter::new(storage);
+    let json: serde_json::Value = serde_json::from_str(&reporter.render_report("json", None, None).unwrap()).unwrap();
+    let source = |name: &str| json["sources"].as_array().unwrap().iter()
+        .find(|s| s["source"] == name)
+        .unwrap()
+        .clone();
+
+    assert_eq!(source("Under10")["status"], "healthy");
+    assert_eq!(source("At10")["error_rate"], 10.0);
+    assert_eq!(source("At10")["status"], "degraded");
+    assert_eq!(source("Under50")["status"], "degraded");
+    assert_eq!(source("At50")["error_rate"], 50.0);
+    assert_eq!(source("At50")["status"], "failing");
+    assert_eq!(source("At90")["error_rate"], 90.0);
+    assert_eq!(source("At90")["status"], "failing");
+}
+
+#[test]
+fn test_report_limit_counts_recent_fetches_per_source() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = Storage::new(temp_dir.path().join("limit.db")).unwrap();
+
+    // Old failures followed by recent successes
+    for failed in [true, true, true, false, false] {
+        storage.store_results(&mut [FetchResult {
+            source: "Recovering".to_string(),
+            error: failed.then(|| "HTTP 503".to_string()),
+            ..Default::default()
+        }]).unwrap();
+    }
+    storage.store_results(&mut [fetch_result("Steady", None)]).unwrap();
+
+    let csv = |reporter: &Reporter| reporter.render_report("csv", None, None).unwrap();
+
+    let all = Reporter::new(storage);
+    assert!(csv(&all).contains("Recovering,5,3,"));
+
+    let recent = all.with_limit(Some(2));
+    let report = csv(&recent);
+    assert!(report.contains("Recovering,2,0,0.0%"), "{}", report);
+    assert!(report.contains("Steady,1,0,0.0%"));
+
+    let unlimited = recent.with_limit(Some(0));
+    assert!(csv(&unlimited).contains("Recovering,5,3,"));
+}
+
+#[test]
+fn test_report_since_filters_old_fetches() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("since.db");
+    let storage = Storage::new(&db_path).unwrap();
+
+    let mut results = vec![
+        fetch_result("Lobsters", None),
+        fetch_result("Lobsters", None),
+        fetch_result("Ancient", None),
+    ];
+    storage.store_results(&mut results).unwrap();
+
+    // Push one Lobsters fetch and the only Ancient fetch into the past
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    conn.execute(
+        "UPDATE fetch_log SET fetched_at = '2020-01-01T00:00:00+00:00' WHERE id IN (1, 3)",
+        [],
+    ).unwrap();
+
+    let reporter = Reporter::new(storage);
+    let report = reporter.render_report("csv", None, Some("24h")).unwrap();
+    assert!(report.contains("Lobsters,1,"), "{}", report);
+    assert!(!report.contains("Ancient"));
+
+    let report = reporter.render_report("csv", None, Some("2019-06-01T00:00:00Z")).unwrap();
+    assert!(report.contains("Lobsters,2,"));
+    assert!(report.contains("Ancient,1,"));
+
+    let err = reporter.render_report("csv", None, Some("yesterday")).unwrap_err();
+    assert!(err.contains("invalid --since value"));
+}
+
+#[test]
+fn test_report_shows_recent_errors() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = Storage::new(temp_dir.path().join("errors.db")).unwrap();
+
+    let failure = |source: &str, error: &str| FetchResult {
+        error: Some(error.to_string()),
+        ..fetch_result(source, None)
+    };
+    let mut results = vec![
+        failure("Broken", "HTTP 500 Internal Server Error after 3 retries"),
+        fetch_result("Working", None),
+    ];
+    storage.store_results(&mut results).unwrap();
+    let mut results = vec![
+        failure("Broken", "HTTP 403 Forbidden after 3 retries"),
+        failure("Working", "timed out after 10s fetching https://example.com/feed"),
+    ];
+    storage.store_results(&mut results).unwrap();
+
+    let errors = storage.get_recent_errors("Broken", 5).unwrap();
+    assert_eq!(
+        errors.iter().map(|(_, message)| message.as_str()).collect::<Vec<_>>(),
+        vec!["HTTP 403 Forbidden after 3 retries", "HTTP 500 Internal Server Error after 3 retries"]
+    );
+    assert_eq!(storage.get_recent_errors("Broken", 1).unwrap().len(), 1);
+
+    // The never-succeeded source shows its latest error inline
+    let reporter = Reporter::new(storage).with_width(Some(200));
+    let output = reporter.render_report("table", None, None).unwrap();
+    assert!(output.contains("never: HTTP 403 Forbidden after 3 retries"), "{}", output);
+    assert!(!output.contains("Recent errors for"));
+
+    let reporter = reporter.with_show_errors(true);
+    let output = reporter.render_report("table", None, None).unwrap();
+    let section = output.split("Recent errors for Broken:\n").nth(1).unwrap();
+    assert!(section.lines().next().unwrap().ends_with("HTTP 403 Forbidden after 3 retries"));
+    assert!(output.contains("Recent errors for Working:"));
+
+    let json: serde_json::Value = serde_json::from_str(&reporter.render_report("json", None, None).unwrap()).unwrap();
+    let working = json["sources"].as_array().unwrap().iter().find(|s| s["source"] == "Working").unwrap();
+    assert_eq!(working["recent_errors"][0]["error"], "timed out after 10s fetching https://example.com/feed");
+}
+
+#[test]
+fn test_report_by_tag_counts_matching_items() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = Storage::new(temp_dir.path().join("tag_report.db")).unwrap();
+
+    let tagged = |title: &str, source: &str, tags: &[&str]| FeedItem::new(
+        title.to_string(),
+        format!("https://example.com/{}", title),
+        source.to_string(),
+    ).with_tags(tags.iter().map(|t| t.to_string()).collect());
+    storage.store_results(&mut [
+        FetchResult { items: vec![tagged("a", "Lobsters", &["rust"]), tagged("b", "Lobsters", &["go"])], ..fetch_result("Lobsters", None) },
+        FetchResult { items: vec![tagged("c", "Reddit", &["rust", "async"])], ..fetch_result("Reddit", None) },
+    ]).unwrap();
+
+    let reporter = Reporter::new(storage).with_tags(vec!["rust".to_string()]);
+    let csv = reporter.render_report("csv", None, None).unwrap();
+    assert_eq!(csv, "Source,Items,Errors,Error Rate,Last Success,Status\nLobsters,1,n/a,n/a,n/a,unknown\nReddit,1,n/a,n/a,n/a,unknown");
+
+    let reporter = reporter.with_tags(vec!["rust".to_string(), "async".to_string()]);
+    let csv = reporter.render_report("csv", None, None).unwrap();
+    assert_eq!(csv, "Source,Items,Errors,Error Rate,Last Success,Status\nReddit,1,n/a,n/a,n/a,unknown");
+}
+
+#[test]
+fn test_render_deduped_items_groups_sources() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("dedup_view.db");
+    let storage = Storage::new(&db_path).unwrap();
+
+    let posted = |source: &str, url: &str, timestamp: &str| {
+        let mut item = FeedItem::new("Rust 2024".to_string(), url.to_string(), source.to_string());
+        item.timestamp = Some(timestamp.to_string());
+        item
+    };
+    storage.store_item(&posted("HackerNews", "https://example.com/rust-2024?utm_source=hn", "2024-07-25T12:00:00+00:00")).unwrap();
+    storage.store_item(&posted("Lobsters", "https://www.example.com/rust-2024/#comments", "2024-07-24T12:00:00+00:00")).unwrap();
+    storage.store_item(&posted("Lobsters", "https://example.com/other", "2024-07-23T12:00:00+00:00")).unwrap();
+
+    let reporter = Reporter::new(storage);
+    let csv = reporter.render_deduped_items("csv", &ItemQuery::default()).unwrap();
+    let lines: Vec<_> = csv.lines().collect();
+    assert_eq!(lines[0], "id,title,url,sources,timestamp,tags");
+    assert_eq!(lines.len(), 3);
+    assert!(lines[1].contains("https://example.com/rust-2024?utm_source=hn,HackerNews;Lobsters,"), "{}", lines[1]);
+    assert!(lines[2].contains(",Lobsters,"));
+
+    let json: serde_json::Value = serde_json::from_str(&reporter.render_deduped_items("json", &ItemQuery::default()).unwrap()).unwrap();
+    assert_eq!(json["items"][0]["sources"], serde_json::json!(["HackerNews", "Lobsters"]));
+
+    // A read-time view: all three rows are still stored
+    assert_eq!(reporter.render_items("csv", &ItemQuery::default()).unwrap().lines().count(), 4);
+}
+
+#[test]
+fn test_ndjson_writes_one_object_per_line() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = Storage::new(temp_dir.path().join("ndjson.db")).unwrap();
+
+    let mut results = vec![
+        FetchResult { source: "Lobsters".to_string(), ..Default::default() },
+        FetchResult { source: "Broken".to_string(), error: Some("HTTP 500".to_string()), ..Default::default() },
+    ];
+    storage.store_results(&mut results).unwrap();
+    for (title, source) in [("One", "Lobsters"), ("Two", "Lobsters"), ("Three", "HackerNews")] {
+        let url = format!("https://example.com/{}", title.to_lowercase());
+        storage.store_item(&FeedItem::new(title.to_string(), url, source.to_string())).unwrap();
+    }
+
+    let reporter = Reporter::new(storage);
+
+    let mut out = Vec::new();
+    reporter.write_report_ndjson(&mut out, None, None).unwrap();
+    let lines: Vec<serde_json::Value> = String::from_utf8(out).unwrap().lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let pretty: serde_json::Value = serde_json::from_str(&reporter.render_report("json", None, None).unwrap()).unwrap();
+    assert_eq!(&lines, pretty["sources"].as_array().unwrap());
+
+    let mut out = Vec::new();
+    reporter.write_report_ndjson(&mut out, Some("Broken"), None).unwrap();
+    let only: serde_json::Value = serde_json::from_str(String::from_utf8(out).unwrap().trim_end()).unwrap();
+    assert_eq!(only["status"], "failing");
+
+    let mut out = Vec::new();
+    let query = ItemQuery { source: Some("Lobsters".to_string()), ..Default::default() };
+    assert_eq!(reporter.write_items_ndjson(&mut out, &query, false, 1000).unwrap(), 2);
+    let out = String::from_utf8(out).unwrap();
+    assert_eq!(out.lines().count(), 2);
+    for line in out.lines() {
+        let item: serde_json::Value = serde_json::from_str(line).unwrap();
+        assert_eq!(item["source"], "Lobsters");
+        assert!(item.get("sources").is_none());
+    }
+}
+
+#[test]
+fn test_items_ndjson_pages_match_unpaged_output() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = Storage::new(temp_dir.path().join("ndjson_pages.db")).unwrap();
+
+    // Tied and missing timestamps exercise the page cursor
+    for n in 0..7 {
+        let timestamp = (n < 5).then(|| format!("2024-01-0{}T00:00:00Z", 1 + n % 3));
+        let item = FeedItem::new(format!("Post {}", n), format!("https://example.com/{}", n), "Lobsters".to_string())
+            .with_timestamp(timestamp);
+        storage.store_item(&item).unwrap();
+    }
+    for (n, source) in [(1, "HackerNews"), (6, "HackerNews")] {
+        let item = FeedItem::new(format!("Post {}", n), format!("https://www.example.com/{}/", n), source.to_string());
+        storage.store_item(&item).unwrap();
+    }
+    let reporter = Reporter::new(storage);
+
+    let write = |query: &ItemQuery, dedup: bool, page_size: usize| {
+        let mut out = Vec::new();
+        let count = reporter.write_items_ndjson(&mut out, query, dedup, page_size).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.lines().count(), count);
+        out
+    };
+
+    let all = ItemQuery::default();
+    let unpaged = write(&all, false, 1000);
+    assert_eq!(unpaged.lines().count(), 9);
+    assert_eq!(write(&all, false, 2), unpaged);
+    assert_eq!(write(&all, true, 2), write(&all, true, 1000));
+
+    let deduped = write(&all, true, 3);
+    assert_eq!(deduped.lines().count(), 7);
+    let sources: Vec<serde_json::Value> = deduped.lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["sources"].clone())
+        .filter(|sources| sources.as_array().unwrap().len() == 2)
+        .collect();
+    assert_eq!(sources.len(), 2, "Both cross-posted URLs list their two sources");
+
+    let limited = ItemQuery { limit: Some(5), ..Default::default() };
+    assert_eq!(write(&limited, false, 2), unpaged.lines().take(5).map(|line| format!("{}\n", line)).collect::<String>());
+}
+
+#[test]
+fn test_rss_output_round_trips_through_parser() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = Storage::new(temp_dir.path().join("rss.db")).unwrap();
+
+    let mut first = FeedItem::new(
+        "Tom & Jerry <3 Rust".to_string(),
+        "https://example.com/a?x=1&y=2".to_string(),
+        "Lobsters".to_string(),
+    );
+    first.timestamp = Some("2024-07-25T12:00:00+00:00".to_string());
+    storage.store_item(&first).unwrap();
+    storage.store_item(&FeedItem::new("Second".to_string(), "https://example.org/b".to_string(), "HackerNews".to_string())).unwrap();
+    storage.store_item(&FeedItem::new("Third".to_string(), "https://example.org/c".to_string(), "HackerNews".to_string())).unwrap();
+
+    let reporter = Reporter::new(storage);
+    let rss = reporter.render_items("rss", &ItemQuery::default()).unwrap();
+    assert!(rss.starts_with("<?xml"));
+    assert!(rss.contains("<rss version=\"2.0\">"));
+
+    let parsed = Parser::parse("Republished", "rss", &rss).unwrap();
+    assert_eq!(parsed.len(), 3);
+    let tom = parsed.iter().find(|item| item.url == "https://example.com/a?x=1&y=2").unwrap();
+    assert_eq!(tom.title, "Tom & Jerry <3 Rust");
+    assert_eq!(tom.tags, vec!["Lobsters"]);
+    assert_eq!(tom.timestamp.as_deref(), Some("2024-07-25T12:00:00+00:00"));
+
+    let query = ItemQuery { source: Some("HackerNews".to_string()), limit: Some(1), ..Default::default() };
+    let scoped = reporter.render_items("rss", &query).unwrap();
+    assert_eq!(Parser::parse("Republished", "rss", &scoped).unwrap().len(), 1);
+}
+
+#[test]
+fn test_items_markdown_snapshot() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = Storage::new(temp_dir.path().join("markdown.db")).unwrap();
+
+    for (title, url, source, ts) in [
+        ("Async *closures*", "https://example.com/async", "Lobsters", Some("2024-07-25T12:00:00+00:00")),
+        ("Show HN: a thing", "https://example.com/hn", "HackerNews", None),
+        ("Tagged elsewhere", "https://example.com/other", "Reddit", Some("2024-07-24T08:30:00+00:00")),
+    ] {
+        let mut item = FeedItem::new(title.to_string(), url.to_string(), source.to_string());
+        item.timestamp = ts.map(str::to_string);
+        if source != "Reddit" {
+            item.tags = vec!["rust".to_string()];
+        }
+        storage.store_item(&item).unwrap();
+    }
+
+    let reporter = Reporter::new(storage);
+    let query = ItemQuery { tags: vec!["rust".to_string()], ..Default::default() };
+    assert_eq!(reporter.render_items("markdown", &query).unwrap(), "\
+# Feed Digest
+
+## HackerNews
+
+- [Show HN: a thing](https://example.com/hn)
+
+## Lobsters
+
+- [Async \\*closures\\*](https://example.com/async) (2024-07-25T12:00:00+00:00)
+");
+
+    // Nothing new since the cutoff: no source sections at all
+    let query = ItemQuery { since: Some("2999-01-01T00:00:00+00:00".to_string()), ..Default::default() };
+    assert_eq!(reporter.render_items("markdown", &query).unwrap(), "# Feed Digest\n\nNo new items.\n");
+}
+
+#[test]
+fn test_fetch_summary_json_shape() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = Storage::new(temp_dir.path().join("summary.db")).unwrap();
+
+    let mut results = vec![
+        FetchResult {
+            source: "Lobsters".to_string(),
+            items: vec![
+                FeedItem::new("One".to_string(), "https://example.com/1".to_string(), "Lobsters".to_string()),
+                FeedItem::new("Two".to_string(), "https://example.com/2".to_string(), "Lobsters".to_string()),
+            ],
+            duration_ms: 120,
+            parse_warnings: vec!["Lobsters item 2: missing url and comments_url".to_string()],
+            ..Default::default()
+        },
+        FetchResult {
+            source: "Broken".to_string(),
+            error: Some("HTTP 500 after 3 retries".to_string()),
+            duration_ms: 900,
+            ..Default::default()
+        },
+    ];
+    let outcome = storage.store_results(&mut results).unwrap();
+
+    let summary = fetch_summary_json(&results, Some(&outcome));
+    assert_eq!(summary, serde_json::json!({
+        "feeds": [
+            {"source": "Lobsters", "status": "ok", "item_count": 2, "skipped_count": 1, "new_count": 2,
+             "duration_ms": 120, "error": null, "warning": null},
+            {"source": "Broken", "status": "error", "item_count": 0, "skipped_count": 0, "new_count": 0,
+             "duration_ms": 900, "error": "HTTP 500 after 3 retries", "warning": null},
+        ],
+        "totals": {"feeds": 2, "succeeded": 1, "errors": 1, "items": 2, "skipped": 1, "new": 2, "updated": 0},
+        "dry_run": false,
+    }));
+
+    // A dry run doesn't know what is new
+    let dry = fetch_summary_json(&results, None);
+    assert_eq!(dry["dry_run"], true);
+    assert!(dry["feeds"][0]["new_count"].is_null());
+    assert!(dry["totals"]["new"].is_null());
+}
+
+#[test]
+fn test_skipped_items_shown_in_history_and_report() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = Storage::new(temp_dir.path().join("skipped.db")).unwrap();
+
+    let items = (0..48)
+        .map(|i| FeedItem::new(format!("Repo {}", i), format!("https://github.com/user/{}", i), "GitHub".to_string()))
+        .collect();
+    let mut results = vec![FetchResult {
+        source: "GitHub".to_string(),
+        items,
+        parse_warnings: vec![
+            "GitHub item 3: missing html_url".to_string(),
+            "GitHub item 9: missing full_name".to_string(),
+        ],
+        ..Default::default()
+    }];
+    storage.store_results(&mut results).unwrap();
+
+    let reporter = Reporter::new(storage).with_width(Some(200));
+    let history = reporter.render_history("table", None, 10).unwrap();
+    assert!(history.contains("48 (2 skipped)"), "{}", history);
+
+    let table = reporter.render_report("table", None, None).unwrap();
+    assert!(table.contains("1 (2 skipped)"), "{}", table);
+
+    let json: serde_json::Value = serde_json::from_str(&reporter.render_report("json", None, None).unwrap()).unwrap();
+    assert_eq!(json["sources"][0]["skipped_items"], 2);
+}
+
+#[test]
+fn test_degraded_fetch_recorded_and_reported() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = Storage::new(temp_dir.path().join("degraded.db")).unwrap();
+
+    let mut results = vec![
+        FetchResult {
+            source: "HackerNews".to_string(),
+            items: vec![FeedItem::new("Only".to_string(), "https://example.com/1".to_string(), "HackerNews".to_string())],
+            warning: Some("only 1 items, expected at least 100".to_string()),
+            ..Default::default()
+        },
+        FetchResult {
+            source: "Lobsters".to_string(),
+            items: vec![FeedItem::new("Fine".to_string(), "https://example.com/2".to_string(), "Lobsters".to_string())],
+            ..Default::default()
+        },
+    ];
+    assert_eq!(results[0].status(), FetchStatus::Degraded);
+    assert_eq!(results[1].status(), FetchStatus::Success);
+    storage.store_results(&mut results).unwrap();
+
+    let history = storage.get_fetch_history(Some("HackerNews"), 1).unwrap();
+    assert_eq!(history[0].status, "degraded");
+    assert_eq!(history[0].error_message.as_deref(), Some("only 1 items, expected at least 100"));
+
+    let reporter = Reporter::new(storage);
+    let report: serde_json::Value = serde_json::from_str(&reporter.render_report("json", None, None).unwrap()).unwrap();
+    let sources = report["sources"].as_array().unwrap();
+    assert_eq!(sources[0]["source"], "HackerNews");
+    assert_eq!(sources[0]["status"], "degraded");
+    assert_eq!(sources[0]["degraded_fetches"], 1);
+    assert_eq!(sources[0]["errors"], 0);
+    assert!(sources[0]["last_success"].is_string());
+    assert_eq!(sources[1]["status"], "healthy");
+
+    let csv = reporter.render_report("csv", None, None).unwrap();
+    let rows: Vec<&str> = csv.lines().collect();
+    assert!(rows[1].starts_with("HackerNews,1,0,0.0%,") && rows[1].ends_with(",degraded"), "{}", rows[1]);
+    assert!(rows[2].ends_with(",healthy"), "{}", rows[2]);
+}
@@ -0,0 +1,684 @@
+/// Tests for SQLite storage behavior
+use feedpulse::config::Config;
+use feedpulse::fetcher::FetchResult;
+use feedpulse::models::{canonical_url, FeedItem};
+use feedpulse::storage::{ItemQuery, Storage, StoreOutcome, SCHEMA_VERSION};
+use std::fs;
+use tempfile::{NamedTempFile, TempDir};
+
+fn load_config(yaml: &str) -> Config {
+    let temp_file = NamedTempFile::new().unwrap();
+    fs::write(&temp_file, yaml).unwrap();
+    Config::load(temp_file.path()).unwrap()
+}
+
+fn fetch_result(source: &str, items: Vec<FeedItem>) -> FetchResult {
+    FetchResult {
+        source: source.to_string(),
+        items,
+        duration_ms: 10,
+        ..Default::default()
+    }
+}
+
+fn tagged_item(source: &str, tags: &[&str]) -> FeedItem {
+    FeedItem::new(
+        "Tagged".to_string(),
+        "https://example.com/tagged".to_string(),
+        source.to_string(),
+    ).with_tags(tags.iter().map(|t| t.to_string()).collect())
+}
+
+#[test]
+fn test_merge_tags_unions_on_update() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = load_config(r#"
+feeds:
+  - name: "Merged"
+    url: "https://example.com/merged"
+    feed_type: json
+    merge_tags: true
+  - name: "Replaced"
+    url: "https://example.com/replaced"
+    feed_type: json
+"#);
+    let storage = Storage::new(temp_dir.path().join("tags.db")).unwrap().with_config(&config);
+
+    for source in ["Merged", "Replaced"] {
+        storage.store_results(&mut [fetch_result(source, vec![tagged_item(source, &["A", "B"])])]).unwrap();
+        storage.store_results(&mut [fetch_result(source, vec![tagged_item(source, &["B", "C"])])]).unwrap();
+    }
+
+    let merged = storage.get_items(Some("Merged"), None).unwrap();
+    assert_eq!(merged.len(), 1);
+    assert_eq!(merged[0].tags, vec!["A", "B", "C"]);
+
+    let replaced = storage.get_items(Some("Replaced"), None).unwrap();
+    assert_eq!(replaced.len(), 1);
+    assert_eq!(replaced[0].tags, vec!["B", "C"]);
+}
+
+#[test]
+fn test_dedupe_collapses_canonical_duplicates() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = Storage::new(temp_dir.path().join("dedupe.db")).unwrap();
+
+    let original = FeedItem::new(
+        "Original".to_string(),
+        "https://example.com/post/".to_string(),
+        "Old Name".to_string(),
+    ).with_tags(vec!["rust".to_string()]);
+    storage.store_item(&original).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(5));
+
+    let renamed = FeedItem::new(
+        "Original (renamed feed)".to_string(),
+        "https://www.example.com/post?utm_source=rss#comments".to_string(),
+        "New Name".to_string(),
+    )
+    .with_tags(vec!["rust".to_string(), "release".to_string()])
+    .with_timestamp(Some("2024-03-01T00:00:00+00:00".to_string()));
+    storage.store_item(&renamed).unwrap();
+
+    storage.store_item(&FeedItem::new(
+        "Unrelated".to_string(),
+        "https://example.com/other".to_string(),
+        "New Name".to_string(),
+    )).unwrap();
+
+    assert_eq!(storage.dedupe().unwrap(), 1);
+
+    let items = storage.get_items(None, None).unwrap();
+    assert_eq!(items.len(), 2);
+
+    let survivor = items.iter().find(|i| i.id == original.id).expect("earliest row survives");
+    assert_eq!(survivor.title, "Original");
+    assert_eq!(survivor.tags, vec!["rust", "release"]);
+    assert_eq!(survivor.timestamp.as_deref(), Some("2024-03-01T00:00:00+00:00"));
+
+    assert_eq!(storage.dedupe().unwrap(), 0, "Dedupe should be idempotent");
+}
+
+#[test]
+fn test_dedupe_uses_configured_tracking_params() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = load_config(r#"
+settings:
+  tracking_params: ["src"]
+feeds: []
+"#);
+    let storage = Storage::new(temp_dir.path().join("dedupe_params.db")).unwrap().with_config(&config);
+
+    for (url, source) in [
+        ("https://example.com/post?src=rss", "A"),
+        ("https://example.com/post?src=home", "B"),
+        ("https://example.com/post?utm_source=x", "C"),
+    ] {
+        storage.store_item(&FeedItem::new("Post".to_string(), url.to_string(), source.to_string())).unwrap();
+    }
+
+    assert_eq!(storage.dedupe().unwrap(), 1, "utm_* isn't configured, so only src is ignored");
+    assert_eq!(storage.get_items(None, None).unwrap().len(), 2);
+}
+
+#[test]
+fn test_canonical_url() {
+    let tracking: Vec<String> = vec!["utm_*".to_string(), "fbclid".to_string()];
+    assert_eq!(
+        canonical_url("https://WWW.Example.com/a/?b=2&utm_medium=x&a=1#frag", &tracking),
+        "https://example.com/a?a=1&b=2"
+    );
+    assert_eq!(canonical_url("https://example.com/", &tracking), "https://example.com/");
+    assert_eq!(canonical_url("not a url", &tracking), "not a url");
+    // Only the configured parameters are dropped; `ref` can identify content
+    assert_eq!(
+        canonical_url("https://example.com/compare?ref=main&fbclid=x", &tracking),
+        "https://example.com/compare?ref=main"
+    );
+}
+
+fn item(source: &str, slug: &str) -> FeedItem {
+    FeedItem::new(
+        slug.to_string(),
+        format!("https://example.com/{}", slug),
+        source.to_string(),
+    )
+}
+
+#[test]
+fn test_mirror_latest_removes_dropped_items_for_that_source_only() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = load_config(r#"
+feeds:
+  - name: "Top"
+    url: "https://example.com/top"
+    feed_type: json
+    mirror_latest: true
+  - name: "Archive"
+    url: "https://example.com/archive"
+    feed_type: json
+"#);
+    let storage = Storage::new(temp_dir.path().join("mirror.db")).unwrap().with_config(&config);
+
+    for source in ["Top", "Archive"] {
+        storage.store_results(&mut [fetch_result(source, vec![item(source, "a"), item(source, "b")])]).unwrap();
+        storage.store_results(&mut [fetch_result(source, vec![item(source, "b"), item(source, "c")])]).unwrap();
+    }
+
+    let mut top: Vec<_> = storage.get_items(Some("Top"), None).unwrap().into_iter().map(|i| i.title).collect();
+    top.sort();
+    assert_eq!(top, vec!["b", "c"]);
+    assert_eq!(storage.get_items(Some("Archive"), None).unwrap().len(), 3);
+
+    // A failed or empty fetch must not wipe the mirrored source
+    let mut failed = fetch_result("Top", Vec::new());
+    failed.error = Some("HTTP 500".to_string());
+    storage.store_results(&mut [failed, fetch_result("Top", Vec::new())]).unwrap();
+    assert_eq!(storage.get_items(Some("Top"), None).unwrap().len(), 2);
+
+    // Nor a degraded one (fewer than min_expected_items)
+    let mut truncated = fetch_result("Top", vec![item("Top", "d")]);
+    truncated.warning = Some("parsed 1 items, expected at least 100".to_string());
+    storage.store_results(&mut [truncated]).unwrap();
+    let mut top: Vec<_> = storage.get_items(Some("Top"), None).unwrap().into_iter().map(|i| i.title).collect();
+    top.sort();
+    assert_eq!(top, vec!["b", "c", "d"]);
+}
+
+#[test]
+fn test_per_feed_retention_prunes_each_source_separately() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("retention.db");
+    let config = load_config(r#"
+settings:
+  retention_days: 30
+feeds:
+  - name: "Firehose"
+    url: "https://example.com/firehose"
+    feed_type: json
+    retention_days: 2
+  - name: "Archive"
+    url: "https://example.com/archive"
+    feed_type: json
+    retention_days: 3650
+  - name: "Default"
+    url: "https://example.com/default"
+    feed_type: json
+"#);
+    let storage = Storage::new(&db_path).unwrap().with_config(&config);
+
+    for source in ["Firehose", "Archive", "Default"] {
+        storage.store_results(&mut [fetch_result(source, vec![item(source, "old"), item(source, "new")])]).unwrap();
+    }
+
+    // Age the "old" items by 10 days
+    let ten_days_ago = (chrono::Utc::now() - chrono::Duration::days(10)).to_rfc3339();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    conn.execute(
+        "UPDATE feed_items SET created_at = ?1 WHERE title = 'old'",
+        rusqlite::params![ten_days_ago],
+    ).unwrap();
+
+    let pruned = storage.prune_expired().unwrap();
+    assert_eq!(pruned, vec![("Firehose".to_string(), 1)]);
+
+    assert_eq!(storage.get_items(Some("Firehose"), None).unwrap().len(), 1);
+    assert_eq!(storage.get_items(Some("Archive"), None).unwrap().len(), 2);
+    assert_eq!(storage.get_items(Some("Default"), None).unwrap().len(), 2);
+}
+
+#[test]
+fn test_prune_older_than_cutoff() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("prune_cutoff.db");
+    let storage = Storage::new(&db_path).unwrap();
+
+    storage.store_results(&mut [fetch_result("Feed", vec![item("Feed", "old"), item("Feed", "new")])]).unwrap();
+    storage.store_results(&mut [fetch_result("Feed", vec![item("Feed", "new")])]).unwrap();
+
+    // Age the "old" item and the first fetch by 10 days
+    let ten_days_ago = (chrono::Utc::now() - chrono::Duration::days(10)).to_rfc3339();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    conn.execute("UPDATE feed_items SET created_at = ?1 WHERE title = 'old'", rusqlite::params![ten_days_ago]).unwrap();
+    conn.execute("UPDATE fetch_log SET fetched_at = ?1 WHERE id = 1", rusqlite::params![ten_days_ago]).unwrap();
+
+    let cutoff = (chrono::Utc::now() - chrono::Duration::days(7)).to_rfc3339();
+    assert_eq!(storage.prune(&cutoff, true).unwrap(), (1, 1));
+    assert_eq!(storage.get_items(None, None).unwrap().len(), 2, "A dry run deletes nothing");
+
+    assert_eq!(storage.prune(&cutoff, false).unwrap(), (1, 1));
+    let items = storage.get_items(None, None).unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].title, "new");
+    assert_eq!(storage.get_fetch_history(None, 10).unwrap().len(), 1);
+}
+
+#[test]
+fn test_prune_keeps_latest_fetch_and_failure_streak() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("prune_streak.db");
+    let storage = Storage::new(&db_path).unwrap();
+
+    let failed = |source: &str| {
+        let mut result = fetch_result(source, Vec::new());
+        result.error = Some("HTTP 503".to_string());
+        result
+    };
+    // Steady: two successes. Flaky: a success, then two failures
+    storage.store_results(&mut [fetch_result("Steady", Vec::new()), fetch_result("Flaky", Vec::new())]).unwrap();
+    storage.store_results(&mut [fetch_result("Steady", Vec::new()), failed("Flaky")]).unwrap();
+    storage.store_results(&mut [failed("Flaky")]).unwrap();
+
+    let cutoff = (chrono::Utc::now() + chrono::Duration::days(1)).to_rfc3339();
+    assert_eq!(storage.prune(&cutoff, false).unwrap().1, 2);
+
+    let steady = storage.get_fetch_history(Some("Steady"), 10).unwrap();
+    assert_eq!(steady.len(), 1, "The latest fetch is kept");
+    let flaky = storage.get_fetch_history(Some("Flaky"), 10).unwrap();
+    assert_eq!(flaky.len(), 2, "The failure streak is kept");
+    assert_eq!(storage.get_consecutive_failures().unwrap().get("Flaky"), Some(&2));
+}
+
+#[test]
+fn test_vacuum_shrinks_database_after_prune() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("vacuum.db");
+    let storage = Storage::new(&db_path).unwrap();
+
+    let items: Vec<FeedItem> = (0..500)
+        .map(|n| item("Feed", &format!("{} {}", n, "padding ".repeat(50))))
+        .collect();
+    storage.store_results(&mut [fetch_result("Feed", items)]).unwrap();
+
+    let future = (chrono::Utc::now() + chrono::Duration::days(1)).to_rfc3339();
+    storage.prune(&future, false).unwrap();
+    let before = fs::metadata(&db_path).unwrap().len();
+
+    storage.vacuum().unwrap();
+    assert!(fs::metadata(&db_path).unwrap().len() < before);
+}
+
+#[test]
+fn test_log_fetches_disabled_writes_no_fetch_log() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = load_config(r#"
+settings:
+  log_fetches: false
+feeds:
+  - name: "Quiet"
+    url: "https://example.com/quiet"
+    feed_type: json
+"#);
+    let storage = Storage::new(temp_dir.path().join("nolog.db")).unwrap().with_config(&config);
+
+    let mut failed = fetch_result("Quiet", Vec::new());
+    failed.error = Some("HTTP 500".to_string());
+    storage.store_results(&mut [fetch_result("Quiet", vec![item("Quiet", "a"), item("Quiet", "b")]), failed]).unwrap();
+
+    assert!(storage.get_fetch_history(None, 10).unwrap().is_empty());
+    assert_eq!(storage.get_items(Some("Quiet"), None).unwrap().len(), 2);
+
+    let stats = storage.get_source_stats(None, None).unwrap();
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].items, 2, "Falls back to stored item counts");
+    assert_eq!(stats[0].errors, None);
+
+    assert!(storage.get_fetched_sources().unwrap().contains("Quiet"), "Stored items count as fetched");
+
+    let report = feedpulse::reporter::Reporter::new(storage).render_report("csv", None, None).unwrap();
+    assert!(report.contains("Quiet,2,n/a,n/a,n/a"));
+}
+
+#[test]
+fn test_store_outcome_counts_new_updated_unchanged() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = Storage::new(temp_dir.path().join("outcome.db")).unwrap();
+
+    let first = storage.store_results(&mut [fetch_result("Feed", vec![item("Feed", "a"), item("Feed", "b")])]).unwrap();
+    assert_eq!(first.total, 2);
+    assert_eq!(first.new, 2);
+    assert_eq!(first.new_item_ids, vec![item("Feed", "a").id, item("Feed", "b").id]);
+
+    let mut retitled = item("Feed", "b");
+    retitled.title = "b (edited)".to_string();
+    let mut results = [fetch_result("Feed", vec![item("Feed", "a"), retitled, item("Feed", "c")])];
+    let second = storage.store_results(&mut results).unwrap();
+
+    assert_eq!(second, StoreOutcome {
+        total: 3,
+        new: 1,
+        updated: 1,
+        unchanged: 1,
+        new_item_ids: vec![item("Feed", "c").id],
+    });
+    assert_eq!(results[0].new_items, second.new, "Per-result count agrees with the outcome");
+}
+
+#[test]
+fn test_refetch_keeps_created_at_and_skips_unchanged_rows() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("hash.db");
+    let storage = Storage::new(&db_path).unwrap();
+    storage.store_results(&mut [fetch_result("Feed", vec![item("Feed", "a"), item("Feed", "b")])]).unwrap();
+
+    // Backdate, and clear one hash as a row stored before content_hash existed
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    conn.execute("UPDATE feed_items SET created_at = '2020-01-01T00:00:00+00:00', link_status = 'ok'", []).unwrap();
+    conn.execute("UPDATE feed_items SET content_hash = NULL WHERE title = 'a'", []).unwrap();
+
+    let mut retitled = item("Feed", "b");
+    retitled.title = "b (edited)".to_string();
+    let outcome = storage.store_results(&mut [fetch_result("Feed", vec![item("Feed", "a"), retitled])]).unwrap();
+    assert_eq!((outcome.new, outcome.updated, outcome.unchanged), (0, 1, 1));
+
+    let rows: Vec<(String, String, Option<String>)> = conn
+        .prepare("SELECT title, created_at, link_status FROM feed_items ORDER BY title").unwrap()
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?))).unwrap()
+        .map(Result::unwrap)
+        .collect();
+    for (title, created_at, link_status) in &rows {
+        assert_eq!(created_at, "2020-01-01T00:00:00+00:00", "{} was re-created", title);
+        assert_eq!(link_status.as_deref(), Some("ok"), "{} lost its link status", title);
+    }
+    assert_eq!(rows[1].0, "b (edited)");
+}
+
+#[test]
+fn test_aliases_fold_renamed_feed_history() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("aliases.db");
+
+    // Stored while the feed was still called "HN"
+    let before = Storage::new(&db_path).unwrap();
+    before.store_results(&mut [fetch_result("HN", vec![item("HN", "old")])]).unwrap();
+
+    let config = load_config(r#"
+feeds:
+  - name: "HackerNews Top"
+    url: "https://example.com/hn"
+    feed_type: json
+    aliases: ["HN"]
+"#);
+    config.validate().unwrap();
+    let storage = Storage::new(&db_path).unwrap().with_config(&config);
+    storage.store_results(&mut [fetch_result("HackerNews Top", vec![item("HackerNews Top", "new")])]).unwrap();
+
+    let items = storage.get_items(Some("HackerNews Top"), None).unwrap();
+    assert_eq!(items.len(), 2);
+    assert!(items.iter().all(|i| i.source == "HackerNews Top"));
+
+    let stats = storage.get_source_stats(None, None).unwrap();
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].source, "HackerNews Top");
+    assert_eq!(stats[0].items, 2, "Fetches under both names are aggregated");
+
+    let report = feedpulse::reporter::Reporter::new(storage).render_report("csv", None, None).unwrap();
+    assert!(report.contains("HackerNews Top,2,0,"));
+    assert!(!report.contains("\nHN,"));
+}
+
+#[test]
+fn test_stats_limit_counts_aliased_fetches_together() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("alias-limit.db");
+
+    // Three failures under the old name, then two successes under the new one
+    let before = Storage::new(&db_path).unwrap();
+    for _ in 0..3 {
+        let mut failed = fetch_result("HN", Vec::new());
+        failed.error = Some("HTTP 503".to_string());
+        before.store_results(&mut [failed]).unwrap();
+    }
+
+    let config = load_config(r#"
+feeds:
+  - name: "HackerNews Top"
+    url: "https://example.com/hn"
+    feed_type: json
+    aliases: ["HN"]
+"#);
+    let storage = Storage::new(&db_path).unwrap().with_config(&config);
+    for slug in ["a", "b"] {
+        storage.store_results(&mut [fetch_result("HackerNews Top", vec![item("HackerNews Top", slug)])]).unwrap();
+    }
+
+    let stats = storage.get_source_stats(Some(2), None).unwrap();
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].items, 2, "The limit applies after folding the alias");
+    assert_eq!(stats[0].errors, Some(0));
+
+    let stats = storage.get_source_stats(Some(4), None).unwrap();
+    assert_eq!((stats[0].items, stats[0].errors), (4, Some(2)));
+}
+
+#[test]
+fn test_query_items_filters_and_limits() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = Storage::new(temp_dir.path().join("query.db")).unwrap();
+
+    for (source, slug, timestamp) in [
+        ("Lobsters", "old", "2024-01-01T00:00:00+00:00"),
+        ("Lobsters", "new", "2024-03-01T00:00:00+00:00"),
+        ("HackerNews", "hn", "2024-02-01T00:00:00+00:00"),
+    ] {
+        let mut stored = item(source, slug);
+        stored.timestamp = Some(timestamp.to_string());
+        storage.store_item(&stored).unwrap();
+    }
+
+    let all = storage.query_items(&ItemQuery::default()).unwrap();
+    let titles: Vec<_> = all.iter().map(|i| i.title.as_str()).collect();
+    assert_eq!(titles, ["new", "hn", "old"]);
+
+    let newest = storage.query_items(&ItemQuery {
+        source: Some("Lobsters".to_string()),
+        limit: Some(1),
+        ..Default::default()
+    }).unwrap();
+    assert_eq!(newest.len(), 1);
+    assert_eq!(newest[0].title, "new");
+
+    let future = storage.query_items(&ItemQuery {
+        since: Some("2999-01-01T00:00:00+00:00".to_string()),
+        ..Default::default()
+    }).unwrap();
+    assert!(future.is_empty());
+}
+
+#[test]
+fn test_migrates_v1_database_without_data_loss() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("v1.db");
+
+    // The original schema, from before versioning and the later columns
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    conn.execute_batch(
+        "CREATE TABLE feed_items (
+            id TEXT PRIMARY KEY, title TEXT NOT NULL, url TEXT NOT NULL, source TEXT NOT NULL,
+            timestamp TEXT, tags TEXT, raw_data TEXT, created_at TEXT NOT NULL
+        );
+        CREATE TABLE fetch_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT, source TEXT NOT NULL, fetched_at TEXT NOT NULL,
+            status TEXT NOT NULL, items_count INTEGER DEFAULT 0, error_message TEXT, duration_ms INTEGER
+        );
+        INSERT INTO feed_items VALUES ('old-id', 'Old', 'https://example.com/old', 'Feed', NULL, '[]', NULL, '2020-01-01T00:00:00+00:00');
+        INSERT INTO fetch_log (source, fetched_at, status, items_count) VALUES ('Feed', '2020-01-01T00:00:00+00:00', 'success', 1);"
+    ).unwrap();
+    drop(conn);
+
+    let storage = Storage::new(&db_path).unwrap();
+    assert_eq!(storage.schema_version().unwrap(), SCHEMA_VERSION);
+
+    let items = storage.get_items(Some("Feed"), None).unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].title, "Old");
+    assert_eq!(storage.get_fetch_history(Some("Feed"), 10).unwrap().len(), 1);
+
+    // New columns and tables work on the migrated database
+    storage.store_results(&mut [fetch_result("Feed", vec![item("Feed", "new")])]).unwrap();
+    assert!(storage.get_fetch_cache().unwrap().is_empty());
+
+    // Reopening is a no-op
+    drop(storage);
+    let reopened = Storage::new(&db_path).unwrap();
+    assert_eq!(reopened.schema_version().unwrap(), SCHEMA_VERSION);
+    assert_eq!(reopened.get_items(Some("Feed"), None).unwrap().len(), 2);
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let rows: i64 = conn.query_row("SELECT COUNT(*) FROM schema_version", [], |row| row.get(0)).unwrap();
+    assert_eq!(rows, 1);
+}
+
+#[test]
+fn test_rejects_newer_schema_version() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("future.db");
+    drop(Storage::new(&db_path).unwrap());
+
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    conn.execute("UPDATE schema_version SET version = version + 1", []).unwrap();
+
+    let err = Storage::new(&db_path).err().unwrap();
+    assert!(err.contains("is newer than this feedpulse supports"), "{}", err);
+}
+
+#[test]
+fn test_open_read_only_reads_history_without_migrating() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("readonly.db");
+    let storage = Storage::new(&db_path).unwrap();
+    storage.store_results(&mut [fetch_result("Feed", vec![item("Feed", "a")])]).unwrap();
+    drop(storage);
+
+    let read_only = Storage::open_read_only(&db_path).unwrap();
+    assert!(read_only.get_fetched_sources().unwrap().contains("Feed"));
+    assert!(read_only.store_results(&mut [fetch_result("Feed", Vec::new())]).is_err());
+
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    conn.execute("UPDATE schema_version SET version = version - 1", []).unwrap();
+    let err = Storage::open_read_only(&db_path).err().unwrap();
+    assert!(err.contains("doesn't match this feedpulse"), "{}", err);
+    let version: i64 = conn.query_row("SELECT version FROM schema_version", [], |row| row.get(0)).unwrap();
+    assert_eq!(version as usize, SCHEMA_VERSION - 1, "Not migrated");
+
+    assert!(Storage::open_read_only(temp_dir.path().join("missing.db")).is_err());
+    assert!(!temp_dir.path().join("missing.db").exists());
+}
+
+#[test]
+fn test_search_titles() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("search.db");
+    let storage = Storage::new(&db_path).unwrap();
+    assert!(storage.has_full_text_search().unwrap());
+
+    let titled = |slug: &str, title: &str| {
+        let mut item = item("Feed", slug);
+        item.title = title.to_string();
+        item
+    };
+    storage.store_results(&mut [fetch_result("Feed", vec![
+        titled("a", "Async Rust in practice"),
+        titled("b", "Rust 1.80 released"),
+        titled("c", "Writing a Python async runtime"),
+        titled("d", "What's new in C++26?"),
+    ])]).unwrap();
+
+    let titles = |query: &str| -> Vec<String> {
+        let mut titles: Vec<String> = storage.search(query, 10).unwrap().into_iter().map(|i| i.title).collect();
+        titles.sort();
+        titles
+    };
+
+    assert_eq!(titles("rust"), vec!["Async Rust in practice", "Rust 1.80 released"]);
+    assert_eq!(titles("async rust"), vec!["Async Rust in practice"]);
+    assert_eq!(titles("rust async"), vec!["Async Rust in practice"]);
+    assert_eq!(titles("C++26?"), vec!["What's new in C++26?"]);
+    assert!(titles("golang").is_empty());
+    assert_eq!(storage.search("async", 1).unwrap().len(), 1);
+
+    // Edits and deletes reach the index
+    storage.store_results(&mut [fetch_result("Feed", vec![titled("b", "Rust 1.81 released")])]).unwrap();
+    assert_eq!(titles("1.81"), vec!["Rust 1.81 released"]);
+    assert!(titles("1.80").is_empty());
+
+    let cutoff = (chrono::Utc::now() + chrono::Duration::days(1)).to_rfc3339();
+    storage.prune(&cutoff, false).unwrap();
+    storage.vacuum().unwrap();
+    assert!(titles("rust").is_empty());
+}
+
+#[test]
+fn test_query_items_by_tags() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = Storage::new(temp_dir.path().join("tags.db")).unwrap();
+
+    let tagged = |slug: &str, tags: &[&str]| item("Feed", slug)
+        .with_tags(tags.iter().map(|t| t.to_string()).collect());
+    storage.store_results(&mut [fetch_result("Feed", vec![
+        tagged("rust-async", &["rust", "async"]),
+        tagged("rust-only", &["rust"]),
+        tagged("python-async", &["python", "async"]),
+        tagged("untagged", &[]),
+        // A tag that merely contains another one must not match it
+        tagged("rustacean", &["rustacean"]),
+    ])]).unwrap();
+
+    let titles = |tags: &[&str]| -> Vec<String> {
+        let mut titles: Vec<String> = storage.query_items(&ItemQuery {
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..Default::default()
+        }).unwrap().into_iter().map(|i| i.title).collect();
+        titles.sort();
+        titles
+    };
+
+    assert_eq!(titles(&["rust"]), vec!["rust-async", "rust-only"]);
+    assert_eq!(titles(&["async"]), vec!["python-async", "rust-async"]);
+    // Overlapping sets AND together
+    assert_eq!(titles(&["rust", "async"]), vec!["rust-async"]);
+    // Disjoint sets match nothing
+    assert!(titles(&["rust", "python"]).is_empty());
+    assert_eq!(titles(&[]).len(), 5);
+}
+
+#[test]
+fn test_consecutive_failures_and_open_circuits() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = load_config("settings:\n  circuit_break_threshold: 3\nfeeds: []\n");
+    let storage = Storage::new(temp_dir.path().join("circuits.db")).unwrap().with_config(&config);
+
+    let failed = |source: &str| FetchResult {
+        source: source.to_string(),
+        error: Some("HTTP 503 after 3 retries".to_string()),
+        ..Default::default()
+    };
+    let parse_failed = |source: &str| FetchResult { parse_failed: true, ..failed(source) };
+    let runs = vec![
+        vec![fetch_result("Recovering", vec![]), failed("Flaky"), failed("Dead"), failed("Recovered")],
+        vec![failed("Recovering"), fetch_result("Flaky", vec![]), parse_failed("Dead"), failed("Recovered")],
+        vec![failed("Recovering"), failed("Flaky"), failed("Dead"), failed("Recovered")],
+        vec![fetch_result("Recovered", vec![])],
+    ];
+    for mut run in runs {
+        storage.store_results(&mut run).unwrap();
+    }
+
+    let failures = storage.get_consecutive_failures().unwrap();
+    assert_eq!(failures.get("Recovering"), Some(&2));
+    assert_eq!(failures.get("Flaky"), Some(&1));
+    assert_eq!(failures.get("Dead"), Some(&3));
+    assert_eq!(failures.get("Recovered"), None);
+
+    let open = storage.get_open_circuits().unwrap();
+    assert_eq!(open.len(), 1);
+    assert_eq!(open.get("Dead"), Some(&3));
+
+    let stats = storage.get_source_stats(None, None).unwrap();
+    let dead = stats.iter().find(|s| s.source == "Dead").unwrap();
+    assert_eq!(dead.open_circuit, Some(3));
+    assert!(stats.iter().filter(|s| s.source != "Dead").all(|s| s.open_circuit.is_none()));
+
+    // Off without a threshold
+    let storage = Storage::new(temp_dir.path().join("circuits.db")).unwrap();
+    assert!(storage.get_open_circuits().unwrap().is_empty());
+}
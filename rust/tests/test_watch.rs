@@ -0,0 +1,122 @@
+/// Tests for watch-mode scheduling
+use feedpulse::config::Config;
+use feedpulse::watch::{self, ConfigWatcher, Schedule};
+use std::fs;
+use std::time::{Duration, Instant};
+
+fn feeds() -> Config {
+    serde_yaml::from_str(r#"
+feeds:
+  - name: "Fast"
+    url: "https://example.com/fast"
+    feed_type: json
+    refresh_interval_secs: 60
+  - name: "Slow"
+    url: "https://example.com/slow"
+    feed_type: rss
+    refresh_interval_secs: 300
+  - name: "Off"
+    url: "https://example.com/off"
+    feed_type: rss
+    enabled: false
+"#).unwrap()
+}
+
+#[test]
+fn test_every_enabled_feed_is_due_at_start() {
+    let start = Instant::now();
+    let schedule = Schedule::new(&feeds().feeds, start);
+    assert_eq!(schedule.due(start), vec!["Fast", "Slow"]);
+    assert_eq!(schedule.next_wakeup(), Some(start));
+}
+
+#[test]
+fn test_feeds_fire_on_their_own_intervals() {
+    let start = Instant::now();
+    let mut schedule = Schedule::new(&feeds().feeds, start);
+    schedule.mark_fetched("Fast", start);
+    schedule.mark_fetched("Slow", start);
+
+    assert!(schedule.due(start + Duration::from_secs(59)).is_empty());
+    assert_eq!(schedule.next_wakeup(), Some(start + Duration::from_secs(60)));
+
+    let minute = start + Duration::from_secs(60);
+    assert_eq!(schedule.due(minute), vec!["Fast"]);
+    schedule.mark_fetched("Fast", minute);
+
+    // Slow comes due at 5 minutes; Fast is due again by then too
+    let five = start + Duration::from_secs(300);
+    assert_eq!(schedule.due(five), vec!["Fast", "Slow"]);
+    assert!(schedule.due(start + Duration::from_secs(119)).is_empty());
+}
+
+#[test]
+fn test_in_flight_feeds_are_not_due() {
+    let start = Instant::now();
+    let mut schedule = Schedule::new(&feeds().feeds, start);
+    schedule.mark_started("Fast");
+    schedule.mark_fetched("Slow", start);
+
+    // Fast is overdue but still fetching
+    assert_eq!(schedule.due(start + Duration::from_secs(600)), vec!["Slow"]);
+    assert_eq!(schedule.next_wakeup(), Some(start + Duration::from_secs(300)));
+
+    schedule.mark_fetched("Fast", start);
+    assert_eq!(schedule.next_wakeup(), Some(start + Duration::from_secs(60)));
+}
+
+#[test]
+fn test_no_enabled_feeds_has_no_wakeup() {
+    let mut config = feeds();
+    config.feeds.retain(|feed| !feed.enabled);
+    assert_eq!(Schedule::new(&config.feeds, Instant::now()).next_wakeup(), None);
+}
+
+#[test]
+fn test_update_adds_and_drops_feeds() {
+    let start = Instant::now();
+    let mut schedule = Schedule::new(&feeds().feeds, start);
+    schedule.mark_fetched("Fast", start);
+    schedule.mark_started("Slow");
+
+    let mut config = feeds();
+    config.feeds.retain(|feed| feed.name != "Fast");
+    for feed in &mut config.feeds {
+        feed.enabled = true;
+    }
+    let later = start + Duration::from_secs(10);
+    schedule.update(&config.feeds, later);
+
+    // Off is newly enabled; Slow is still in flight
+    assert_eq!(schedule.due(later), vec!["Off"]);
+    schedule.mark_fetched("Slow", start);
+    schedule.mark_fetched("Off", later);
+    assert_eq!(schedule.next_wakeup(), Some(start + Duration::from_secs(300)));
+    assert!(schedule.due(start + Duration::from_secs(3600)).iter().all(|name| name != "Fast"));
+}
+
+#[tokio::test]
+async fn test_config_reload_picks_up_changes_and_rejects_invalid_config() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("config.yaml");
+    let feed = |name: &str| format!(
+        "  - name: \"{0}\"\n    url: \"https://example.com/{0}\"\n    feed_type: json\n", name
+    );
+    fs::write(&path, format!("feeds:\n{}", feed("First"))).unwrap();
+
+    let config = watch::load_config(&path).unwrap();
+    let start = Instant::now();
+    let mut schedule = Schedule::new(&config.feeds, start);
+    schedule.mark_fetched("First", start);
+    let mut watcher = ConfigWatcher::new(&path).unwrap();
+
+    fs::write(&path, format!("feeds:\n{}{}", feed("First"), feed("Second"))).unwrap();
+    tokio::time::timeout(Duration::from_secs(5), watcher.changed()).await.expect("change not noticed");
+    let reloaded = watch::load_config(&path).unwrap();
+    schedule.update(&reloaded.feeds, start);
+    assert_eq!(schedule.due(start), vec!["Second"]);
+
+    fs::write(&path, "feeds:\n  - name: \"Broken\"\n    url: \"not a url\"\n    feed_type: json\n").unwrap();
+    tokio::time::timeout(Duration::from_secs(5), watcher.changed()).await.expect("change not noticed");
+    assert!(watch::load_config(&path).is_err());
+}